@@ -0,0 +1,967 @@
+//! `fee-routing`: operator CLI for initializing, inspecting, cranking,
+//! simulating and administering the fee-routing program.
+
+mod output;
+mod status;
+
+use std::{path::PathBuf, str::FromStr};
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::Instruction,
+        message::{Message, VersionedMessage},
+    },
+};
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use fee_routing_client::{
+    accounts::{decode_pending_policy_change, decode_policy},
+    instructions::{
+        self, AllocationMode, CapExcessMode, FeeShareStep, InitializePositionAccounts, LockedMode,
+        PolicyParams, PolicyUpdate, RoundingMode, TransferPositionToCreatorAccounts, Y0Mode,
+    },
+    pda,
+};
+use fee_routing_keeper::{
+    config::KeeperConfig,
+    keeper::{Keeper, TickOutcome},
+    rpc::KeeperRpc,
+};
+use fee_routing_simulator::{simulate, CsvTable, SimulationInput};
+use serde::Serialize;
+use solana_commitment_config::CommitmentConfig;
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_transaction::versioned::VersionedTransaction;
+
+use output::{OutputFormat, TxReport};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "fee-routing",
+    version,
+    about = "Operator CLI for the fee-routing program"
+)]
+struct Cli {
+    /// RPC endpoint
+    #[arg(
+        long,
+        short = 'u',
+        global = true,
+        env = "FEE_ROUTING_RPC_URL",
+        default_value = "http://127.0.0.1:8899"
+    )]
+    url: String,
+
+    /// Signer and fee payer keypair [default: ~/.config/solana/id.json]
+    #[arg(long, short = 'k', global = true, env = "FEE_ROUTING_KEYPAIR")]
+    keypair: Option<PathBuf>,
+
+    #[arg(long, global = true, default_value = "confirmed")]
+    commitment: String,
+
+    #[arg(long, short = 'o', global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Create the Policy account
+    InitPolicy(InitPolicyArgs),
+    /// Create the Progress account
+    InitProgress,
+    /// Create the DistributionHistory account
+    InitHistory,
+    /// Create the InvestorLedger account of each stream, in one transaction
+    /// signed by the policy authority
+    InitInvestorLedger {
+        /// Streamflow contract of an investor
+        #[arg(long = "stream", required = true, value_parser = parse_pubkey)]
+        streams: Vec<Pubkey>,
+    },
+    /// Create the honorary quote-only position
+    InitPosition(InitPositionArgs),
+    /// Show decoded Policy/Progress, the next window and treasury balances
+    Status {
+        /// Also show the treasury balance for this mint (e.g. the base token)
+        #[arg(long = "mint", value_parser = parse_pubkey)]
+        mints: Vec<Pubkey>,
+    },
+    /// Run whatever crank pages are due once, using a keeper config
+    Crank {
+        /// Keeper TOML config (see crates/fee-routing-keeper/keeper.example.toml)
+        #[arg(long, short)]
+        config: PathBuf,
+        /// Print the page plan without sending
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Replay a fee series against vesting schedules and a policy, offline
+    Simulate {
+        /// Simulation input, TOML or `.json` (see crates/fee-routing-simulator)
+        #[arg(long, short)]
+        input: PathBuf,
+        /// Print this table as CSV instead of the `--output` format
+        #[arg(long, value_enum)]
+        csv: Option<CsvArg>,
+    },
+    /// Apply a queued policy change whose delay has passed (anyone may run it)
+    ApplyPolicyChange { id: u64 },
+    /// Policy authority commands
+    #[command(subcommand)]
+    Admin(AdminCommand),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum CsvArg {
+    /// One row per distribution day
+    Days,
+    /// One row per investor per day
+    Payouts,
+    /// Per-investor totals
+    Investors,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum AllocationModeArg {
+    /// Locked fraction floored to whole bps
+    Legacy,
+    /// Single floor on `claimed * min(share, locked / y0)`
+    FullPrecision,
+}
+
+impl From<AllocationModeArg> for AllocationMode {
+    fn from(mode: AllocationModeArg) -> Self {
+        match mode {
+            AllocationModeArg::Legacy => AllocationMode::Legacy,
+            AllocationModeArg::FullPrecision => AllocationMode::FullPrecision,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum RoundingModeArg {
+    /// Floor every payout, leaving rounding dust in the treasury
+    Floor,
+    /// Hand the dust to the largest fractional remainders
+    LargestRemainder,
+}
+
+impl From<RoundingModeArg> for RoundingMode {
+    fn from(mode: RoundingModeArg) -> Self {
+        match mode {
+            RoundingModeArg::Floor => RoundingMode::Floor,
+            RoundingModeArg::LargestRemainder => RoundingMode::LargestRemainder,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum CapExcessModeArg {
+    /// Share payouts over the cap among the investors under it
+    Redistribute,
+    /// Pay payouts over the cap to the creator
+    Creator,
+}
+
+impl From<CapExcessModeArg> for CapExcessMode {
+    fn from(mode: CapExcessModeArg) -> Self {
+        match mode {
+            CapExcessModeArg::Redistribute => CapExcessMode::Redistribute,
+            CapExcessModeArg::Creator => CapExcessMode::Creator,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum LockedModeArg {
+    /// Read locked amounts when each page runs
+    Instant,
+    /// Average locked amounts over the UTC day the distribution day opened in
+    WindowAverage,
+}
+
+impl From<LockedModeArg> for LockedMode {
+    fn from(mode: LockedModeArg) -> Self {
+        match mode {
+            LockedModeArg::Instant => LockedMode::Instant,
+            LockedModeArg::WindowAverage => LockedMode::WindowAverage,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Y0ModeArg {
+    /// The policy's Y0, changed with `admin update-y0`
+    Fixed,
+    /// The sum of every registered stream's deposit
+    StreamDeposits,
+}
+
+impl From<Y0ModeArg> for Y0Mode {
+    fn from(mode: Y0ModeArg) -> Self {
+        match mode {
+            Y0ModeArg::Fixed => Y0Mode::Fixed,
+            Y0ModeArg::StreamDeposits => Y0Mode::StreamDeposits,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+struct InitPolicyArgs {
+    #[arg(long)]
+    y0: u64,
+    #[arg(long)]
+    investor_fee_share_bps: u16,
+    /// 0 disables the cap
+    #[arg(long, default_value_t = 0)]
+    daily_cap_lamports: u64,
+    #[arg(long, default_value_t = 0)]
+    min_payout_lamports: u64,
+    #[arg(long, value_parser = parse_pubkey)]
+    quote_mint: Pubkey,
+    #[arg(long, value_parser = parse_pubkey)]
+    creator_wallet: Pubkey,
+}
+
+#[derive(Args, Debug)]
+struct InitPositionArgs {
+    #[arg(long, value_parser = parse_pubkey)]
+    vault: Pubkey,
+    #[arg(long, value_parser = parse_pubkey)]
+    pool: Pubkey,
+    #[arg(long, value_parser = parse_pubkey)]
+    quote_mint: Pubkey,
+    /// Keypair for the position NFT mint; a fresh one is generated if omitted
+    #[arg(long)]
+    position_nft_mint: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum AdminCommand {
+    /// Change policy parameters (only between distribution days)
+    UpdatePolicy(UpdatePolicyArgs),
+    /// Set the fixed Y0 after TGE, e.g. for a top-up or a new round
+    UpdateY0 { y0: u64 },
+    /// Queue a policy change behind the policy's timelock delay
+    QueuePolicyChange(UpdatePolicyArgs),
+    /// Drop a queued policy change before its delay ends
+    CancelPolicyChange { id: u64 },
+    /// Hand policy administration to a new authority
+    SetAuthority {
+        #[arg(value_parser = parse_pubkey)]
+        new_authority: Pubkey,
+    },
+    /// Grow Policy and Progress written by an earlier release to the current
+    /// layout, between distribution days
+    MigrateAccounts,
+    /// Give the honorary position NFT to the creator once distribution has
+    /// sunset; the creator's Token-2022 ATA for the NFT must exist
+    TransferPositionToCreator {
+        #[arg(long, value_parser = parse_pubkey)]
+        vault: Pubkey,
+        #[arg(long, value_parser = parse_pubkey)]
+        position_nft_mint: Pubkey,
+    },
+}
+
+#[derive(Args, Debug)]
+struct UpdatePolicyArgs {
+    #[arg(long)]
+    y0: Option<u64>,
+    #[arg(long)]
+    investor_fee_share_bps: Option<u16>,
+    #[arg(long)]
+    daily_cap_lamports: Option<u64>,
+    #[arg(long)]
+    min_payout_lamports: Option<u64>,
+    #[arg(long, value_parser = parse_pubkey)]
+    creator_wallet: Option<Pubkey>,
+    #[arg(long, value_enum)]
+    allocation_mode: Option<AllocationModeArg>,
+    #[arg(long, value_enum)]
+    rounding_mode: Option<RoundingModeArg>,
+    /// Per-investor cap in bps of the day's investor allocation, owed
+    /// balances included; 0 removes it
+    #[arg(long)]
+    max_investor_payout_bps: Option<u16>,
+    /// Per-investor cap in lamports; 0 removes it
+    #[arg(long)]
+    max_investor_payout_lamports: Option<u64>,
+    #[arg(long, value_enum)]
+    cap_excess_mode: Option<CapExcessModeArg>,
+    #[arg(long, value_enum)]
+    locked_mode: Option<LockedModeArg>,
+    #[arg(long, value_enum)]
+    y0_mode: Option<Y0ModeArg>,
+    /// Replace the fee share schedule: comma-separated START_TS:BPS steps,
+    /// or "" to clear it
+    #[arg(long, value_parser = parse_fee_share_schedule)]
+    fee_share_schedule: Option<FeeShareSchedule>,
+    /// Delay before queued changes may be applied: 0 (no timelock) or at
+    /// least 86400, and a set delay can't go back to 0
+    #[arg(long)]
+    policy_change_delay_seconds: Option<i64>,
+    /// Unix time distribution sunsets at; 0 removes it
+    #[arg(long)]
+    end_ts: Option<i64>,
+}
+
+impl From<UpdatePolicyArgs> for PolicyUpdate {
+    fn from(args: UpdatePolicyArgs) -> Self {
+        PolicyUpdate {
+            y0: args.y0,
+            investor_fee_share_bps: args.investor_fee_share_bps,
+            daily_cap_lamports: args.daily_cap_lamports,
+            min_payout_lamports: args.min_payout_lamports,
+            creator_wallet: args.creator_wallet,
+            authority: None,
+            allocation_mode: args.allocation_mode.map(Into::into),
+            rounding_mode: args.rounding_mode.map(Into::into),
+            max_investor_payout_bps: args.max_investor_payout_bps,
+            max_investor_payout_lamports: args.max_investor_payout_lamports,
+            cap_excess_mode: args.cap_excess_mode.map(Into::into),
+            locked_mode: args.locked_mode.map(Into::into),
+            y0_mode: args.y0_mode.map(Into::into),
+            fee_share_schedule: args.fee_share_schedule.map(|s| s.0),
+            policy_change_delay_seconds: args.policy_change_delay_seconds,
+            end_ts: args.end_ts,
+        }
+    }
+}
+
+/// `--fee-share-schedule` steps; a newtype so clap takes one value
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct FeeShareSchedule(Vec<FeeShareStep>);
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let commitment = CommitmentConfig::from_str(&cli.commitment)
+        .map_err(|_| anyhow!("unknown commitment {:?}", cli.commitment))?;
+    let rpc = RpcClient::new_with_commitment(cli.url.clone(), commitment);
+
+    match cli.command {
+        Command::InitPolicy(args) => {
+            let payer = load_keypair(&cli.keypair)?;
+            let ix = instructions::initialize_policy(
+                payer.pubkey(),
+                &PolicyParams {
+                    y0: args.y0,
+                    investor_fee_share_bps: args.investor_fee_share_bps,
+                    daily_cap_lamports: args.daily_cap_lamports,
+                    min_payout_lamports: args.min_payout_lamports,
+                    quote_mint: args.quote_mint,
+                    creator_wallet: args.creator_wallet,
+                },
+            );
+            let signature = send(&rpc, &payer, &[], &[ix])?;
+            output::print(
+                cli.output,
+                &TxReport {
+                    command: "init-policy",
+                    signature: signature.to_string(),
+                    accounts: vec![("policy", pda::policy().0.to_string())],
+                },
+            )
+        }
+        Command::InitProgress => {
+            let payer = load_keypair(&cli.keypair)?;
+            let signature = send(
+                &rpc,
+                &payer,
+                &[],
+                &[instructions::initialize_progress(payer.pubkey())],
+            )?;
+            output::print(
+                cli.output,
+                &TxReport {
+                    command: "init-progress",
+                    signature: signature.to_string(),
+                    accounts: vec![("progress", pda::progress().0.to_string())],
+                },
+            )
+        }
+        Command::InitHistory => {
+            let payer = load_keypair(&cli.keypair)?;
+            let signature = send(
+                &rpc,
+                &payer,
+                &[],
+                &[instructions::initialize_history(payer.pubkey())],
+            )?;
+            output::print(
+                cli.output,
+                &TxReport {
+                    command: "init-history",
+                    signature: signature.to_string(),
+                    accounts: vec![("history", pda::history().0.to_string())],
+                },
+            )
+        }
+        Command::InitInvestorLedger { streams } => {
+            let payer = load_keypair(&cli.keypair)?;
+            let ixs: Vec<_> = streams
+                .iter()
+                .map(|stream| {
+                    instructions::initialize_investor_ledger(
+                        payer.pubkey(),
+                        payer.pubkey(),
+                        *stream,
+                    )
+                })
+                .collect();
+            let signature = send(&rpc, &payer, &[], &ixs)?;
+            output::print(
+                cli.output,
+                &TxReport {
+                    command: "init-investor-ledger",
+                    signature: signature.to_string(),
+                    accounts: streams
+                        .iter()
+                        .map(|stream| {
+                            (
+                                "investor_ledger",
+                                pda::investor_ledger(stream).0.to_string(),
+                            )
+                        })
+                        .collect(),
+                },
+            )
+        }
+        Command::InitPosition(args) => {
+            let payer = load_keypair(&cli.keypair)?;
+            let nft_mint = match &args.position_nft_mint {
+                Some(path) => read_keypair_file(path)
+                    .map_err(|e| anyhow!("failed to read {}: {e}", path.display()))?,
+                None => Keypair::new(),
+            };
+            let accounts = InitializePositionAccounts {
+                authority: payer.pubkey(),
+                vault: args.vault,
+                position_nft_mint: nft_mint.pubkey(),
+                pool: args.pool,
+                quote_mint: args.quote_mint,
+            };
+            let signature = send(
+                &rpc,
+                &payer,
+                &[&nft_mint],
+                &[instructions::initialize_position(&accounts)],
+            )?;
+            output::print(
+                cli.output,
+                &TxReport {
+                    command: "init-position",
+                    signature: signature.to_string(),
+                    accounts: vec![
+                        ("position_nft_mint", nft_mint.pubkey().to_string()),
+                        (
+                            "position",
+                            pda::cp_amm::position(&nft_mint.pubkey()).0.to_string(),
+                        ),
+                        (
+                            "position_nft_account",
+                            pda::cp_amm::position_nft_account(&nft_mint.pubkey())
+                                .0
+                                .to_string(),
+                        ),
+                        (
+                            "position_owner",
+                            pda::position_owner(&args.vault).0.to_string(),
+                        ),
+                    ],
+                },
+            )
+        }
+        Command::Status { mints } => output::print(cli.output, &status::status(&rpc, &mints)?),
+        Command::Crank { config, dry_run } => crank(&config, dry_run, cli.output),
+        Command::Simulate { input, csv } => {
+            let report = simulate(&SimulationInput::load(&input)?)?;
+            match csv {
+                Some(table) => {
+                    let table = match table {
+                        CsvArg::Days => CsvTable::Days,
+                        CsvArg::Payouts => CsvTable::Payouts,
+                        CsvArg::Investors => CsvTable::Investors,
+                    };
+                    Ok(report.write_csv(table, std::io::stdout().lock())?)
+                }
+                None => output::print(cli.output, &report),
+            }
+        }
+        Command::Admin(AdminCommand::UpdatePolicy(args)) => {
+            let update = PolicyUpdate::from(args);
+            if update == PolicyUpdate::default() {
+                bail!("nothing to update; pass at least one field");
+            }
+            admin(
+                &rpc,
+                &cli.keypair,
+                "admin update-policy",
+                cli.output,
+                |authority| instructions::update_policy(authority, &update),
+            )
+        }
+        Command::Admin(AdminCommand::UpdateY0 { y0 }) => admin(
+            &rpc,
+            &cli.keypair,
+            "admin update-y0",
+            cli.output,
+            |authority| instructions::update_y0(authority, y0),
+        ),
+        Command::Admin(AdminCommand::QueuePolicyChange(args)) => {
+            let update = PolicyUpdate::from(args);
+            if update == PolicyUpdate::default() {
+                bail!("nothing to queue; pass at least one field");
+            }
+            let id = read_account(&rpc, &pda::policy().0, decode_policy)?.next_policy_change_id;
+            admin(
+                &rpc,
+                &cli.keypair,
+                "admin queue-policy-change",
+                cli.output,
+                |authority| instructions::queue_policy_change(authority, id, &update),
+            )
+        }
+        Command::Admin(AdminCommand::CancelPolicyChange { id }) => {
+            let pending = read_account(
+                &rpc,
+                &pda::policy_change(id).0,
+                decode_pending_policy_change,
+            )?;
+            admin(
+                &rpc,
+                &cli.keypair,
+                "admin cancel-policy-change",
+                cli.output,
+                |authority| instructions::cancel_policy_change(authority, id, pending.queued_by),
+            )
+        }
+        Command::ApplyPolicyChange { id } => {
+            let pending = read_account(
+                &rpc,
+                &pda::policy_change(id).0,
+                decode_pending_policy_change,
+            )?;
+            let payer = load_keypair(&cli.keypair)?;
+            let ix = instructions::apply_policy_change(payer.pubkey(), id, pending.queued_by);
+            let signature = send(&rpc, &payer, &[], &[ix])?;
+            output::print(
+                cli.output,
+                &TxReport {
+                    command: "apply-policy-change",
+                    signature: signature.to_string(),
+                    accounts: vec![("policy", pda::policy().0.to_string())],
+                },
+            )
+        }
+        Command::Admin(AdminCommand::SetAuthority { new_authority }) => {
+            let update = PolicyUpdate {
+                authority: Some(new_authority),
+                ..Default::default()
+            };
+            admin(
+                &rpc,
+                &cli.keypair,
+                "admin set-authority",
+                cli.output,
+                |authority| instructions::update_policy(authority, &update),
+            )
+        }
+        Command::Admin(AdminCommand::MigrateAccounts) => admin(
+            &rpc,
+            &cli.keypair,
+            "admin migrate-accounts",
+            cli.output,
+            instructions::migrate_accounts,
+        ),
+        Command::Admin(AdminCommand::TransferPositionToCreator {
+            vault,
+            position_nft_mint,
+        }) => {
+            let policy = read_account(&rpc, &pda::policy().0, decode_policy)?;
+            let authority = load_keypair(&cli.keypair)?;
+            let accounts = TransferPositionToCreatorAccounts {
+                authority: authority.pubkey(),
+                vault,
+                position_nft_mint,
+                creator_wallet: policy.creator_wallet,
+            };
+            let ix = instructions::transfer_position_to_creator(&accounts);
+            let signature = send(&rpc, &authority, &[], &[ix])?;
+            output::print(
+                cli.output,
+                &TxReport {
+                    command: "admin transfer-position-to-creator",
+                    signature: signature.to_string(),
+                    accounts: vec![(
+                        "creator_nft_account",
+                        instructions::creator_position_nft_account(&accounts).to_string(),
+                    )],
+                },
+            )
+        }
+    }
+}
+
+fn admin(
+    rpc: &RpcClient,
+    keypair: &Option<PathBuf>,
+    command: &'static str,
+    format: OutputFormat,
+    instruction: impl FnOnce(Pubkey) -> Instruction,
+) -> Result<()> {
+    let authority = load_keypair(keypair)?;
+    let signature = send(rpc, &authority, &[], &[instruction(authority.pubkey())])?;
+    output::print(
+        format,
+        &TxReport {
+            command,
+            signature: signature.to_string(),
+            accounts: vec![("policy", pda::policy().0.to_string())],
+        },
+    )
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum CrankReport {
+    Planned {
+        pages: Vec<PlannedPageView>,
+    },
+    Waiting {
+        next_window_ts: i64,
+    },
+    Ran {
+        day: u64,
+        first_page: u16,
+        pages_planned: usize,
+        signatures: Vec<String>,
+        day_complete: bool,
+        claimed_quote: u64,
+        distributed_to_investors: u64,
+        carry_over: u64,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct PlannedPageView {
+    page_index: u16,
+    is_final_page: bool,
+    investors: usize,
+    compute_unit_limit: u32,
+    transaction_size: usize,
+    account_locks: usize,
+}
+
+impl std::fmt::Display for CrankReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CrankReport::Planned { pages } => {
+                writeln!(f, "{} page(s) planned", pages.len())?;
+                for p in pages {
+                    writeln!(
+                        f,
+                        "  page {}{}: {} investors, {} CU, {} bytes, {} accounts",
+                        p.page_index,
+                        if p.is_final_page { " (final)" } else { "" },
+                        p.investors,
+                        p.compute_unit_limit,
+                        p.transaction_size,
+                        p.account_locks
+                    )?;
+                }
+                Ok(())
+            }
+            CrankReport::Waiting { next_window_ts } => {
+                writeln!(f, "Nothing to do; next window opens at {next_window_ts}")
+            }
+            CrankReport::Ran {
+                day,
+                first_page,
+                signatures,
+                day_complete,
+                claimed_quote,
+                distributed_to_investors,
+                carry_over,
+                ..
+            } => {
+                writeln!(
+                    f,
+                    "Day {day}: sent {} page(s) starting at page {first_page}",
+                    signatures.len()
+                )?;
+                for signature in signatures {
+                    writeln!(f, "  {signature}")?;
+                }
+                writeln!(f, "  day complete:              {day_complete}")?;
+                writeln!(f, "  claimed quote:             {claimed_quote}")?;
+                writeln!(f, "  distributed to investors:  {distributed_to_investors}")?;
+                writeln!(f, "  carry over:                {carry_over}")
+            }
+        }
+    }
+}
+
+fn crank(config_path: &PathBuf, dry_run: bool, format: OutputFormat) -> Result<()> {
+    let config = KeeperConfig::load(config_path)?;
+    let payer = read_keypair_file(&config.keypair)
+        .map_err(|e| anyhow!("failed to read keypair {}: {e}", config.keypair))?;
+    let rpc = RpcClient::new_with_commitment(config.rpc_url.clone(), config.commitment_config()?);
+    let keeper = Keeper::from_config(rpc, payer, &config);
+
+    let report = if dry_run {
+        let (_, pages) = keeper.plan().context("planning pages")?;
+        CrankReport::Planned {
+            pages: pages
+                .iter()
+                .map(|p| PlannedPageView {
+                    page_index: p.page_index,
+                    is_final_page: p.is_final_page,
+                    investors: p.investors.len(),
+                    compute_unit_limit: p.compute_unit_limit,
+                    transaction_size: p.transaction_size,
+                    account_locks: p.account_locks,
+                })
+                .collect(),
+        }
+    } else {
+        match keeper.tick()? {
+            TickOutcome::Waiting { until } => CrankReport::Waiting {
+                next_window_ts: until,
+            },
+            TickOutcome::Ran(summary) => CrankReport::Ran {
+                day: summary.day,
+                first_page: summary.first_page,
+                pages_planned: summary.pages_planned,
+                signatures: summary.signatures.iter().map(ToString::to_string).collect(),
+                day_complete: summary.day_complete,
+                claimed_quote: summary.claimed_quote,
+                distributed_to_investors: summary.distributed_to_investors,
+                carry_over: summary.carry_over,
+            },
+        }
+    };
+    output::print(format, &report)
+}
+
+/// Sign with the payer plus any extra signers and wait for confirmation
+fn send(
+    rpc: &RpcClient,
+    payer: &Keypair,
+    extra_signers: &[&Keypair],
+    ixs: &[Instruction],
+) -> Result<Signature> {
+    let blockhash = KeeperRpc::get_latest_blockhash(rpc)?;
+    let message = Message::new_with_blockhash(ixs, Some(&payer.pubkey()), &blockhash);
+    let mut signers = vec![payer];
+    signers.extend_from_slice(extra_signers);
+    let tx = VersionedTransaction::try_new(VersionedMessage::Legacy(message), &signers)?;
+    Ok(rpc.send_and_confirm(&tx)?)
+}
+
+/// Fetch and decode an account that must exist
+fn read_account<T>(
+    rpc: &RpcClient,
+    key: &Pubkey,
+    decode: fn(&[u8]) -> anchor_lang::Result<T>,
+) -> Result<T> {
+    let data =
+        KeeperRpc::get_account_data(rpc, key)?.ok_or_else(|| anyhow!("account {key} not found"))?;
+    Ok(decode(&data)?)
+}
+
+fn load_keypair(path: &Option<PathBuf>) -> Result<Keypair> {
+    let path = match path {
+        Some(path) => path.clone(),
+        None => {
+            let home = std::env::var("HOME").context("HOME not set; pass --keypair")?;
+            PathBuf::from(home).join(".config/solana/id.json")
+        }
+    };
+    read_keypair_file(&path).map_err(|e| anyhow!("failed to read keypair {}: {e}", path.display()))
+}
+
+fn parse_pubkey(raw: &str) -> Result<Pubkey, String> {
+    Pubkey::from_str(raw).map_err(|e| format!("invalid pubkey {raw:?}: {e}"))
+}
+
+fn parse_fee_share_schedule(raw: &str) -> Result<FeeShareSchedule, String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|step| !step.is_empty())
+        .map(|step| {
+            let (start_ts, share_bps) = step
+                .split_once(':')
+                .ok_or_else(|| format!("invalid step {step:?}: expected START_TS:BPS"))?;
+            Ok(FeeShareStep {
+                start_ts: start_ts
+                    .parse()
+                    .map_err(|e| format!("invalid start_ts in {step:?}: {e}"))?,
+                share_bps: share_bps
+                    .parse()
+                    .map_err(|e| format!("invalid share_bps in {step:?}: {e}"))?,
+            })
+        })
+        .collect::<Result<_, String>>()
+        .map(FeeShareSchedule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from([
+            "fee-routing",
+            "admin",
+            "update-policy",
+            "--y0",
+            "5",
+            "-o",
+            "json",
+        ])
+        .unwrap();
+        assert_eq!(cli.output, OutputFormat::Json);
+        assert!(matches!(
+            cli.command,
+            Command::Admin(AdminCommand::UpdatePolicy(UpdatePolicyArgs {
+                y0: Some(5),
+                ..
+            }))
+        ));
+
+        let cli = Cli::try_parse_from([
+            "fee-routing",
+            "simulate",
+            "-i",
+            "sim.toml",
+            "--csv",
+            "payouts",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Simulate {
+                csv: Some(CsvArg::Payouts),
+                ..
+            }
+        ));
+
+        let stream = Pubkey::new_unique();
+        let cli = Cli::try_parse_from([
+            "fee-routing",
+            "init-investor-ledger",
+            "--stream",
+            &stream.to_string(),
+            "--stream",
+            &stream.to_string(),
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::InitInvestorLedger { streams } if streams == [stream, stream]
+        ));
+        assert!(Cli::try_parse_from(["fee-routing", "init-investor-ledger"]).is_err());
+
+        let cli = Cli::try_parse_from([
+            "fee-routing",
+            "admin",
+            "update-policy",
+            "--fee-share-schedule",
+            "1751328000:4000, 1767225600:2000",
+        ])
+        .unwrap();
+        let Command::Admin(AdminCommand::UpdatePolicy(args)) = cli.command else {
+            panic!("expected update-policy");
+        };
+        assert_eq!(
+            args.fee_share_schedule.unwrap().0,
+            [
+                FeeShareStep {
+                    start_ts: 1_751_328_000,
+                    share_bps: 4_000
+                },
+                FeeShareStep {
+                    start_ts: 1_767_225_600,
+                    share_bps: 2_000
+                },
+            ]
+        );
+        assert_eq!(
+            parse_fee_share_schedule(""),
+            Ok(FeeShareSchedule(Vec::new()))
+        );
+        assert!(parse_fee_share_schedule("1751328000").is_err());
+
+        let cli = Cli::try_parse_from(["fee-routing", "admin", "update-y0", "7000000"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Admin(AdminCommand::UpdateY0 { y0: 7_000_000 })
+        ));
+
+        let cli = Cli::try_parse_from([
+            "fee-routing",
+            "admin",
+            "queue-policy-change",
+            "--investor-fee-share-bps",
+            "3000",
+            "--policy-change-delay-seconds",
+            "172800",
+        ])
+        .unwrap();
+        let Command::Admin(AdminCommand::QueuePolicyChange(args)) = cli.command else {
+            panic!("expected queue-policy-change");
+        };
+        let update = PolicyUpdate::from(args);
+        assert_eq!(update.investor_fee_share_bps, Some(3_000));
+        assert_eq!(update.policy_change_delay_seconds, Some(172_800));
+
+        let cli = Cli::try_parse_from(["fee-routing", "apply-policy-change", "4"]).unwrap();
+        assert!(matches!(cli.command, Command::ApplyPolicyChange { id: 4 }));
+    }
+
+    #[test]
+    fn test_parse_sunset_commands() {
+        let cli = Cli::try_parse_from([
+            "fee-routing",
+            "admin",
+            "update-policy",
+            "--end-ts",
+            "1798761600",
+        ])
+        .unwrap();
+        let Command::Admin(AdminCommand::UpdatePolicy(args)) = cli.command else {
+            panic!("expected update-policy");
+        };
+        assert_eq!(PolicyUpdate::from(args).end_ts, Some(1_798_761_600));
+
+        let vault = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let cli = Cli::try_parse_from([
+            "fee-routing",
+            "admin",
+            "transfer-position-to-creator",
+            "--vault",
+            &vault.to_string(),
+            "--position-nft-mint",
+            &mint.to_string(),
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Admin(AdminCommand::TransferPositionToCreator { vault: v, position_nft_mint: m })
+                if v == vault && m == mint
+        ));
+    }
+}
@@ -0,0 +1,67 @@
+//! Text and JSON rendering for command results.
+
+use std::fmt;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// Print a command result in the selected format
+pub fn print<T: Serialize + fmt::Display>(format: OutputFormat, report: &T) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Text => print!("{report}"),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(report)?),
+    }
+    Ok(())
+}
+
+/// Result of any command that sends one transaction
+#[derive(Debug, Serialize)]
+pub struct TxReport {
+    pub command: &'static str,
+    pub signature: String,
+    /// Notable accounts created or touched, as (name, address)
+    pub accounts: Vec<(&'static str, String)>,
+}
+
+impl fmt::Display for TxReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: confirmed", self.command)?;
+        writeln!(f, "  signature: {}", self.signature)?;
+        for (name, address) in &self.accounts {
+            writeln!(f, "  {name}: {address}")?;
+        }
+        Ok(())
+    }
+}
+
+/// `3h 05m`-style rendering of a non-negative number of seconds
+pub fn duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    let (h, m, s) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    if h > 0 {
+        format!("{h}h {m:02}m")
+    } else if m > 0 {
+        format!("{m}m {s:02}s")
+    } else {
+        format!("{s}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(duration(-5), "0s");
+        assert_eq!(duration(42), "42s");
+        assert_eq!(duration(125), "2m 05s");
+        assert_eq!(duration(86_399), "23h 59m");
+    }
+}
@@ -0,0 +1,378 @@
+//! `fee-routing status`: decoded program state plus treasury balances.
+
+use std::fmt;
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use anchor_spl::{token, token_2022, token_interface::TokenAccount};
+use anyhow::Result;
+use fee_routing_client::{
+    accounts::{decode_history, decode_policy, decode_progress, next_window_ts},
+    instructions::{
+        treasury_token_account, AllocationMode, CapExcessMode, LockedMode, RoundingMode, Y0Mode,
+    },
+    pda, PROGRAM_ID,
+};
+use fee_routing_keeper::rpc::KeeperRpc;
+use serde::Serialize;
+
+use crate::output::duration;
+
+#[derive(Debug, Serialize)]
+pub struct StatusReport {
+    pub program_id: String,
+    pub cluster_time: i64,
+    pub policy: Option<PolicyView>,
+    pub progress: Option<ProgressView>,
+    pub treasury: TreasuryView,
+    pub history: Option<HistoryView>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PolicyView {
+    pub address: String,
+    pub authority: String,
+    pub y0: u64,
+    pub y0_mode: &'static str,
+    pub investor_fee_share_bps: u16,
+    pub fee_share_schedule: Vec<FeeShareStepView>,
+    /// Share a distribution day opening now would apply
+    pub current_fee_share_bps: u16,
+    pub daily_cap_lamports: u64,
+    pub min_payout_lamports: u64,
+    pub allocation_mode: &'static str,
+    pub rounding_mode: &'static str,
+    pub max_investor_payout_bps: u16,
+    pub max_investor_payout_lamports: u64,
+    pub cap_excess_mode: &'static str,
+    pub locked_mode: &'static str,
+    pub policy_change_delay_seconds: i64,
+    /// `PolicyUpdate::field_mask` bits covered by queued changes
+    pub pending_fields: u32,
+    pub next_policy_change_id: u64,
+    /// 0 when distribution only sunsets once the streams fully vest
+    pub end_ts: i64,
+    /// Days opening now pay every fee to the creator
+    pub sunset: bool,
+    pub quote_mint: String,
+    pub creator_wallet: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeeShareStepView {
+    pub start_ts: i64,
+    pub share_bps: u16,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProgressView {
+    pub address: String,
+    pub current_day: u64,
+    pub current_page: u16,
+    pub creator_payout_sent: bool,
+    pub last_distribution_ts: i64,
+    pub next_window_ts: i64,
+    pub window_open: bool,
+    pub day_claimed_quote: u64,
+    pub daily_distributed_to_investors: u64,
+    pub buckets: BucketsView,
+    pub total_rounding_dust: u64,
+    pub registered_deposits: u64,
+    pub registered_streams: u64,
+    /// What the registered streams had locked when last read
+    pub registered_locked: u64,
+    /// When a day first found every registered stream fully vested; 0 if never
+    pub fully_vested_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BucketsView {
+    pub investor_budget: u64,
+    pub creator_budget: u64,
+    pub cap_carry: u64,
+    pub owed: u64,
+    pub dust: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TreasuryView {
+    pub authority: String,
+    pub balances: Vec<TokenBalance>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenBalance {
+    pub mint: String,
+    pub account: String,
+    /// `None` when the treasury token account doesn't exist yet
+    pub amount: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistoryView {
+    pub address: String,
+    pub total_days_recorded: u64,
+    pub latest_day: Option<u64>,
+    pub latest_claimed_quote: Option<u64>,
+    pub latest_investor_total: Option<u64>,
+    pub latest_creator_total: Option<u64>,
+}
+
+/// Gather status; `extra_mints` adds treasury balances beyond the quote mint
+pub fn status(rpc: &impl KeeperRpc, extra_mints: &[Pubkey]) -> Result<StatusReport> {
+    let now = rpc.get_unix_timestamp()?;
+
+    let policy_key = pda::policy().0;
+    let policy = rpc
+        .get_account_data(&policy_key)?
+        .map(|data| decode_policy(&data))
+        .transpose()?;
+
+    let progress_key = pda::progress().0;
+    let progress = rpc
+        .get_account_data(&progress_key)?
+        .map(|data| decode_progress(&data))
+        .transpose()?;
+
+    let history_key = pda::history().0;
+    let history = rpc
+        .get_account_data(&history_key)?
+        .map(|data| decode_history(&data))
+        .transpose()?;
+
+    let mut mints: Vec<Pubkey> = policy.iter().map(|p| p.quote_mint).collect();
+    for mint in extra_mints {
+        if !mints.contains(mint) {
+            mints.push(*mint);
+        }
+    }
+    let balances = mints
+        .into_iter()
+        .map(|mint| treasury_balance(rpc, mint))
+        .collect::<Result<_>>()?;
+
+    let fully_vested_at = progress.as_ref().map_or(0, |p| p.fully_vested_at);
+
+    Ok(StatusReport {
+        program_id: PROGRAM_ID.to_string(),
+        cluster_time: now,
+        policy: policy.map(|p| PolicyView {
+            address: policy_key.to_string(),
+            authority: p.authority.to_string(),
+            y0: p.y0,
+            y0_mode: match p.y0_mode {
+                Y0Mode::Fixed => "fixed",
+                Y0Mode::StreamDeposits => "stream_deposits",
+            },
+            investor_fee_share_bps: p.investor_fee_share_bps,
+            fee_share_schedule: p
+                .fee_share_schedule
+                .iter()
+                .map(|step| FeeShareStepView {
+                    start_ts: step.start_ts,
+                    share_bps: step.share_bps,
+                })
+                .collect(),
+            current_fee_share_bps: p.day_fee_share_bps(fully_vested_at, now),
+            daily_cap_lamports: p.daily_cap_lamports,
+            min_payout_lamports: p.min_payout_lamports,
+            allocation_mode: match p.allocation_mode {
+                AllocationMode::Legacy => "legacy",
+                AllocationMode::FullPrecision => "full_precision",
+            },
+            rounding_mode: match p.rounding_mode {
+                RoundingMode::Floor => "floor",
+                RoundingMode::LargestRemainder => "largest_remainder",
+            },
+            max_investor_payout_bps: p.max_investor_payout_bps,
+            max_investor_payout_lamports: p.max_investor_payout_lamports,
+            cap_excess_mode: match p.cap_excess_mode {
+                CapExcessMode::Redistribute => "redistribute",
+                CapExcessMode::Creator => "creator",
+            },
+            locked_mode: match p.locked_mode {
+                LockedMode::Instant => "instant",
+                LockedMode::WindowAverage => "window_average",
+            },
+            policy_change_delay_seconds: p.policy_change_delay_seconds,
+            pending_fields: p.pending_fields,
+            next_policy_change_id: p.next_policy_change_id,
+            end_ts: p.end_ts,
+            sunset: p.sunset(fully_vested_at, now),
+            quote_mint: p.quote_mint.to_string(),
+            creator_wallet: p.creator_wallet.to_string(),
+        }),
+        progress: progress.map(|p| ProgressView {
+            address: progress_key.to_string(),
+            current_day: p.current_day,
+            current_page: p.current_page,
+            creator_payout_sent: p.creator_payout_sent,
+            last_distribution_ts: p.last_distribution_ts,
+            next_window_ts: next_window_ts(&p),
+            window_open: now >= next_window_ts(&p),
+            day_claimed_quote: p.day_claimed_quote,
+            daily_distributed_to_investors: p.daily_distributed_to_investors,
+            buckets: BucketsView {
+                investor_budget: p.buckets.investor_budget,
+                creator_budget: p.buckets.creator_budget,
+                cap_carry: p.buckets.cap_carry,
+                owed: p.buckets.owed,
+                dust: p.buckets.dust,
+            },
+            total_rounding_dust: p.total_rounding_dust,
+            registered_deposits: p.registered_deposits,
+            registered_streams: p.registered_streams,
+            registered_locked: p.registered_locked,
+            fully_vested_at: p.fully_vested_at,
+        }),
+        treasury: TreasuryView {
+            authority: pda::treasury_authority().0.to_string(),
+            balances,
+        },
+        history: history.map(|h| {
+            let latest = h.latest();
+            HistoryView {
+                address: history_key.to_string(),
+                total_days_recorded: h.total_days_recorded,
+                latest_day: latest.map(|r| r.day),
+                latest_claimed_quote: latest.map(|r| r.claimed_quote),
+                latest_investor_total: latest.map(|r| r.investor_total),
+                latest_creator_total: latest.map(|r| r.creator_total),
+            }
+        }),
+    })
+}
+
+/// Treasury ATA balance for `mint`, checking SPL Token first, then Token-2022
+fn treasury_balance(rpc: &impl KeeperRpc, mint: Pubkey) -> Result<TokenBalance> {
+    for program in [token::ID, token_2022::ID] {
+        let account = treasury_token_account(&mint, &program);
+        if let Some(data) = rpc.get_account_data(&account)? {
+            let parsed = TokenAccount::try_deserialize(&mut &data[..])?;
+            return Ok(TokenBalance {
+                mint: mint.to_string(),
+                account: account.to_string(),
+                amount: Some(parsed.amount),
+            });
+        }
+    }
+
+    Ok(TokenBalance {
+        mint: mint.to_string(),
+        account: treasury_token_account(&mint, &token::ID).to_string(),
+        amount: None,
+    })
+}
+
+impl fmt::Display for StatusReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Program  {}", self.program_id)?;
+        writeln!(f, "Cluster time  {}", self.cluster_time)?;
+
+        writeln!(f)?;
+        match &self.policy {
+            Some(p) => {
+                writeln!(f, "Policy  {}", p.address)?;
+                writeln!(f, "  authority               {}", p.authority)?;
+                writeln!(f, "  y0                      {} ({})", p.y0, p.y0_mode)?;
+                writeln!(f, "  investor_fee_share_bps  {}", p.investor_fee_share_bps)?;
+                for step in &p.fee_share_schedule {
+                    writeln!(f, "    from {:<19} {} bps", step.start_ts, step.share_bps)?;
+                }
+                writeln!(f, "  current_fee_share_bps   {}", p.current_fee_share_bps)?;
+                writeln!(f, "  daily_cap_lamports      {}", p.daily_cap_lamports)?;
+                writeln!(f, "  min_payout_lamports     {}", p.min_payout_lamports)?;
+                writeln!(f, "  allocation_mode         {}", p.allocation_mode)?;
+                writeln!(f, "  rounding_mode           {}", p.rounding_mode)?;
+                writeln!(
+                    f,
+                    "  investor_cap            {} bps  {} lamports  excess {}",
+                    p.max_investor_payout_bps, p.max_investor_payout_lamports, p.cap_excess_mode
+                )?;
+                writeln!(f, "  locked_mode             {}", p.locked_mode)?;
+                writeln!(
+                    f,
+                    "  policy_change_delay     {}s  pending fields {:#x}  next id {}",
+                    p.policy_change_delay_seconds, p.pending_fields, p.next_policy_change_id
+                )?;
+                writeln!(
+                    f,
+                    "  end_ts                  {}{}",
+                    p.end_ts,
+                    if p.sunset { "  (sunset)" } else { "" }
+                )?;
+                writeln!(f, "  quote_mint              {}", p.quote_mint)?;
+                writeln!(f, "  creator_wallet          {}", p.creator_wallet)?;
+            }
+            None => writeln!(f, "Policy  not initialized")?,
+        }
+
+        writeln!(f)?;
+        match &self.progress {
+            Some(p) => {
+                writeln!(f, "Progress  {}", p.address)?;
+                writeln!(f, "  current_day             {}", p.current_day)?;
+                writeln!(f, "  current_page            {}", p.current_page)?;
+                writeln!(f, "  creator_payout_sent     {}", p.creator_payout_sent)?;
+                writeln!(f, "  last_distribution_ts    {}", p.last_distribution_ts)?;
+                if p.window_open {
+                    writeln!(f, "  next window             open now")?;
+                } else {
+                    writeln!(
+                        f,
+                        "  next window             {} (in {})",
+                        p.next_window_ts,
+                        duration(p.next_window_ts - self.cluster_time)
+                    )?;
+                }
+                writeln!(f, "  day_claimed_quote       {}", p.day_claimed_quote)?;
+                writeln!(
+                    f,
+                    "  distributed_today       {}",
+                    p.daily_distributed_to_investors
+                )?;
+                let b = &p.buckets;
+                writeln!(
+                    f,
+                    "  budgets                 investors {}  creator {}",
+                    b.investor_budget, b.creator_budget
+                )?;
+                writeln!(
+                    f,
+                    "  carry                   cap {}  dust {}",
+                    b.cap_carry, b.dust
+                )?;
+                writeln!(f, "  owed_to_investors       {}", b.owed)?;
+                writeln!(f, "  total_rounding_dust     {}", p.total_rounding_dust)?;
+                writeln!(f, "  registered_deposits     {}", p.registered_deposits)?;
+                writeln!(f, "  registered_streams      {}", p.registered_streams)?;
+                writeln!(f, "  fully_vested_at         {}", p.fully_vested_at)?;
+            }
+            None => writeln!(f, "Progress  not initialized")?,
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Treasury  {}", self.treasury.authority)?;
+        for balance in &self.treasury.balances {
+            match balance.amount {
+                Some(amount) => writeln!(f, "  {}  {amount}", balance.mint)?,
+                None => writeln!(f, "  {}  (no account)", balance.mint)?,
+            }
+        }
+
+        if let Some(h) = &self.history {
+            writeln!(f)?;
+            writeln!(f, "History  {}", h.address)?;
+            writeln!(f, "  days_recorded           {}", h.total_days_recorded)?;
+            if let Some(day) = h.latest_day {
+                writeln!(
+                    f,
+                    "  latest day {day}: claimed {}, investors {}, creator {}",
+                    h.latest_claimed_quote.unwrap_or_default(),
+                    h.latest_investor_total.unwrap_or_default(),
+                    h.latest_creator_total.unwrap_or_default()
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
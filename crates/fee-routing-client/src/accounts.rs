@@ -0,0 +1,122 @@
+//! Decoders for fee-routing program accounts.
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, Result};
+use fee_routing::{constants::DISTRIBUTION_WINDOW_SECONDS, meteora::CpAmmAccount};
+
+pub use fee_routing::meteora::{Pool as CpAmmPool, Position as CpAmmPosition};
+
+pub use fee_routing::state::{
+    Buckets, DistributionHistory, DistributionRecord, InvestorLedger, PendingPolicyChange, Policy,
+    Progress,
+};
+
+/// Decode any Anchor account (checks the 8-byte discriminator)
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `Policy` account
+pub fn decode_policy(data: &[u8]) -> Result<Policy> {
+    decode(data)
+}
+
+/// Decode a `Progress` account
+pub fn decode_progress(data: &[u8]) -> Result<Progress> {
+    decode(data)
+}
+
+/// Decode a `DistributionHistory` account
+pub fn decode_history(data: &[u8]) -> Result<DistributionHistory> {
+    decode(data)
+}
+
+/// Decode a `PendingPolicyChange` account
+pub fn decode_pending_policy_change(data: &[u8]) -> Result<PendingPolicyChange> {
+    decode(data)
+}
+
+/// Decode an `InvestorLedger` account
+pub fn decode_investor_ledger(data: &[u8]) -> Result<InvestorLedger> {
+    decode(data)
+}
+
+/// Decode a CP-AMM `Pool` account (checks the owner and discriminator)
+pub fn decode_cp_amm_pool(owner: &Pubkey, data: &[u8]) -> Result<CpAmmPool> {
+    CpAmmPool::from_account_data(owner, data).copied()
+}
+
+/// Decode a CP-AMM `Position` account (checks the owner and discriminator)
+pub fn decode_cp_amm_position(owner: &Pubkey, data: &[u8]) -> Result<CpAmmPosition> {
+    CpAmmPosition::from_account_data(owner, data).copied()
+}
+
+/// Earliest unix timestamp at which page 0 of the next day may run
+pub fn next_window_ts(progress: &Progress) -> i64 {
+    progress
+        .last_distribution_ts
+        .saturating_add(DISTRIBUTION_WINDOW_SECONDS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AccountSerialize;
+    use fee_routing::meteora;
+
+    #[test]
+    fn test_decode_progress_roundtrip() {
+        let progress = Progress {
+            last_distribution_ts: 1_700_000_000,
+            current_day: 12,
+            daily_distributed_to_investors: 5_000,
+            current_page: 2,
+            pages_processed_today: 2,
+            total_investors: 0,
+            creator_payout_sent: false,
+            has_base_fees: false,
+            total_rounding_dust: 3,
+            day_claimed_quote: 10_000,
+            day_carry_over_in: 0,
+            day_total_locked: 1_000_000,
+            day_rounding_dust: 1,
+            buckets: Buckets {
+                cap_carry: 17,
+                ..Default::default()
+            },
+            day_owed_released: 0,
+            registered_deposits: 0,
+            registered_streams: 0,
+            day_streams_read: 0,
+            fully_vested_at: 0,
+            registered_locked: 0,
+            day_locked_basis: 0,
+            day_eligible_share_bps: 0,
+            day_investor_allocation: 0,
+            bump: 254,
+        };
+
+        let mut data = Vec::new();
+        progress.try_serialize(&mut data).unwrap();
+
+        let decoded = decode_progress(&data).unwrap();
+        assert_eq!(decoded.current_day, 12);
+        assert_eq!(decoded.day_total_locked, 1_000_000);
+        assert_eq!(decoded.buckets.carry_over().unwrap(), 17);
+        assert_eq!(next_window_ts(&decoded), 1_700_086_400);
+
+        // Wrong discriminator is rejected
+        assert!(decode_policy(&data).is_err());
+    }
+
+    #[test]
+    fn test_decode_cp_amm_position_checks_owner() {
+        let mut data = vec![0; meteora::POSITION_LEN];
+        data[..8].copy_from_slice(&meteora::POSITION_DISCRIMINATOR);
+        data[136..144].copy_from_slice(&42u64.to_le_bytes());
+
+        let position = decode_cp_amm_position(&meteora::cp_amm_program_id(), &data).unwrap();
+        assert_eq!({ position.fee_a_pending }, 42);
+        assert!(decode_cp_amm_position(&Pubkey::new_unique(), &data).is_err());
+        assert!(decode_cp_amm_pool(&meteora::cp_amm_program_id(), &data).is_err());
+    }
+}
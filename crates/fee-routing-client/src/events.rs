@@ -0,0 +1,294 @@
+//! Decoders for fee-routing events.
+//!
+//! By default the program emits events through a self-CPI (`emit_cpi!`), so
+//! they appear as inner instructions whose data is `EVENT_IX_TAG_LE` followed
+//! by the event discriminator and borsh body. Programs built with the
+//! `log-events` feature write the same discriminator + body base64-encoded in
+//! `Program data:` log lines, which only count inside the program's own
+//! invoke frame. Both forms decode to [`FeeRoutingEvent`].
+
+use anchor_lang::{event::EVENT_IX_TAG_LE, AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+pub use fee_routing::events::{
+    CreatorPayoutDayClosed, HonoraryPositionInitialized, InvestorLedgerInitialized, InvestorPayout,
+    InvestorPayoutPage, PolicyChangeApplied, PolicyChangeCancelled, PolicyChangeQueued,
+    PolicyUpdated, PositionTransferredToCreator, QuoteFeesClaimed, StreamsFullyVested, Y0Updated,
+};
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Any event emitted by the fee-routing program
+#[derive(Debug, Clone)]
+pub enum FeeRoutingEvent {
+    HonoraryPositionInitialized(HonoraryPositionInitialized),
+    QuoteFeesClaimed(QuoteFeesClaimed),
+    InvestorPayout(InvestorPayout),
+    InvestorPayoutPage(InvestorPayoutPage),
+    CreatorPayoutDayClosed(CreatorPayoutDayClosed),
+    PolicyUpdated(PolicyUpdated),
+    Y0Updated(Y0Updated),
+    InvestorLedgerInitialized(InvestorLedgerInitialized),
+    PolicyChangeQueued(PolicyChangeQueued),
+    PolicyChangeCancelled(PolicyChangeCancelled),
+    PolicyChangeApplied(PolicyChangeApplied),
+    StreamsFullyVested(StreamsFullyVested),
+    PositionTransferredToCreator(PositionTransferredToCreator),
+}
+
+fn try_decode<E: AnchorDeserialize + Discriminator>(data: &[u8]) -> Option<E> {
+    let body = data.strip_prefix(E::DISCRIMINATOR)?;
+    E::try_from_slice(body).ok()
+}
+
+impl FeeRoutingEvent {
+    /// Decode discriminator-prefixed event bytes
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if let Some(event) = try_decode(data) {
+            return Some(Self::HonoraryPositionInitialized(event));
+        }
+        if let Some(event) = try_decode(data) {
+            return Some(Self::QuoteFeesClaimed(event));
+        }
+        if let Some(event) = try_decode(data) {
+            return Some(Self::InvestorPayout(event));
+        }
+        if let Some(event) = try_decode(data) {
+            return Some(Self::InvestorPayoutPage(event));
+        }
+        if let Some(event) = try_decode(data) {
+            return Some(Self::CreatorPayoutDayClosed(event));
+        }
+        if let Some(event) = try_decode(data) {
+            return Some(Self::PolicyUpdated(event));
+        }
+        if let Some(event) = try_decode(data) {
+            return Some(Self::Y0Updated(event));
+        }
+        if let Some(event) = try_decode(data) {
+            return Some(Self::InvestorLedgerInitialized(event));
+        }
+        if let Some(event) = try_decode(data) {
+            return Some(Self::PolicyChangeQueued(event));
+        }
+        if let Some(event) = try_decode(data) {
+            return Some(Self::PolicyChangeCancelled(event));
+        }
+        if let Some(event) = try_decode(data) {
+            return Some(Self::PolicyChangeApplied(event));
+        }
+        if let Some(event) = try_decode(data) {
+            return Some(Self::StreamsFullyVested(event));
+        }
+        if let Some(event) = try_decode(data) {
+            return Some(Self::PositionTransferredToCreator(event));
+        }
+        None
+    }
+
+    /// Decode the instruction data of an event self-CPI
+    ///
+    /// Callers should only pass inner instructions whose program id is the
+    /// fee-routing program.
+    pub fn from_cpi_data(ix_data: &[u8]) -> Option<Self> {
+        Self::decode(ix_data.strip_prefix(EVENT_IX_TAG_LE)?)
+    }
+
+    /// Decode a single `Program data: <base64>` log line
+    pub fn from_log(line: &str) -> Option<Self> {
+        let encoded = line.strip_prefix(PROGRAM_DATA_PREFIX)?;
+        let data = STANDARD.decode(encoded.trim()).ok()?;
+        Self::decode(&data)
+    }
+}
+
+/// Decode every fee-routing event found in a transaction's log messages
+///
+/// Any program in the transaction can write `Program data:` lines, so only
+/// those logged while the fee-routing program is the innermost frame count.
+/// Frames follow the runtime's `Program <id> invoke [n]` and
+/// `Program <id> success` / `failed:` lines.
+pub fn decode_logs<S: AsRef<str>>(logs: &[S]) -> Vec<FeeRoutingEvent> {
+    let program_id = crate::PROGRAM_ID.to_string();
+    let mut frames: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs {
+        let line = line.as_ref();
+        if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(id), Some("invoke")) => {
+                    frames.push(id);
+                    continue;
+                }
+                (Some(_), Some("success" | "failed:")) => {
+                    frames.pop();
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        if frames.last().copied() == Some(program_id.as_str()) {
+            events.extend(FeeRoutingEvent::from_log(line));
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{prelude::Pubkey, Event};
+    use fee_routing::state::Policy;
+
+    fn payout() -> InvestorPayout {
+        InvestorPayout {
+            day: 4,
+            page_index: 1,
+            stream: Pubkey::new_unique(),
+            destination_ata: Pubkey::new_unique(),
+            locked_amount: 250,
+            weight_numerator: 250,
+            weight_denominator: 1_000,
+            page_distributable: 4_000,
+            payout: 1_000,
+            paid: true,
+            owed_released: 0,
+            owed: 0,
+            timestamp: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_decode_cpi_event() {
+        let event = payout();
+        let mut ix_data = EVENT_IX_TAG_LE.to_vec();
+        ix_data.extend(event.data());
+
+        match FeeRoutingEvent::from_cpi_data(&ix_data) {
+            Some(FeeRoutingEvent::InvestorPayout(decoded)) => {
+                assert_eq!(decoded.stream, event.stream);
+                assert_eq!(decoded.payout, 1_000);
+                assert!(decoded.paid);
+            }
+            other => panic!("unexpected decode result: {:?}", other),
+        }
+
+        // Event bytes without the CPI tag are not mistaken for a CPI event
+        assert!(FeeRoutingEvent::from_cpi_data(&event.data()).is_none());
+    }
+
+    #[test]
+    fn test_decode_log_event() {
+        let event = QuoteFeesClaimed {
+            amount: 42,
+            timestamp: 1_700_000_000,
+            distribution_day: 9,
+        };
+        let logs = vec![
+            format!("Program {} invoke [1]", crate::PROGRAM_ID),
+            "Program log: Instruction: DistributeFees".to_string(),
+            format!("Program data: {}", STANDARD.encode(event.data())),
+            format!("Program {} success", crate::PROGRAM_ID),
+        ];
+
+        let events = decode_logs(&logs);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0],
+            FeeRoutingEvent::QuoteFeesClaimed(e) if e.amount == 42 && e.distribution_day == 9
+        ));
+    }
+
+    #[test]
+    fn test_decode_logs_ignores_other_programs() {
+        let data = format!("Program data: {}", STANDARD.encode(payout().data()));
+        let other = Pubkey::new_unique();
+        let logs = vec![
+            // Another top-level instruction forging an event
+            format!("Program {other} invoke [1]"),
+            data.clone(),
+            format!("Program {other} success"),
+            // Outside any frame
+            data.clone(),
+            // Inside the fee-routing frame, but from a program it calls
+            format!("Program {} invoke [1]", crate::PROGRAM_ID),
+            format!("Program {other} invoke [2]"),
+            data.clone(),
+            format!("Program {other} failed: custom program error: 0x1"),
+            format!(
+                "Program {} consumed 5000 of 200000 compute units",
+                crate::PROGRAM_ID
+            ),
+            // The program's own event, back in its frame
+            data,
+            format!("Program {} success", crate::PROGRAM_ID),
+        ];
+
+        let events = decode_logs(&logs);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0], FeeRoutingEvent::InvestorPayout(_)));
+    }
+
+    #[test]
+    fn test_decode_policy_updated_end_ts() {
+        let policy = Policy {
+            y0: 10_000_000,
+            investor_fee_share_bps: 6_000,
+            daily_cap_lamports: 0,
+            min_payout_lamports: 1_000,
+            quote_mint: Pubkey::new_unique(),
+            creator_wallet: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            allocation_mode: Default::default(),
+            rounding_mode: Default::default(),
+            max_investor_payout_bps: 0,
+            max_investor_payout_lamports: 0,
+            cap_excess_mode: Default::default(),
+            locked_mode: Default::default(),
+            y0_mode: Default::default(),
+            fee_share_schedule: Vec::new(),
+            policy_change_delay_seconds: 0,
+            pending_fields: 0,
+            next_policy_change_id: 0,
+            end_ts: 1_767_225_600,
+            bump: 255,
+        };
+        let event = PolicyUpdated::new(policy.authority, &policy, 1_700_000_000);
+        let mut ix_data = EVENT_IX_TAG_LE.to_vec();
+        ix_data.extend(event.data());
+
+        match FeeRoutingEvent::from_cpi_data(&ix_data) {
+            Some(FeeRoutingEvent::PolicyUpdated(decoded)) => {
+                assert_eq!(decoded.end_ts, 1_767_225_600);
+                assert_eq!(decoded.timestamp, 1_700_000_000);
+            }
+            other => panic!("unexpected decode result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_investor_ledger_initialized() {
+        let event = InvestorLedgerInitialized {
+            stream: Pubkey::new_unique(),
+            recipient: Pubkey::new_unique(),
+            deposited: 2_000_000,
+            locked: 1_500_000,
+            registered_streams: 3,
+            registered_deposits: 12_000_000,
+            effective_y0: 12_000_000,
+            timestamp: 1_700_000_000,
+        };
+        let mut ix_data = EVENT_IX_TAG_LE.to_vec();
+        ix_data.extend(event.data());
+
+        match FeeRoutingEvent::from_cpi_data(&ix_data) {
+            Some(FeeRoutingEvent::InvestorLedgerInitialized(decoded)) => {
+                assert_eq!(decoded.stream, event.stream);
+                assert_eq!(decoded.recipient, event.recipient);
+                assert_eq!(decoded.effective_y0, 12_000_000);
+            }
+            other => panic!("unexpected decode result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,607 @@
+//! Typed instruction builders.
+//!
+//! Each builder fills in every PDA and well-known program so callers only pass
+//! the accounts that actually vary between deployments.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, sysvar},
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id, token, token_2022,
+};
+use fee_routing::meteora;
+
+use crate::pda;
+
+pub use fee_routing::{
+    instructions::PolicyUpdate,
+    state::{AllocationMode, CapExcessMode, FeeShareStep, LockedMode, RoundingMode, Y0Mode},
+};
+
+/// Arguments for `initialize_policy`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyParams {
+    pub y0: u64,
+    pub investor_fee_share_bps: u16,
+    pub daily_cap_lamports: u64,
+    pub min_payout_lamports: u64,
+    pub quote_mint: Pubkey,
+    pub creator_wallet: Pubkey,
+}
+
+/// Build `initialize_policy`
+pub fn initialize_policy(authority: Pubkey, params: &PolicyParams) -> Instruction {
+    let accounts = fee_routing::accounts::InitializePolicy {
+        authority,
+        policy: pda::policy().0,
+        system_program: system_program::ID,
+        event_authority: pda::event_authority().0,
+        program: fee_routing::ID,
+    };
+
+    let data = fee_routing::instruction::InitializePolicy {
+        y0: params.y0,
+        investor_fee_share_bps: params.investor_fee_share_bps,
+        daily_cap_lamports: params.daily_cap_lamports,
+        min_payout_lamports: params.min_payout_lamports,
+        quote_mint: params.quote_mint,
+        creator_wallet: params.creator_wallet,
+    };
+
+    Instruction {
+        program_id: fee_routing::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Build `initialize_progress`
+pub fn initialize_progress(authority: Pubkey) -> Instruction {
+    let accounts = fee_routing::accounts::InitializeProgress {
+        authority,
+        progress: pda::progress().0,
+        system_program: system_program::ID,
+        event_authority: pda::event_authority().0,
+        program: fee_routing::ID,
+    };
+
+    Instruction {
+        program_id: fee_routing::ID,
+        accounts: accounts.to_account_metas(None),
+        data: fee_routing::instruction::InitializeProgress {}.data(),
+    }
+}
+
+/// Build `initialize_history`
+pub fn initialize_history(authority: Pubkey) -> Instruction {
+    let accounts = fee_routing::accounts::InitializeHistory {
+        authority,
+        history: pda::history().0,
+        system_program: system_program::ID,
+        event_authority: pda::event_authority().0,
+        program: fee_routing::ID,
+    };
+
+    Instruction {
+        program_id: fee_routing::ID,
+        accounts: accounts.to_account_metas(None),
+        data: fee_routing::instruction::InitializeHistory {}.data(),
+    }
+}
+
+/// Build `update_policy`; only the `Some` fields of `update` change
+pub fn update_policy(authority: Pubkey, update: &PolicyUpdate) -> Instruction {
+    let accounts = fee_routing::accounts::UpdatePolicy {
+        authority,
+        policy: pda::policy().0,
+        progress: pda::progress().0,
+        event_authority: pda::event_authority().0,
+        program: fee_routing::ID,
+    };
+
+    Instruction {
+        program_id: fee_routing::ID,
+        accounts: accounts.to_account_metas(None),
+        data: fee_routing::instruction::UpdatePolicy {
+            update: update.clone(),
+        }
+        .data(),
+    }
+}
+
+/// Build `update_y0` setting the policy's fixed Y0
+pub fn update_y0(authority: Pubkey, y0: u64) -> Instruction {
+    let accounts = fee_routing::accounts::UpdateY0 {
+        authority,
+        policy: pda::policy().0,
+        progress: pda::progress().0,
+        event_authority: pda::event_authority().0,
+        program: fee_routing::ID,
+    };
+
+    Instruction {
+        program_id: fee_routing::ID,
+        accounts: accounts.to_account_metas(None),
+        data: fee_routing::instruction::UpdateY0 { y0 }.data(),
+    }
+}
+
+/// Build `queue_policy_change` creating pending change `id`, which must be
+/// the policy's current `next_policy_change_id`
+pub fn queue_policy_change(authority: Pubkey, id: u64, update: &PolicyUpdate) -> Instruction {
+    let accounts = fee_routing::accounts::QueuePolicyChange {
+        authority,
+        policy: pda::policy().0,
+        pending_change: pda::policy_change(id).0,
+        system_program: system_program::ID,
+        event_authority: pda::event_authority().0,
+        program: fee_routing::ID,
+    };
+
+    Instruction {
+        program_id: fee_routing::ID,
+        accounts: accounts.to_account_metas(None),
+        data: fee_routing::instruction::QueuePolicyChange {
+            update: update.clone(),
+        }
+        .data(),
+    }
+}
+
+/// Build `cancel_policy_change`; `queued_by` gets the pending change's rent back
+pub fn cancel_policy_change(authority: Pubkey, id: u64, queued_by: Pubkey) -> Instruction {
+    let accounts = fee_routing::accounts::CancelPolicyChange {
+        authority,
+        policy: pda::policy().0,
+        pending_change: pda::policy_change(id).0,
+        queued_by,
+        event_authority: pda::event_authority().0,
+        program: fee_routing::ID,
+    };
+
+    Instruction {
+        program_id: fee_routing::ID,
+        accounts: accounts.to_account_metas(None),
+        data: fee_routing::instruction::CancelPolicyChange {}.data(),
+    }
+}
+
+/// Build `apply_policy_change`; `queued_by` gets the pending change's rent back
+pub fn apply_policy_change(caller: Pubkey, id: u64, queued_by: Pubkey) -> Instruction {
+    let accounts = fee_routing::accounts::ApplyPolicyChange {
+        caller,
+        policy: pda::policy().0,
+        progress: pda::progress().0,
+        pending_change: pda::policy_change(id).0,
+        queued_by,
+        event_authority: pda::event_authority().0,
+        program: fee_routing::ID,
+    };
+
+    Instruction {
+        program_id: fee_routing::ID,
+        accounts: accounts.to_account_metas(None),
+        data: fee_routing::instruction::ApplyPolicyChange {}.data(),
+    }
+}
+
+/// Build `initialize_investor_ledger` for the investor vesting through `stream`,
+/// signed by the policy `authority`
+pub fn initialize_investor_ledger(payer: Pubkey, authority: Pubkey, stream: Pubkey) -> Instruction {
+    let accounts = fee_routing::accounts::InitializeInvestorLedger {
+        payer,
+        authority,
+        stream,
+        policy: pda::policy().0,
+        investor_ledger: pda::investor_ledger(&stream).0,
+        progress: pda::progress().0,
+        system_program: system_program::ID,
+        event_authority: pda::event_authority().0,
+        program: fee_routing::ID,
+    };
+
+    Instruction {
+        program_id: fee_routing::ID,
+        accounts: accounts.to_account_metas(None),
+        data: fee_routing::instruction::InitializeInvestorLedger {}.data(),
+    }
+}
+
+/// Accounts for `initialize_position` that can't be derived
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InitializePositionAccounts {
+    /// Creator authority (payer)
+    pub authority: Pubkey,
+    /// Vault reference used to derive the position owner PDA
+    pub vault: Pubkey,
+    /// Fresh keypair for the position NFT mint (must also sign)
+    pub position_nft_mint: Pubkey,
+    /// CP-AMM pool
+    pub pool: Pubkey,
+    /// Quote mint (token B); must be `Policy.quote_mint`
+    pub quote_mint: Pubkey,
+}
+
+/// Build `initialize_position`
+pub fn initialize_position(accounts: &InitializePositionAccounts) -> Instruction {
+    let metas = fee_routing::accounts::InitializePosition {
+        authority: accounts.authority,
+        policy: pda::policy().0,
+        position_owner_pda: pda::position_owner(&accounts.vault).0,
+        vault: accounts.vault,
+        position_nft_mint: accounts.position_nft_mint,
+        position_nft_account: pda::cp_amm::position_nft_account(&accounts.position_nft_mint).0,
+        position: pda::cp_amm::position(&accounts.position_nft_mint).0,
+        pool: accounts.pool,
+        pool_authority: meteora::pool_authority(),
+        quote_mint: accounts.quote_mint,
+        rent: sysvar::rent::ID,
+        token_program: token::ID,
+        system_program: system_program::ID,
+        cp_amm_event_authority: pda::cp_amm::event_authority().0,
+        cp_amm_program: meteora::cp_amm_program_id(),
+        event_authority: pda::event_authority().0,
+        program: fee_routing::ID,
+    };
+
+    Instruction {
+        program_id: fee_routing::ID,
+        accounts: metas.to_account_metas(None),
+        data: fee_routing::instruction::InitializePosition {}.data(),
+    }
+}
+
+/// Accounts for `transfer_position_to_creator` that can't be derived
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransferPositionToCreatorAccounts {
+    /// Policy authority (signer)
+    pub authority: Pubkey,
+    /// Vault reference used to derive the position owner PDA
+    pub vault: Pubkey,
+    /// Position NFT mint
+    pub position_nft_mint: Pubkey,
+    /// `Policy.creator_wallet`; receives the NFT in its Token-2022 ATA
+    pub creator_wallet: Pubkey,
+}
+
+/// Build `transfer_position_to_creator`
+///
+/// The creator's ATA for the position NFT must already exist.
+pub fn transfer_position_to_creator(accounts: &TransferPositionToCreatorAccounts) -> Instruction {
+    let metas = fee_routing::accounts::TransferPositionToCreator {
+        authority: accounts.authority,
+        policy: pda::policy().0,
+        progress: pda::progress().0,
+        position_owner_pda: pda::position_owner(&accounts.vault).0,
+        vault: accounts.vault,
+        position_nft_mint: accounts.position_nft_mint,
+        position_nft_account: pda::cp_amm::position_nft_account(&accounts.position_nft_mint).0,
+        creator_nft_account: creator_position_nft_account(accounts),
+        token_program: token_2022::ID,
+        event_authority: pda::event_authority().0,
+        program: fee_routing::ID,
+    };
+
+    Instruction {
+        program_id: fee_routing::ID,
+        accounts: metas.to_account_metas(None),
+        data: fee_routing::instruction::TransferPositionToCreator {}.data(),
+    }
+}
+
+/// Build `migrate_accounts`, growing Policy and Progress to the current
+/// layout; the authority pays the extra rent
+pub fn migrate_accounts(authority: Pubkey) -> Instruction {
+    let accounts = fee_routing::accounts::MigrateAccounts {
+        authority,
+        policy: pda::policy().0,
+        progress: pda::progress().0,
+        system_program: system_program::ID,
+    };
+
+    Instruction {
+        program_id: fee_routing::ID,
+        accounts: accounts.to_account_metas(None),
+        data: fee_routing::instruction::MigrateAccounts {}.data(),
+    }
+}
+
+/// The creator's Token-2022 ATA the position NFT is transferred to
+pub fn creator_position_nft_account(accounts: &TransferPositionToCreatorAccounts) -> Pubkey {
+    get_associated_token_address_with_program_id(
+        &accounts.creator_wallet,
+        &accounts.position_nft_mint,
+        &token_2022::ID,
+    )
+}
+
+/// Accounts for `distribute_fees` that can't be derived
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DistributeFeesAccounts {
+    /// Permissionless caller (signer)
+    pub caller: Pubkey,
+    /// Vault reference used to derive the position owner PDA
+    pub vault: Pubkey,
+    /// CP-AMM pool
+    pub pool: Pubkey,
+    /// Honorary position account
+    pub position: Pubkey,
+    /// Position NFT token account
+    pub position_nft_account: Pubkey,
+    /// Treasury token A account (owned by the treasury authority PDA)
+    pub treasury_token_a: Pubkey,
+    /// Treasury token B (quote) account (owned by the treasury authority PDA)
+    pub treasury_token_b: Pubkey,
+    /// Pool token A vault
+    pub pool_token_a_vault: Pubkey,
+    /// Pool token B vault
+    pub pool_token_b_vault: Pubkey,
+    /// Token A mint
+    pub token_a_mint: Pubkey,
+    /// Token B (quote) mint
+    pub token_b_mint: Pubkey,
+    /// Token program owning token A
+    pub token_a_program: Pubkey,
+    /// Token program owning token B
+    pub token_b_program: Pubkey,
+    /// Creator quote ATA
+    pub creator_ata: Pubkey,
+}
+
+impl DistributeFeesAccounts {
+    /// Fixed account list for `distribute_fees`, in instruction order, without investors
+    pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+        fee_routing::accounts::DistributeFees {
+            caller: self.caller,
+            policy: pda::policy().0,
+            progress: pda::progress().0,
+            history: pda::history().0,
+            position_owner_pda: pda::position_owner(&self.vault).0,
+            vault: self.vault,
+            pool_authority: meteora::pool_authority(),
+            pool: self.pool,
+            position: self.position,
+            position_nft_account: self.position_nft_account,
+            treasury_authority: pda::treasury_authority().0,
+            treasury_token_a: self.treasury_token_a,
+            treasury_token_b: self.treasury_token_b,
+            pool_token_a_vault: self.pool_token_a_vault,
+            pool_token_b_vault: self.pool_token_b_vault,
+            token_a_mint: self.token_a_mint,
+            token_b_mint: self.token_b_mint,
+            token_a_program: self.token_a_program,
+            token_b_program: self.token_b_program,
+            cp_amm_event_authority: pda::cp_amm::event_authority().0,
+            cp_amm_program: meteora::cp_amm_program_id(),
+            creator_ata: self.creator_ata,
+            streamflow_program: streamflow_sdk::id(),
+            token_program: token::ID,
+            event_authority: pda::event_authority().0,
+            program: fee_routing::ID,
+        }
+        .to_account_metas(None)
+    }
+}
+
+/// One investor's remaining accounts for `distribute_fees`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InvestorAccounts {
+    /// Streamflow contract account
+    pub stream: Pubkey,
+    /// Investor quote token account receiving the payout
+    pub ata: Pubkey,
+}
+
+impl InvestorAccounts {
+    /// Investor ledger PDA derived from the stream
+    pub fn ledger(&self) -> Pubkey {
+        pda::investor_ledger(&self.stream).0
+    }
+
+    /// Remaining-account metas in the order the program expects
+    pub fn to_account_metas(&self) -> [AccountMeta; 3] {
+        [
+            AccountMeta::new_readonly(self.stream, false),
+            AccountMeta::new(self.ata, false),
+            AccountMeta::new(self.ledger(), false),
+        ]
+    }
+}
+
+/// Build one `distribute_fees` page
+pub fn distribute_fees(
+    accounts: &DistributeFeesAccounts,
+    investors: &[InvestorAccounts],
+    page_index: u16,
+    is_final_page: bool,
+) -> Instruction {
+    let mut metas = accounts.to_account_metas();
+    metas.extend(
+        investors
+            .iter()
+            .flat_map(InvestorAccounts::to_account_metas),
+    );
+
+    Instruction {
+        program_id: fee_routing::ID,
+        accounts: metas,
+        data: fee_routing::instruction::DistributeFees {
+            page_index,
+            is_final_page,
+        }
+        .data(),
+    }
+}
+
+/// Treasury token account for `mint`: the treasury authority PDA's ATA
+pub fn treasury_token_account(mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(&pda::treasury_authority().0, mint, token_program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distribute_accounts() -> DistributeFeesAccounts {
+        DistributeFeesAccounts {
+            caller: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            position: Pubkey::new_unique(),
+            position_nft_account: Pubkey::new_unique(),
+            treasury_token_a: Pubkey::new_unique(),
+            treasury_token_b: Pubkey::new_unique(),
+            pool_token_a_vault: Pubkey::new_unique(),
+            pool_token_b_vault: Pubkey::new_unique(),
+            token_a_mint: Pubkey::new_unique(),
+            token_b_mint: Pubkey::new_unique(),
+            token_a_program: token::ID,
+            token_b_program: token::ID,
+            creator_ata: Pubkey::new_unique(),
+        }
+    }
+
+    #[test]
+    fn test_distribute_fees_appends_investor_triples() {
+        let accounts = distribute_accounts();
+        let investors = [
+            InvestorAccounts {
+                stream: Pubkey::new_unique(),
+                ata: Pubkey::new_unique(),
+            },
+            InvestorAccounts {
+                stream: Pubkey::new_unique(),
+                ata: Pubkey::new_unique(),
+            },
+        ];
+
+        let ix = distribute_fees(&accounts, &investors, 3, true);
+        let fixed = accounts.to_account_metas().len();
+
+        assert_eq!(ix.program_id, fee_routing::ID);
+        assert_eq!(ix.accounts.len(), fixed + 6);
+        assert!(ix.accounts[0].is_signer);
+
+        let stream = &ix.accounts[fixed];
+        let ata = &ix.accounts[fixed + 1];
+        assert_eq!(stream.pubkey, investors[0].stream);
+        assert!(!stream.is_writable);
+        assert_eq!(ata.pubkey, investors[0].ata);
+        assert!(ata.is_writable);
+        let ledger = &ix.accounts[fixed + 2];
+        assert_eq!(ledger.pubkey, pda::investor_ledger(&investors[0].stream).0);
+        assert!(ledger.is_writable);
+        assert_eq!(ix.accounts[fixed + 3].pubkey, investors[1].stream);
+
+        // 8-byte discriminator + u16 page_index + bool is_final_page
+        assert_eq!(ix.data.len(), 8 + 2 + 1);
+        assert_eq!(&ix.data[8..10], &3u16.to_le_bytes());
+        assert_eq!(ix.data[10], 1);
+    }
+
+    #[test]
+    fn test_initialize_position_derives_meteora_pdas() {
+        let accounts = InitializePositionAccounts {
+            authority: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            position_nft_mint: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+        };
+
+        let ix = initialize_position(&accounts);
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|m| m.pubkey).collect();
+
+        assert!(keys.contains(&pda::cp_amm::position(&accounts.position_nft_mint).0));
+        assert!(keys.contains(&pda::cp_amm::position_nft_account(&accounts.position_nft_mint).0));
+        assert!(keys.contains(&pda::position_owner(&accounts.vault).0));
+        assert!(keys.contains(&pda::event_authority().0));
+    }
+
+    #[test]
+    fn test_transfer_position_to_creator_targets_creator_ata() {
+        let accounts = TransferPositionToCreatorAccounts {
+            authority: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            position_nft_mint: Pubkey::new_unique(),
+            creator_wallet: Pubkey::new_unique(),
+        };
+
+        let ix = transfer_position_to_creator(&accounts);
+        let position_nft_account = &ix.accounts[6];
+        let creator_nft_account = &ix.accounts[7];
+
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(
+            ix.accounts[3].pubkey,
+            pda::position_owner(&accounts.vault).0
+        );
+        assert_eq!(
+            position_nft_account.pubkey,
+            pda::cp_amm::position_nft_account(&accounts.position_nft_mint).0
+        );
+        assert!(position_nft_account.is_writable);
+        assert_eq!(
+            creator_nft_account.pubkey,
+            creator_position_nft_account(&accounts)
+        );
+        assert!(creator_nft_account.is_writable);
+        assert_eq!(ix.accounts[8].pubkey, token_2022::ID);
+    }
+
+    #[test]
+    fn test_update_policy_encodes_only_given_fields() {
+        let authority = Pubkey::new_unique();
+        let update = PolicyUpdate {
+            investor_fee_share_bps: Some(5_000),
+            allocation_mode: Some(AllocationMode::FullPrecision),
+            rounding_mode: Some(RoundingMode::LargestRemainder),
+            max_investor_payout_bps: Some(2_500),
+            cap_excess_mode: Some(CapExcessMode::Creator),
+            locked_mode: Some(LockedMode::WindowAverage),
+            y0_mode: Some(Y0Mode::StreamDeposits),
+            fee_share_schedule: Some(vec![FeeShareStep {
+                start_ts: 1_751_328_000,
+                share_bps: 4_000,
+            }]),
+            ..Default::default()
+        };
+
+        let ix = update_policy(authority, &update);
+        assert_eq!(ix.accounts[0].pubkey, authority);
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(ix.accounts[1].pubkey, pda::policy().0);
+        assert!(ix.accounts[1].is_writable);
+        assert!(!ix.accounts[2].is_writable);
+
+        let decoded = PolicyUpdate::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(decoded, update);
+    }
+
+    #[test]
+    fn test_policy_change_builders_target_the_same_pda() {
+        let authority = Pubkey::new_unique();
+        let update = PolicyUpdate {
+            policy_change_delay_seconds: Some(86_400),
+            ..Default::default()
+        };
+
+        let queue = queue_policy_change(authority, 7, &update);
+        let cancel = cancel_policy_change(authority, 7, authority);
+        let apply = apply_policy_change(Pubkey::new_unique(), 7, authority);
+        let pending = pda::policy_change(7).0;
+
+        assert_eq!(queue.accounts[2].pubkey, pending);
+        assert!(queue.accounts[0].is_writable);
+        assert_eq!(cancel.accounts[2].pubkey, pending);
+        assert!(cancel.accounts[3].is_writable);
+        assert_eq!(apply.accounts[3].pubkey, pending);
+        assert!(!apply.accounts[2].is_writable);
+        assert_eq!(
+            PolicyUpdate::try_from_slice(&queue.data[8..]).unwrap(),
+            update
+        );
+    }
+}
@@ -0,0 +1,23 @@
+//! Rust client for the fee-routing program.
+//!
+//! Everything here is built on the program crate's `cpi` feature, so account
+//! layouts, instruction arguments and event types come straight from the
+//! on-chain definitions instead of being re-declared by hand.
+//!
+//! - [`pda`]: PDA derivations for program and Meteora CP-AMM accounts
+//! - [`instructions`]: typed builders for every program instruction
+//! - [`accounts`]: decoders for `Policy`, `Progress` and `DistributionHistory`
+//! - [`events`]: decoders for CPI-emitted and log-emitted events
+//! - [`lookup_table`]: Address Lookup Table instructions for the crank's
+//!   static and investor accounts
+//! - [`planner`]: splits an investor set into `distribute_fees` pages that fit
+//!   transaction size, account-lock and compute limits
+
+pub mod accounts;
+pub mod events;
+pub mod instructions;
+pub mod lookup_table;
+pub mod pda;
+pub mod planner;
+
+pub use fee_routing::ID as PROGRAM_ID;
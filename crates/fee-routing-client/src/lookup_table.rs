@@ -0,0 +1,216 @@
+//! Address Lookup Table support for the `distribute_fees` crank.
+//!
+//! Every page repeats the same ~25 fixed accounts before the investor triples.
+//! Putting those accounts, plus the registered investor streams, ATAs and
+//! ledgers, in a lookup table shrinks each reference from 32 bytes to a
+//! 1-byte index, which lets v0 transactions carry far more investors per page.
+//!
+//! Tables are created once by an operator and extended as investors are
+//! registered. New addresses only become usable one slot after the extending
+//! transaction lands, so keepers should wait for that before planning pages.
+
+use std::collections::HashSet;
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, message::AddressLookupTableAccount},
+};
+use solana_address_lookup_table_interface::{
+    instruction as alt_instruction,
+    state::{AddressLookupTable, LOOKUP_TABLE_MAX_ADDRESSES},
+};
+use thiserror::Error;
+
+use crate::instructions::{DistributeFeesAccounts, InvestorAccounts};
+
+pub use solana_address_lookup_table_interface::program::ID as ADDRESS_LOOKUP_TABLE_PROGRAM_ID;
+
+/// Addresses appended per `ExtendLookupTable` instruction, keeping each
+/// extend transaction comfortably under the packet size limit
+pub const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LookupTableError {
+    #[error("lookup table needs {needed} more addresses but only {available} slots remain")]
+    TableFull { needed: usize, available: usize },
+
+    #[error("invalid lookup table account data")]
+    InvalidAccountData,
+}
+
+/// Fixed `distribute_fees` accounts worth putting in a table
+///
+/// Signers are excluded: they must stay in the static account list.
+pub fn static_addresses(accounts: &DistributeFeesAccounts) -> Vec<Pubkey> {
+    dedup(
+        accounts
+            .to_account_metas()
+            .into_iter()
+            .filter(|meta| !meta.is_signer)
+            .map(|meta| meta.pubkey),
+    )
+}
+
+/// Stream, ATA and ledger addresses for every investor, in page order
+pub fn investor_addresses(investors: &[InvestorAccounts]) -> Vec<Pubkey> {
+    dedup(
+        investors
+            .iter()
+            .flat_map(|inv| [inv.stream, inv.ata, inv.ledger()]),
+    )
+}
+
+/// Addresses from `wanted` that are not yet in `existing`, in order, without duplicates
+pub fn missing_addresses(existing: &[Pubkey], wanted: &[Pubkey]) -> Vec<Pubkey> {
+    let existing: HashSet<&Pubkey> = existing.iter().collect();
+    dedup(wanted.iter().filter(|key| !existing.contains(key)).copied())
+}
+
+/// `CreateLookupTable` instruction and the derived table address
+///
+/// `recent_slot` must be a recent finalized slot; it seeds the table address.
+pub fn create_lookup_table(
+    authority: Pubkey,
+    payer: Pubkey,
+    recent_slot: u64,
+) -> (Instruction, Pubkey) {
+    alt_instruction::create_lookup_table(authority, payer, recent_slot)
+}
+
+/// `ExtendLookupTable` instructions adding whatever `wanted` addresses the
+/// table doesn't hold yet, chunked so each fits its own transaction
+pub fn extend_lookup_table(
+    table: Pubkey,
+    authority: Pubkey,
+    payer: Pubkey,
+    existing: &[Pubkey],
+    wanted: &[Pubkey],
+) -> Result<Vec<Instruction>, LookupTableError> {
+    let missing = missing_addresses(existing, wanted);
+    let available = LOOKUP_TABLE_MAX_ADDRESSES.saturating_sub(existing.len());
+    if missing.len() > available {
+        return Err(LookupTableError::TableFull {
+            needed: missing.len(),
+            available,
+        });
+    }
+
+    Ok(missing
+        .chunks(MAX_ADDRESSES_PER_EXTEND)
+        .map(|chunk| {
+            alt_instruction::extend_lookup_table(table, authority, Some(payer), chunk.to_vec())
+        })
+        .collect())
+}
+
+/// Decode a fetched lookup table account into the form v0 compilation expects
+pub fn decode_lookup_table(
+    key: Pubkey,
+    data: &[u8],
+) -> Result<AddressLookupTableAccount, LookupTableError> {
+    let table =
+        AddressLookupTable::deserialize(data).map_err(|_| LookupTableError::InvalidAccountData)?;
+    Ok(AddressLookupTableAccount {
+        key,
+        addresses: table.addresses.to_vec(),
+    })
+}
+
+fn dedup(keys: impl IntoIterator<Item = Pubkey>) -> Vec<Pubkey> {
+    let mut seen = HashSet::new();
+    keys.into_iter().filter(|key| seen.insert(*key)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::token;
+    use solana_address_lookup_table_interface::state::LookupTableMeta;
+    use std::borrow::Cow;
+
+    fn accounts() -> DistributeFeesAccounts {
+        DistributeFeesAccounts {
+            caller: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            position: Pubkey::new_unique(),
+            position_nft_account: Pubkey::new_unique(),
+            treasury_token_a: Pubkey::new_unique(),
+            treasury_token_b: Pubkey::new_unique(),
+            pool_token_a_vault: Pubkey::new_unique(),
+            pool_token_b_vault: Pubkey::new_unique(),
+            token_a_mint: Pubkey::new_unique(),
+            token_b_mint: Pubkey::new_unique(),
+            token_a_program: token::ID,
+            token_b_program: token::ID,
+            creator_ata: Pubkey::new_unique(),
+        }
+    }
+
+    #[test]
+    fn test_static_addresses_exclude_signer_and_duplicates() {
+        let accounts = accounts();
+        let keys = static_addresses(&accounts);
+
+        assert!(!keys.contains(&accounts.caller));
+        assert!(keys.contains(&accounts.pool));
+        // token_a_program, token_b_program and token_program are all SPL Token
+        assert_eq!(keys.iter().filter(|k| **k == token::ID).count(), 1);
+    }
+
+    #[test]
+    fn test_extend_only_adds_missing_addresses_in_chunks() {
+        let existing: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let mut wanted = existing.clone();
+        wanted.extend((0..45).map(|_| Pubkey::new_unique()));
+
+        let table = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let ixs = extend_lookup_table(table, authority, authority, &existing, &wanted).unwrap();
+
+        assert_eq!(ixs.len(), 3);
+        assert!(ixs
+            .iter()
+            .all(|ix| ix.program_id == ADDRESS_LOOKUP_TABLE_PROGRAM_ID));
+        assert!(
+            extend_lookup_table(table, authority, authority, &wanted, &wanted)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_extend_rejects_overflowing_table() {
+        let existing: Vec<Pubkey> = (0..250).map(|_| Pubkey::new_unique()).collect();
+        let wanted: Vec<Pubkey> = (0..10).map(|_| Pubkey::new_unique()).collect();
+        let key = Pubkey::new_unique();
+
+        assert_eq!(
+            extend_lookup_table(key, key, key, &existing, &wanted).unwrap_err(),
+            LookupTableError::TableFull {
+                needed: 10,
+                available: 6
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_lookup_table() {
+        let addresses: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let data = AddressLookupTable {
+            meta: LookupTableMeta::new(Pubkey::new_unique()),
+            addresses: Cow::Borrowed(&addresses),
+        }
+        .serialize_for_tests()
+        .unwrap();
+
+        let key = Pubkey::new_unique();
+        let decoded = decode_lookup_table(key, &data).unwrap();
+        assert_eq!(decoded.key, key);
+        assert_eq!(decoded.addresses, addresses);
+        assert_eq!(
+            decode_lookup_table(key, &[0u8; 4]).unwrap_err(),
+            LookupTableError::InvalidAccountData
+        );
+    }
+}
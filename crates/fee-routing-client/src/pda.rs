@@ -0,0 +1,120 @@
+//! PDA derivations used by the fee-routing program and its Meteora CPIs.
+
+use anchor_lang::prelude::Pubkey;
+use fee_routing::{constants::*, meteora};
+
+/// Seed Anchor uses for the `#[event_cpi]` event authority
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Policy PDA: `[b"policy"]`
+pub fn policy() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POLICY_SEED], &fee_routing::ID)
+}
+
+/// Progress PDA: `[b"progress"]`
+pub fn progress() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROGRESS_SEED], &fee_routing::ID)
+}
+
+/// Distribution history PDA: `[b"history"]`
+pub fn history() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[HISTORY_SEED], &fee_routing::ID)
+}
+
+/// Investor ledger PDA: `[b"investor_ledger", stream]`
+pub fn investor_ledger(stream: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[INVESTOR_LEDGER_SEED, stream.as_ref()], &fee_routing::ID)
+}
+
+/// Pending policy change PDA: `[b"policy_change", id as u64 LE]`
+pub fn policy_change(id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POLICY_CHANGE_SEED, &id.to_le_bytes()], &fee_routing::ID)
+}
+
+/// Treasury authority PDA: `[b"treasury"]`
+pub fn treasury_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED], &fee_routing::ID)
+}
+
+/// Honorary position owner PDA: `[b"vault", vault, b"investor_fee_pos_owner"]`
+pub fn position_owner(vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VAULT_SEED, vault.as_ref(), INVESTOR_FEE_POS_OWNER_SEED],
+        &fee_routing::ID,
+    )
+}
+
+/// Fee-routing event authority PDA used by `emit_cpi!`
+pub fn event_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &fee_routing::ID)
+}
+
+/// PDAs owned by the Meteora CP-AMM program
+pub mod cp_amm {
+    use super::*;
+
+    /// CP-AMM event authority: `[b"__event_authority"]`
+    pub fn event_authority() -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[meteora::EVENT_AUTHORITY_SEED],
+            &meteora::cp_amm_program_id(),
+        )
+    }
+
+    /// Position account: `[b"position", position_nft_mint]`
+    pub fn position(position_nft_mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[meteora::POSITION_SEED, position_nft_mint.as_ref()],
+            &meteora::cp_amm_program_id(),
+        )
+    }
+
+    /// Position NFT token account: `[b"position_nft_account", position_nft_mint]`
+    pub fn position_nft_account(position_nft_mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                meteora::POSITION_NFT_ACCOUNT_SEED,
+                position_nft_mint.as_ref(),
+            ],
+            &meteora::cp_amm_program_id(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_pdas_are_distinct() {
+        let keys = [
+            policy().0,
+            progress().0,
+            history().0,
+            treasury_authority().0,
+            event_authority().0,
+        ];
+
+        for (i, a) in keys.iter().enumerate() {
+            for b in &keys[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_policy_change_depends_on_id() {
+        assert_eq!(policy_change(0), policy_change(0));
+        assert_ne!(policy_change(0).0, policy_change(1).0);
+        assert_ne!(policy_change(0).0, policy().0);
+    }
+
+    #[test]
+    fn test_position_owner_depends_on_vault() {
+        let vault_a = Pubkey::new_unique();
+        let vault_b = Pubkey::new_unique();
+
+        assert_eq!(position_owner(&vault_a), position_owner(&vault_a));
+        assert_ne!(position_owner(&vault_a).0, position_owner(&vault_b).0);
+    }
+}
@@ -0,0 +1,476 @@
+//! Page planning for the `distribute_fees` crank.
+//!
+//! `MAX_INVESTORS_PER_PAGE` is only the program's hard ceiling. In practice a
+//! page is bounded much earlier by the 1232-byte packet limit, the per-transaction
+//! account-lock limit and the compute budget. The planner packs investors into
+//! pages greedily, checking each candidate page against all three by building
+//! the real instructions and measuring the compiled message.
+//!
+//! When `PlannerConfig::lookup_tables` is non-empty, pages are measured as v0
+//! transactions so table-resident accounts cost one byte instead of 32.
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        hash::Hash,
+        instruction::Instruction,
+        message::{v0, AddressLookupTableAccount, CompileError, Message, VersionedMessage},
+    },
+};
+use fee_routing::constants::MAX_INVESTORS_PER_PAGE;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use thiserror::Error;
+
+use crate::instructions::{distribute_fees, DistributeFeesAccounts, InvestorAccounts};
+
+/// Maximum serialized transaction size (`PACKET_DATA_SIZE`)
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Maximum accounts a single transaction may lock
+pub const MAX_ACCOUNT_LOCKS: usize = 64;
+
+/// Maximum compute unit limit a transaction may request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Limits and cost model used when splitting investors into pages
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlannerConfig {
+    /// Maximum serialized transaction size in bytes
+    pub max_transaction_size: usize,
+    /// Maximum number of accounts the transaction may lock
+    pub max_account_locks: usize,
+    /// Compute units a page may be estimated to use
+    pub target_compute_units: u32,
+    /// Fixed cost of every page (account validation, progress/history writes)
+    pub base_compute_units: u32,
+    /// Extra cost of page 0 (CP-AMM `claim_position_fee` CPI and balance reads)
+    pub first_page_compute_units: u32,
+    /// Extra cost of the final page (creator transfer, history record)
+    pub final_page_compute_units: u32,
+    /// Cost per investor (stream read, ATA read, transfer CPI, payout event CPI)
+    pub compute_units_per_investor: u32,
+    /// Headroom added on top of the estimate when setting the CU limit (bps)
+    pub compute_unit_margin_bps: u32,
+    /// Priority fee in micro-lamports per CU; `None` skips `SetComputeUnitPrice`
+    pub compute_unit_price: Option<u64>,
+    /// Fee payer, if different from the crank caller
+    pub fee_payer: Option<Pubkey>,
+    /// Lookup tables to compile pages against; empty means legacy transactions
+    pub lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl Default for PlannerConfig {
+    fn default() -> Self {
+        Self {
+            max_transaction_size: MAX_TRANSACTION_SIZE,
+            max_account_locks: MAX_ACCOUNT_LOCKS,
+            target_compute_units: MAX_COMPUTE_UNIT_LIMIT,
+            base_compute_units: 40_000,
+            first_page_compute_units: 80_000,
+            final_page_compute_units: 30_000,
+            // ~11k measured for read + transfer, plus the per-investor event CPI
+            compute_units_per_investor: 15_000,
+            compute_unit_margin_bps: 1_000,
+            compute_unit_price: None,
+            fee_payer: None,
+            lookup_tables: Vec::new(),
+        }
+    }
+}
+
+impl PlannerConfig {
+    /// Estimated compute units for a page
+    pub fn estimate_compute_units(&self, investors: usize, first: bool, last: bool) -> u64 {
+        let mut units = self.base_compute_units as u64
+            + self.compute_units_per_investor as u64 * investors as u64;
+        if first {
+            units += self.first_page_compute_units as u64;
+        }
+        if last {
+            units += self.final_page_compute_units as u64;
+        }
+        units
+    }
+
+    /// Compute unit limit to request for an estimate, including the margin
+    pub fn compute_unit_limit(&self, estimate: u64) -> u32 {
+        let with_margin = estimate + estimate * self.compute_unit_margin_bps as u64 / 10_000;
+        with_margin.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+    }
+}
+
+/// Reasons a plan can't be produced
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PlanError {
+    #[error("investor {index} ({stream}) does not fit in a page on its own")]
+    InvestorDoesNotFit { index: usize, stream: Pubkey },
+
+    #[error("too many pages for a u16 page index")]
+    TooManyPages,
+
+    #[error("failed to compile v0 message: {0}")]
+    Compile(#[from] CompileError),
+}
+
+/// One `distribute_fees` transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlannedPage {
+    pub page_index: u16,
+    pub is_final_page: bool,
+    pub investors: Vec<InvestorAccounts>,
+    /// Compute unit limit requested via `SetComputeUnitLimit`
+    pub compute_unit_limit: u32,
+    /// Estimated compute units before margin
+    pub estimated_compute_units: u64,
+    /// Serialized size of the signed transaction
+    pub transaction_size: usize,
+    /// Accounts locked by the transaction
+    pub account_locks: usize,
+}
+
+impl PlannedPage {
+    /// Compute budget instructions followed by the `distribute_fees` instruction
+    pub fn instructions(
+        &self,
+        accounts: &DistributeFeesAccounts,
+        config: &PlannerConfig,
+    ) -> Vec<Instruction> {
+        page_instructions(
+            accounts,
+            &self.investors,
+            self.page_index,
+            self.is_final_page,
+            self.compute_unit_limit,
+            config.compute_unit_price,
+        )
+    }
+
+    /// Message for this page: v0 when `config` has lookup tables, legacy otherwise
+    pub fn message(
+        &self,
+        accounts: &DistributeFeesAccounts,
+        config: &PlannerConfig,
+        recent_blockhash: Hash,
+    ) -> Result<VersionedMessage, CompileError> {
+        let payer = config.fee_payer.unwrap_or(accounts.caller);
+        let ixs = self.instructions(accounts, config);
+        if config.lookup_tables.is_empty() {
+            Ok(VersionedMessage::Legacy(Message::new_with_blockhash(
+                &ixs,
+                Some(&payer),
+                &recent_blockhash,
+            )))
+        } else {
+            v0::Message::try_compile(&payer, &ixs, &config.lookup_tables, recent_blockhash)
+                .map(VersionedMessage::V0)
+        }
+    }
+}
+
+fn page_instructions(
+    accounts: &DistributeFeesAccounts,
+    investors: &[InvestorAccounts],
+    page_index: u16,
+    is_final_page: bool,
+    compute_unit_limit: u32,
+    compute_unit_price: Option<u64>,
+) -> Vec<Instruction> {
+    let mut ixs = Vec::with_capacity(3);
+    ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(
+        compute_unit_limit,
+    ));
+    if let Some(price) = compute_unit_price {
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+    }
+    ixs.push(distribute_fees(
+        accounts,
+        investors,
+        page_index,
+        is_final_page,
+    ));
+    ixs
+}
+
+/// Size of a compact-u16 length prefix
+fn compact_u16_len(value: usize) -> usize {
+    match value {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+/// Serialized size and account-lock count of a legacy transaction
+pub fn measure_legacy(instructions: &[Instruction], payer: &Pubkey) -> (usize, usize) {
+    let message = Message::new(instructions, Some(payer));
+    let signatures = message.header.num_required_signatures as usize;
+    let size = compact_u16_len(signatures) + signatures * 64 + message.serialize().len();
+    (size, message.account_keys.len())
+}
+
+/// Serialized size and account-lock count of a v0 transaction
+///
+/// Accounts loaded from tables still count toward the lock limit.
+pub fn measure_v0(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Result<(usize, usize), CompileError> {
+    let message = v0::Message::try_compile(payer, instructions, lookup_tables, Hash::default())?;
+    let signatures = message.header.num_required_signatures as usize;
+    let locks = message.account_keys.len()
+        + message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+            .sum::<usize>();
+    let size = compact_u16_len(signatures)
+        + signatures * 64
+        + VersionedMessage::V0(message).serialize().len();
+    Ok((size, locks))
+}
+
+/// Split `investors` into ordered pages that satisfy `config`
+///
+/// Always returns at least one page: with no investors, a single final page
+/// still claims fees and pays the creator.
+pub fn plan_pages(
+    accounts: &DistributeFeesAccounts,
+    investors: &[InvestorAccounts],
+    config: &PlannerConfig,
+) -> Result<Vec<PlannedPage>, PlanError> {
+    let payer = config.fee_payer.unwrap_or(accounts.caller);
+    let mut pages: Vec<PlannedPage> = Vec::new();
+    let mut start = 0usize;
+
+    loop {
+        let page_index = u16::try_from(pages.len()).map_err(|_| PlanError::TooManyPages)?;
+        let mut best: Option<PlannedPage> = None;
+        let mut end = start;
+
+        // Grow the page one investor at a time until it no longer fits
+        while end <= investors.len() {
+            let candidate = try_page(accounts, investors, start, end, page_index, &payer, config)?;
+            match candidate {
+                Some(page) => {
+                    best = Some(page);
+                    end += 1;
+                }
+                None => break,
+            }
+        }
+
+        let page = match best {
+            // An empty non-final page makes no progress
+            Some(page) if !page.investors.is_empty() || page.is_final_page => page,
+            _ => {
+                return Err(PlanError::InvestorDoesNotFit {
+                    index: start,
+                    stream: investors[start].stream,
+                })
+            }
+        };
+
+        start += page.investors.len();
+        let done = page.is_final_page;
+        pages.push(page);
+
+        if done {
+            return Ok(pages);
+        }
+    }
+}
+
+fn try_page(
+    accounts: &DistributeFeesAccounts,
+    investors: &[InvestorAccounts],
+    start: usize,
+    end: usize,
+    page_index: u16,
+    payer: &Pubkey,
+    config: &PlannerConfig,
+) -> Result<Option<PlannedPage>, PlanError> {
+    let page_investors = &investors[start..end];
+    if page_investors.len() > MAX_INVESTORS_PER_PAGE {
+        return Ok(None);
+    }
+
+    let is_final_page = end == investors.len();
+    let estimate =
+        config.estimate_compute_units(page_investors.len(), page_index == 0, is_final_page);
+    if estimate > config.target_compute_units as u64 {
+        return Ok(None);
+    }
+
+    let compute_unit_limit = config.compute_unit_limit(estimate);
+    let ixs = page_instructions(
+        accounts,
+        page_investors,
+        page_index,
+        is_final_page,
+        compute_unit_limit,
+        config.compute_unit_price,
+    );
+    let (transaction_size, account_locks) = if config.lookup_tables.is_empty() {
+        measure_legacy(&ixs, payer)
+    } else {
+        measure_v0(&ixs, payer, &config.lookup_tables)?
+    };
+    if transaction_size > config.max_transaction_size || account_locks > config.max_account_locks {
+        return Ok(None);
+    }
+
+    Ok(Some(PlannedPage {
+        page_index,
+        is_final_page,
+        investors: page_investors.to_vec(),
+        compute_unit_limit,
+        estimated_compute_units: estimate,
+        transaction_size,
+        account_locks,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup_table;
+    use anchor_spl::token;
+
+    fn accounts() -> DistributeFeesAccounts {
+        DistributeFeesAccounts {
+            caller: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            position: Pubkey::new_unique(),
+            position_nft_account: Pubkey::new_unique(),
+            treasury_token_a: Pubkey::new_unique(),
+            treasury_token_b: Pubkey::new_unique(),
+            pool_token_a_vault: Pubkey::new_unique(),
+            pool_token_b_vault: Pubkey::new_unique(),
+            token_a_mint: Pubkey::new_unique(),
+            token_b_mint: Pubkey::new_unique(),
+            token_a_program: token::ID,
+            token_b_program: token::ID,
+            creator_ata: Pubkey::new_unique(),
+        }
+    }
+
+    fn investors(n: usize) -> Vec<InvestorAccounts> {
+        (0..n)
+            .map(|_| InvestorAccounts {
+                stream: Pubkey::new_unique(),
+                ata: Pubkey::new_unique(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pages_respect_limits_and_cover_all_investors() {
+        let accounts = accounts();
+        let investors = investors(40);
+        let config = PlannerConfig::default();
+
+        let pages = plan_pages(&accounts, &investors, &config).unwrap();
+        assert!(pages.len() > 1);
+
+        let mut covered = Vec::new();
+        for (i, page) in pages.iter().enumerate() {
+            assert_eq!(page.page_index as usize, i);
+            assert_eq!(page.is_final_page, i == pages.len() - 1);
+            assert!(page.transaction_size <= MAX_TRANSACTION_SIZE);
+            assert!(page.account_locks <= MAX_ACCOUNT_LOCKS);
+            assert!(page.estimated_compute_units <= config.target_compute_units as u64);
+            assert!(!page.investors.is_empty());
+            covered.extend(page.investors.iter().copied());
+
+            let ixs = page.instructions(&accounts, &config);
+            assert_eq!(ixs.len(), 2);
+            assert_eq!(ixs[1].program_id, fee_routing::ID);
+        }
+        assert_eq!(covered, investors);
+    }
+
+    #[test]
+    fn test_compute_target_bounds_page_size() {
+        let accounts = accounts();
+        let investors = investors(12);
+        let config = PlannerConfig {
+            target_compute_units: 160_000,
+            ..Default::default()
+        };
+
+        let pages = plan_pages(&accounts, &investors, &config).unwrap();
+        for page in &pages {
+            assert!(page.estimated_compute_units <= 160_000);
+        }
+        // 160k - 40k base - 80k claim leaves room for 2 investors on page 0
+        assert_eq!(pages[0].investors.len(), 2);
+    }
+
+    #[test]
+    fn test_no_investors_yields_single_final_page() {
+        let pages = plan_pages(&accounts(), &[], &PlannerConfig::default()).unwrap();
+        assert_eq!(pages.len(), 1);
+        assert!(pages[0].is_final_page);
+        assert!(pages[0].investors.is_empty());
+    }
+
+    #[test]
+    fn test_priority_fee_adds_instruction() {
+        let accounts = accounts();
+        let config = PlannerConfig {
+            compute_unit_price: Some(5_000),
+            ..Default::default()
+        };
+
+        let pages = plan_pages(&accounts, &investors(3), &config).unwrap();
+        assert_eq!(pages[0].instructions(&accounts, &config).len(), 3);
+    }
+
+    #[test]
+    fn test_lookup_tables_fit_more_investors_per_page() {
+        let accounts = accounts();
+        let investors = investors(40);
+        let legacy = plan_pages(&accounts, &investors, &PlannerConfig::default()).unwrap();
+
+        let mut addresses = lookup_table::static_addresses(&accounts);
+        addresses.extend(lookup_table::investor_addresses(&investors));
+        let config = PlannerConfig {
+            lookup_tables: vec![AddressLookupTableAccount {
+                key: Pubkey::new_unique(),
+                addresses,
+            }],
+            ..Default::default()
+        };
+        let v0_pages = plan_pages(&accounts, &investors, &config).unwrap();
+
+        assert!(v0_pages.len() * 2 <= legacy.len());
+        for page in &v0_pages {
+            assert!(page.transaction_size <= MAX_TRANSACTION_SIZE);
+            assert!(page.account_locks <= MAX_ACCOUNT_LOCKS);
+            assert!(matches!(
+                page.message(&accounts, &config, Hash::default()).unwrap(),
+                VersionedMessage::V0(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_investor_that_cannot_fit_is_reported() {
+        let investors = investors(2);
+        let config = PlannerConfig {
+            target_compute_units: 100_000,
+            ..Default::default()
+        };
+
+        let err = plan_pages(&accounts(), &investors, &config).unwrap_err();
+        assert_eq!(
+            err,
+            PlanError::InvestorDoesNotFit {
+                index: 0,
+                stream: investors[0].stream
+            }
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    #[error("rpc error: {0}")]
+    Rpc(String),
+
+    #[error("snapshot error: {0}")]
+    Snapshot(String),
+
+    #[error("cursor signature {0} not found in the ledger source")]
+    CursorNotFound(String),
+
+    #[error("storage error: {0}")]
+    Storage(String),
+}
+
+impl From<rusqlite::Error> for IndexerError {
+    fn from(err: rusqlite::Error) -> Self {
+        IndexerError::Storage(err.to_string())
+    }
+}
+
+impl From<csv::Error> for IndexerError {
+    fn from(err: csv::Error) -> Self {
+        IndexerError::Storage(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for IndexerError {
+    fn from(err: std::io::Error) -> Self {
+        IndexerError::Storage(err.to_string())
+    }
+}
@@ -0,0 +1,410 @@
+//! Turns decoded events into normalized ledger records.
+//!
+//! A day opens with `QuoteFeesClaimed`, accumulates `InvestorPayoutPage`
+//! totals and closes with `CreatorPayoutDayClosed`. Each `InvestorPayout` and
+//! each day close becomes one per-recipient record. The open day lives in
+//! [`IndexerState`] so it survives restarts between pages.
+//!
+//! Days are expected to follow each other without holes. When a claim skips
+//! a day, a day's pages or close show up without its claim, or a new day
+//! opens before the previous one closed, a [`GapRecord`] is emitted.
+
+use fee_routing_client::events::FeeRoutingEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::transaction::IndexedTransaction;
+
+/// Last transaction folded into the ledger
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cursor {
+    pub signature: String,
+    pub slot: u64,
+}
+
+/// Everything needed to resume indexing, persisted with each batch
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexerState {
+    pub cursor: Option<Cursor>,
+    /// Most recent `current_day` seen
+    pub last_day: Option<u64>,
+    /// Day whose claim was seen but whose close wasn't yet
+    pub open_day: Option<DayRecord>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayRecord {
+    pub day: u64,
+    pub claimed_quote: u64,
+    /// `None` when the claim was never seen (see the matching gap)
+    pub claim_signature: Option<String>,
+    pub claimed_at: Option<i64>,
+    pub pages: u16,
+    pub investors_paid: u32,
+    pub investor_total: u64,
+    pub rounding_dust: u64,
+    pub creator_amount: u64,
+    pub total_locked: u64,
+    pub locked_fraction_bps: u64,
+    pub eligible_share_bps: u64,
+    pub close_signature: Option<String>,
+    /// `None` for a day that was abandoned before its final page
+    pub closed_at: Option<i64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecipientKind {
+    Investor,
+    Creator,
+}
+
+/// One payout, or below-minimum non-payout, to a single recipient
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoutRecord {
+    pub day: u64,
+    /// `None` for the creator, which is paid after the final page
+    pub page_index: Option<u16>,
+    pub kind: RecipientKind,
+    /// Investor ATA; `None` for the creator (the event doesn't carry it)
+    pub recipient: Option<String>,
+    pub stream: Option<String>,
+    pub locked_amount: Option<u64>,
+    pub amount: u64,
+    /// False when the payout was below the minimum and carried over
+    pub paid: bool,
+    pub timestamp: i64,
+    pub signature: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionRecord {
+    pub position: String,
+    pub owner_pda: String,
+    pub quote_mint: String,
+    pub timestamp: i64,
+    pub signature: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GapKind {
+    /// Days between `expected_day` and `found_day` were never claimed (or not indexed)
+    Missing,
+    /// `expected_day` was claimed but never closed before `found_day` opened
+    Unclosed,
+    /// `found_day` is not after the last day seen
+    OutOfOrder,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GapRecord {
+    pub kind: GapKind,
+    pub expected_day: u64,
+    pub found_day: u64,
+    pub signature: String,
+    pub slot: u64,
+}
+
+/// Records produced by one batch of transactions
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LedgerBatch {
+    /// Closed (or abandoned) days
+    pub days: Vec<DayRecord>,
+    pub payouts: Vec<PayoutRecord>,
+    pub positions: Vec<PositionRecord>,
+    pub gaps: Vec<GapRecord>,
+}
+
+impl LedgerBatch {
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty()
+            && self.payouts.is_empty()
+            && self.positions.is_empty()
+            && self.gaps.is_empty()
+    }
+}
+
+impl IndexerState {
+    /// Fold one transaction into the state, appending its records to `out`
+    pub fn apply(&mut self, tx: &IndexedTransaction, out: &mut LedgerBatch) {
+        for event in &tx.events {
+            self.apply_event(tx, event, out);
+        }
+        self.cursor = Some(Cursor {
+            signature: tx.signature.clone(),
+            slot: tx.slot,
+        });
+    }
+
+    fn apply_event(
+        &mut self,
+        tx: &IndexedTransaction,
+        event: &FeeRoutingEvent,
+        out: &mut LedgerBatch,
+    ) {
+        match event {
+            FeeRoutingEvent::HonoraryPositionInitialized(e) => out.positions.push(PositionRecord {
+                position: e.position.to_string(),
+                owner_pda: e.owner_pda.to_string(),
+                quote_mint: e.quote_mint.to_string(),
+                timestamp: e.timestamp,
+                signature: tx.signature.clone(),
+            }),
+            FeeRoutingEvent::QuoteFeesClaimed(e) => {
+                let day = self.open(e.distribution_day, tx, out);
+                day.claimed_quote = e.amount;
+                day.claim_signature = Some(tx.signature.clone());
+                day.claimed_at = Some(e.timestamp);
+            }
+            FeeRoutingEvent::InvestorPayout(e) => out.payouts.push(PayoutRecord {
+                day: e.day,
+                page_index: Some(e.page_index),
+                kind: RecipientKind::Investor,
+                recipient: Some(e.destination_ata.to_string()),
+                stream: Some(e.stream.to_string()),
+                locked_amount: Some(e.locked_amount),
+                // A paid row also carries the owed balance it released
+                amount: e.payout + e.owed_released,
+                paid: e.paid,
+                timestamp: e.timestamp,
+                signature: tx.signature.clone(),
+            }),
+            FeeRoutingEvent::InvestorPayoutPage(e) => {
+                let day = self.current(e.day, tx, out);
+                day.pages += 1;
+                day.investors_paid += u32::from(e.investors_paid);
+                day.investor_total += e.total_distributed;
+                day.rounding_dust += e.rounding_dust;
+            }
+            FeeRoutingEvent::CreatorPayoutDayClosed(e) => {
+                let day = self.current(e.day, tx, out);
+                day.creator_amount = e.creator_amount;
+                day.investor_total = e.total_distributed_to_investors;
+                day.total_locked = e.total_locked;
+                day.locked_fraction_bps = e.locked_fraction_bps;
+                day.eligible_share_bps = e.eligible_share_bps;
+                day.close_signature = Some(tx.signature.clone());
+                day.closed_at = Some(e.timestamp);
+
+                out.payouts.push(PayoutRecord {
+                    day: e.day,
+                    page_index: None,
+                    kind: RecipientKind::Creator,
+                    recipient: None,
+                    stream: None,
+                    locked_amount: None,
+                    amount: e.creator_amount,
+                    paid: e.creator_amount > 0,
+                    timestamp: e.timestamp,
+                    signature: tx.signature.clone(),
+                });
+                out.days.extend(self.open_day.take());
+            }
+            FeeRoutingEvent::PolicyUpdated(_)
+            | FeeRoutingEvent::Y0Updated(_)
+            | FeeRoutingEvent::InvestorLedgerInitialized(_)
+            | FeeRoutingEvent::PolicyChangeQueued(_)
+            | FeeRoutingEvent::PolicyChangeCancelled(_)
+            | FeeRoutingEvent::PolicyChangeApplied(_)
+            | FeeRoutingEvent::StreamsFullyVested(_)
+            | FeeRoutingEvent::PositionTransferredToCreator(_) => {}
+        }
+    }
+
+    /// Start `day`, closing out whatever day was still open
+    fn open(&mut self, day: u64, tx: &IndexedTransaction, out: &mut LedgerBatch) -> &mut DayRecord {
+        if let Some(unclosed) = self.open_day.take() {
+            out.gaps.push(gap(GapKind::Unclosed, unclosed.day, day, tx));
+            out.days.push(unclosed);
+        }
+
+        let expected = self.last_day.map_or(1, |last| last + 1);
+        if day > expected {
+            out.gaps.push(gap(GapKind::Missing, expected, day, tx));
+        } else if day < expected {
+            out.gaps.push(gap(GapKind::OutOfOrder, expected, day, tx));
+        }
+
+        self.last_day = Some(day);
+        self.open_day.insert(DayRecord {
+            day,
+            ..Default::default()
+        })
+    }
+
+    /// The open `day`, opening it (and recording its missing claim) if needed
+    fn current(
+        &mut self,
+        day: u64,
+        tx: &IndexedTransaction,
+        out: &mut LedgerBatch,
+    ) -> &mut DayRecord {
+        if self.open_day.as_ref().is_some_and(|open| open.day == day) {
+            return self.open_day.as_mut().expect("checked above");
+        }
+        // Pages or a close without the claim. If `day` also skips ahead,
+        // `open` already reports the hole including this day.
+        let claim_missing = self.last_day.map_or(day == 1, |last| last + 1 == day);
+        let opened = self.open(day, tx, out);
+        if claim_missing {
+            out.gaps.push(gap(GapKind::Missing, day, day, tx));
+        }
+        opened
+    }
+}
+
+fn gap(kind: GapKind, expected_day: u64, found_day: u64, tx: &IndexedTransaction) -> GapRecord {
+    GapRecord {
+        kind,
+        expected_day,
+        found_day,
+        signature: tx.signature.clone(),
+        slot: tx.slot,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use fee_routing_client::events::{
+        CreatorPayoutDayClosed, InvestorPayout, InvestorPayoutPage, QuoteFeesClaimed,
+    };
+
+    fn tx(signature: &str, events: Vec<FeeRoutingEvent>) -> IndexedTransaction {
+        IndexedTransaction {
+            signature: signature.to_string(),
+            slot: 1,
+            block_time: None,
+            succeeded: true,
+            events,
+        }
+    }
+
+    fn claim(day: u64, amount: u64) -> FeeRoutingEvent {
+        FeeRoutingEvent::QuoteFeesClaimed(QuoteFeesClaimed {
+            amount,
+            timestamp: day as i64 * 86_400,
+            distribution_day: day,
+        })
+    }
+
+    fn payout(day: u64, page_index: u16, amount: u64) -> FeeRoutingEvent {
+        FeeRoutingEvent::InvestorPayout(InvestorPayout {
+            day,
+            page_index,
+            stream: Pubkey::new_unique(),
+            destination_ata: Pubkey::new_unique(),
+            locked_amount: 10,
+            weight_numerator: 10,
+            weight_denominator: 20,
+            page_distributable: amount * 2,
+            payout: amount,
+            paid: true,
+            owed_released: 0,
+            owed: 0,
+            timestamp: 0,
+        })
+    }
+
+    fn page(day: u64, page_index: u16, total: u64) -> FeeRoutingEvent {
+        FeeRoutingEvent::InvestorPayoutPage(InvestorPayoutPage {
+            day,
+            page_index,
+            investors_paid: 1,
+            total_distributed: total,
+            rounding_dust: 1,
+            total_locked: 10,
+            locked_fraction_bps: 100,
+            eligible_share_bps: 100,
+            payout_cap_excess: 0,
+            timestamp: 0,
+        })
+    }
+
+    fn close(day: u64, creator: u64, investors: u64) -> FeeRoutingEvent {
+        FeeRoutingEvent::CreatorPayoutDayClosed(CreatorPayoutDayClosed {
+            day,
+            creator_amount: creator,
+            total_distributed_to_investors: investors,
+            total_locked: 20,
+            locked_fraction_bps: 200,
+            eligible_share_bps: 200,
+            timestamp: day as i64 * 86_400 + 60,
+        })
+    }
+
+    #[test]
+    fn test_day_spanning_transactions() {
+        let mut state = IndexerState::default();
+        let mut out = LedgerBatch::default();
+
+        state.apply(
+            &tx("a", vec![claim(1, 1_000), payout(1, 0, 40), page(1, 0, 40)]),
+            &mut out,
+        );
+        assert!(out.days.is_empty());
+        assert_eq!(state.open_day.as_ref().unwrap().pages, 1);
+
+        state.apply(
+            &tx(
+                "b",
+                vec![payout(1, 1, 60), page(1, 1, 60), close(1, 900, 100)],
+            ),
+            &mut out,
+        );
+
+        assert!(out.gaps.is_empty());
+        assert!(state.open_day.is_none());
+        assert_eq!(state.cursor.as_ref().unwrap().signature, "b");
+        let day = &out.days[0];
+        assert_eq!(day.claimed_quote, 1_000);
+        assert_eq!(day.pages, 2);
+        assert_eq!(day.investor_total, 100);
+        assert_eq!(day.rounding_dust, 2);
+        assert_eq!(day.creator_amount, 900);
+        assert_eq!(day.claim_signature.as_deref(), Some("a"));
+        assert_eq!(day.close_signature.as_deref(), Some("b"));
+
+        // two investor rows plus the creator row
+        assert_eq!(out.payouts.len(), 3);
+        assert_eq!(out.payouts[2].kind, RecipientKind::Creator);
+        assert_eq!(out.payouts[2].amount, 900);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let mut state = IndexerState::default();
+        let mut out = LedgerBatch::default();
+
+        // Day 1 closes normally, day 2 opens but never closes, day 4 skips day 3
+        state.apply(&tx("a", vec![claim(1, 10), close(1, 10, 0)]), &mut out);
+        state.apply(&tx("b", vec![claim(2, 10)]), &mut out);
+        state.apply(&tx("c", vec![claim(4, 10)]), &mut out);
+        // Day 5's close arrives without its claim
+        state.apply(&tx("d", vec![close(5, 0, 0)]), &mut out);
+
+        let gaps: Vec<(GapKind, u64, u64)> = out
+            .gaps
+            .iter()
+            .map(|g| (g.kind, g.expected_day, g.found_day))
+            .collect();
+        assert_eq!(
+            gaps,
+            vec![
+                (GapKind::Unclosed, 2, 4),
+                (GapKind::Missing, 3, 4),
+                (GapKind::Unclosed, 4, 5),
+                (GapKind::Missing, 5, 5),
+            ]
+        );
+
+        let days: Vec<(u64, bool)> = out
+            .days
+            .iter()
+            .map(|d| (d.day, d.closed_at.is_some()))
+            .collect();
+        assert_eq!(days, vec![(1, true), (2, false), (4, false), (5, true)]);
+        assert_eq!(state.last_day, Some(5));
+    }
+}
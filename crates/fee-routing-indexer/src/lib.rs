@@ -0,0 +1,22 @@
+//! Distribution ledger indexer for the fee-routing program.
+//!
+//! Reads program transactions from an RPC node or a local snapshot, decodes
+//! the fee-routing events with [`fee_routing_client::events`] and writes
+//! normalized per-day and per-recipient records to SQLite or CSV for finance
+//! and tax reporting. Progress is saved with every batch, so runs resume
+//! incrementally, and holes in `current_day` are recorded as gaps.
+//!
+//! - [`source`]: [`source::LedgerSource`] with RPC and snapshot implementations
+//! - [`transaction`]: the `getTransaction` JSON subset and event extraction
+//! - [`ledger`]: record types and the event-to-record state machine
+//! - [`sink`]: SQLite and CSV outputs
+//! - [`indexer`]: the fetch, apply, commit loop
+
+pub mod error;
+pub mod indexer;
+pub mod ledger;
+pub mod sink;
+pub mod source;
+pub mod transaction;
+
+pub use error::IndexerError;
@@ -0,0 +1,132 @@
+use std::{path::PathBuf, thread, time::Duration};
+
+use anyhow::{bail, Result};
+use clap::{Args, Parser, ValueEnum};
+use fee_routing_indexer::{
+    indexer::{Indexer, DEFAULT_BATCH_SIZE},
+    sink::{CsvSink, LedgerSink, SqliteSink},
+    source::{LedgerSource, RpcSource, SnapshotSource},
+};
+use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client::rpc_client::RpcClient;
+use tracing::{error, info};
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "fee-routing-indexer",
+    about = "Indexes fee-routing events into a distribution ledger"
+)]
+struct Cli {
+    #[command(flatten)]
+    source: SourceArgs,
+
+    #[command(flatten)]
+    sink: SinkArgs,
+
+    /// Transactions per committed batch
+    #[arg(long, default_value_t = DEFAULT_BATCH_SIZE)]
+    batch_size: usize,
+
+    /// `confirmed` or `finalized`
+    #[arg(long, default_value = "confirmed")]
+    commitment: String,
+
+    /// Keep polling for new transactions instead of exiting when caught up
+    #[arg(long)]
+    follow: bool,
+
+    #[arg(long, default_value_t = 30)]
+    poll_interval_secs: u64,
+
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+}
+
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+struct SourceArgs {
+    /// RPC endpoint with transaction history for the program
+    #[arg(long, short = 'u', env = "FEE_ROUTING_RPC_URL")]
+    url: Option<String>,
+
+    /// JSON Lines file of `getTransaction` results, oldest first
+    #[arg(long)]
+    snapshot: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+struct SinkArgs {
+    /// SQLite database to create or extend
+    #[arg(long)]
+    sqlite: Option<PathBuf>,
+
+    /// Directory for days.csv, payouts.csv, positions.csv, gaps.csv and state.json
+    #[arg(long)]
+    csv_dir: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum LogFormat {
+    Text,
+    Json,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    init_tracing(cli.log_format);
+
+    let source: Box<dyn LedgerSource> = match (&cli.source.url, &cli.source.snapshot) {
+        (Some(url), _) => {
+            let commitment = match cli.commitment.as_str() {
+                "confirmed" => CommitmentConfig::confirmed(),
+                "finalized" => CommitmentConfig::finalized(),
+                other => bail!("unsupported commitment {other:?}; use confirmed or finalized"),
+            };
+            let rpc = RpcClient::new_with_commitment(url.clone(), commitment);
+            Box::new(RpcSource::new(rpc, cli.commitment.clone()))
+        }
+        (None, Some(path)) => {
+            if cli.follow {
+                bail!("--follow needs --url; a snapshot doesn't grow");
+            }
+            Box::new(SnapshotSource::load(path)?)
+        }
+        (None, None) => unreachable!("clap requires a source"),
+    };
+    let sink: Box<dyn LedgerSink> = match (&cli.sink.sqlite, &cli.sink.csv_dir) {
+        (Some(path), _) => Box::new(SqliteSink::open(path)?),
+        (None, Some(dir)) => Box::new(CsvSink::open(dir)?),
+        (None, None) => unreachable!("clap requires a sink"),
+    };
+
+    let mut indexer = Indexer::new(source, sink).with_batch_size(cli.batch_size);
+    loop {
+        match indexer.run() {
+            Ok(summary) => info!(
+                transactions = summary.transactions,
+                days = summary.days,
+                payouts = summary.payouts,
+                positions = summary.positions,
+                gaps = summary.gaps,
+                "caught up"
+            ),
+            Err(err) if cli.follow => error!(error = %err, "indexing failed"),
+            Err(err) => return Err(err.into()),
+        }
+        if !cli.follow {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(cli.poll_interval_secs));
+    }
+}
+
+fn init_tracing(format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().flatten_event(true).init(),
+    }
+}
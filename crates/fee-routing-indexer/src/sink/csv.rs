@@ -0,0 +1,114 @@
+use std::{
+    fs::{self, OpenOptions},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use super::LedgerSink;
+use crate::{
+    ledger::{IndexerState, LedgerBatch},
+    IndexerError,
+};
+
+const STATE_FILE: &str = "state.json";
+
+/// Directory of `days.csv`, `payouts.csv`, `positions.csv` and `gaps.csv`
+pub struct CsvSink {
+    dir: PathBuf,
+}
+
+impl CsvSink {
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, IndexerError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Append rows, writing the header only when the file is new
+    fn append<T: Serialize>(&self, file: &str, rows: &[T]) -> Result<(), IndexerError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let path = self.dir.join(file);
+        let is_new = !path.exists();
+        let out = OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(is_new)
+            .from_writer(out);
+        for row in rows {
+            writer.serialize(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl LedgerSink for CsvSink {
+    fn load_state(&mut self) -> Result<IndexerState, IndexerError> {
+        let path = self.dir.join(STATE_FILE);
+        if !path.exists() {
+            return Ok(IndexerState::default());
+        }
+        serde_json::from_str(&fs::read_to_string(&path)?)
+            .map_err(|e| IndexerError::Storage(format!("{}: {e}", path.display())))
+    }
+
+    fn commit(&mut self, batch: &LedgerBatch, state: &IndexerState) -> Result<(), IndexerError> {
+        self.append("days.csv", &batch.days)?;
+        self.append("payouts.csv", &batch.payouts)?;
+        self.append("positions.csv", &batch.positions)?;
+        self.append("gaps.csv", &batch.gaps)?;
+
+        // Replace the state file atomically so a crash never leaves it half-written
+        let tmp = self.dir.join(format!("{STATE_FILE}.tmp"));
+        let state = serde_json::to_string_pretty(state)
+            .map_err(|e| IndexerError::Storage(e.to_string()))?;
+        fs::write(&tmp, state)?;
+        fs::rename(&tmp, self.dir.join(STATE_FILE))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::{Cursor, GapKind, GapRecord};
+
+    #[test]
+    fn test_appends_rows_and_round_trips_state() {
+        let dir = std::env::temp_dir().join(format!("fee-routing-indexer-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut sink = CsvSink::open(&dir).unwrap();
+        assert_eq!(sink.load_state().unwrap(), IndexerState::default());
+
+        let batch = LedgerBatch {
+            gaps: vec![GapRecord {
+                kind: GapKind::Missing,
+                expected_day: 3,
+                found_day: 5,
+                signature: "sig".to_string(),
+                slot: 9,
+            }],
+            ..Default::default()
+        };
+        let state = IndexerState {
+            cursor: Some(Cursor {
+                signature: "sig".to_string(),
+                slot: 9,
+            }),
+            last_day: Some(5),
+            open_day: None,
+        };
+        sink.commit(&batch, &state).unwrap();
+        sink.commit(&batch, &state).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.join("gaps.csv")).unwrap(),
+            "kind,expected_day,found_day,signature,slot\nmissing,3,5,sig,9\nmissing,3,5,sig,9\n"
+        );
+        assert!(!dir.join("days.csv").exists());
+        assert_eq!(CsvSink::open(&dir).unwrap().load_state().unwrap(), state);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,34 @@
+//! Where ledger records go.
+//!
+//! [`SqliteSink`] writes each batch and the resume state in one transaction.
+//! [`CsvSink`] appends to one CSV per record type and rewrites `state.json`
+//! afterwards; a crash between the two can repeat the last batch's rows on
+//! the next run.
+
+mod csv;
+mod sqlite;
+
+pub use self::{csv::CsvSink, sqlite::SqliteSink};
+
+use crate::{
+    ledger::{IndexerState, LedgerBatch},
+    IndexerError,
+};
+
+pub trait LedgerSink {
+    /// State saved by the last commit, or the default for a fresh ledger
+    fn load_state(&mut self) -> Result<IndexerState, IndexerError>;
+
+    /// Persist `batch` together with the state reached after it
+    fn commit(&mut self, batch: &LedgerBatch, state: &IndexerState) -> Result<(), IndexerError>;
+}
+
+impl<T: LedgerSink + ?Sized> LedgerSink for Box<T> {
+    fn load_state(&mut self) -> Result<IndexerState, IndexerError> {
+        (**self).load_state()
+    }
+
+    fn commit(&mut self, batch: &LedgerBatch, state: &IndexerState) -> Result<(), IndexerError> {
+        (**self).commit(batch, state)
+    }
+}
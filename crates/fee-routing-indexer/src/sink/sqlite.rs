@@ -0,0 +1,188 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::LedgerSink;
+use crate::{
+    ledger::{IndexerState, LedgerBatch},
+    IndexerError,
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS days (
+    day                 INTEGER PRIMARY KEY,
+    claimed_quote       INTEGER NOT NULL,
+    claim_signature     TEXT,
+    claimed_at          INTEGER,
+    pages               INTEGER NOT NULL,
+    investors_paid      INTEGER NOT NULL,
+    investor_total      INTEGER NOT NULL,
+    rounding_dust       INTEGER NOT NULL,
+    creator_amount      INTEGER NOT NULL,
+    total_locked        INTEGER NOT NULL,
+    locked_fraction_bps INTEGER NOT NULL,
+    eligible_share_bps  INTEGER NOT NULL,
+    close_signature     TEXT,
+    closed_at           INTEGER
+);
+CREATE TABLE IF NOT EXISTS payouts (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    day           INTEGER NOT NULL,
+    page_index    INTEGER,
+    kind          TEXT NOT NULL,
+    recipient     TEXT,
+    stream        TEXT,
+    locked_amount INTEGER,
+    amount        INTEGER NOT NULL,
+    paid          INTEGER NOT NULL,
+    timestamp     INTEGER NOT NULL,
+    signature     TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS payouts_day ON payouts (day);
+CREATE INDEX IF NOT EXISTS payouts_recipient ON payouts (recipient);
+CREATE TABLE IF NOT EXISTS positions (
+    position  TEXT PRIMARY KEY,
+    owner_pda TEXT NOT NULL,
+    quote_mint TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    signature TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS gaps (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind         TEXT NOT NULL,
+    expected_day INTEGER NOT NULL,
+    found_day    INTEGER NOT NULL,
+    signature    TEXT NOT NULL,
+    slot         INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS indexer_state (
+    id    INTEGER PRIMARY KEY CHECK (id = 0),
+    state TEXT NOT NULL
+);
+";
+
+/// SQLite ledger; amounts are stored as INTEGER (i64), which covers any
+/// realistic token amount
+pub struct SqliteSink {
+    conn: Connection,
+}
+
+impl SqliteSink {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, IndexerError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, IndexerError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self, IndexerError> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+fn label<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+impl LedgerSink for SqliteSink {
+    fn load_state(&mut self) -> Result<IndexerState, IndexerError> {
+        let raw: Option<String> = self
+            .conn
+            .query_row("SELECT state FROM indexer_state WHERE id = 0", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        match raw {
+            Some(raw) => serde_json::from_str(&raw)
+                .map_err(|e| IndexerError::Storage(format!("corrupt indexer state: {e}"))),
+            None => Ok(IndexerState::default()),
+        }
+    }
+
+    fn commit(&mut self, batch: &LedgerBatch, state: &IndexerState) -> Result<(), IndexerError> {
+        let tx = self.conn.transaction()?;
+        for d in &batch.days {
+            tx.execute(
+                "INSERT OR REPLACE INTO days VALUES
+                 (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                params![
+                    d.day as i64,
+                    d.claimed_quote as i64,
+                    d.claim_signature,
+                    d.claimed_at,
+                    d.pages,
+                    d.investors_paid,
+                    d.investor_total as i64,
+                    d.rounding_dust as i64,
+                    d.creator_amount as i64,
+                    d.total_locked as i64,
+                    d.locked_fraction_bps as i64,
+                    d.eligible_share_bps as i64,
+                    d.close_signature,
+                    d.closed_at,
+                ],
+            )?;
+        }
+        for p in &batch.payouts {
+            tx.execute(
+                "INSERT INTO payouts
+                 (day, page_index, kind, recipient, stream, locked_amount, amount, paid, timestamp, signature)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    p.day as i64,
+                    p.page_index,
+                    label(&p.kind),
+                    p.recipient,
+                    p.stream,
+                    p.locked_amount.map(|v| v as i64),
+                    p.amount as i64,
+                    p.paid,
+                    p.timestamp,
+                    p.signature,
+                ],
+            )?;
+        }
+        for p in &batch.positions {
+            tx.execute(
+                "INSERT OR REPLACE INTO positions VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    p.position,
+                    p.owner_pda,
+                    p.quote_mint,
+                    p.timestamp,
+                    p.signature
+                ],
+            )?;
+        }
+        for g in &batch.gaps {
+            tx.execute(
+                "INSERT INTO gaps (kind, expected_day, found_day, signature, slot)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    label(&g.kind),
+                    g.expected_day as i64,
+                    g.found_day as i64,
+                    g.signature,
+                    g.slot as i64,
+                ],
+            )?;
+        }
+        let state =
+            serde_json::to_string(state).map_err(|e| IndexerError::Storage(e.to_string()))?;
+        tx.execute(
+            "INSERT OR REPLACE INTO indexer_state (id, state) VALUES (0, ?1)",
+            params![state],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+//! Where transactions come from: a live RPC node or a local snapshot.
+
+use std::{collections::VecDeque, fs, path::Path};
+
+use fee_routing_client::PROGRAM_ID;
+use serde::Deserialize;
+use serde_json::json;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::request::RpcRequest;
+use tracing::debug;
+
+use crate::{ledger::Cursor, transaction::RawTransaction, IndexerError};
+
+/// `getSignaturesForAddress` page size (the RPC maximum)
+const SIGNATURES_PER_REQUEST: usize = 1_000;
+
+/// A source of program transactions in ledger order
+pub trait LedgerSource {
+    /// Up to `limit` transactions that landed after `cursor`, oldest first.
+    /// An empty result means the source is caught up.
+    fn fetch_after(
+        &mut self,
+        cursor: Option<&Cursor>,
+        limit: usize,
+    ) -> Result<Vec<RawTransaction>, IndexerError>;
+}
+
+impl<T: LedgerSource + ?Sized> LedgerSource for Box<T> {
+    fn fetch_after(
+        &mut self,
+        cursor: Option<&Cursor>,
+        limit: usize,
+    ) -> Result<Vec<RawTransaction>, IndexerError> {
+        (**self).fetch_after(cursor, limit)
+    }
+}
+
+/// Reads program history from an RPC node
+///
+/// Requires a node that keeps transaction history back to the cursor (or to
+/// program deployment for a first run).
+pub struct RpcSource {
+    rpc: RpcClient,
+    /// `confirmed` or `finalized`; `getTransaction` doesn't accept `processed`
+    commitment: String,
+    /// Signatures listed but not fetched yet, oldest first
+    pending: VecDeque<String>,
+}
+
+#[derive(Deserialize)]
+struct SignatureInfo {
+    signature: String,
+}
+
+impl RpcSource {
+    pub fn new(rpc: RpcClient, commitment: impl Into<String>) -> Self {
+        Self {
+            rpc,
+            commitment: commitment.into(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Every program signature newer than `until`, oldest first
+    fn list_signatures(&self, until: Option<&str>) -> Result<VecDeque<String>, IndexerError> {
+        let mut newest_first = Vec::new();
+        let mut before: Option<String> = None;
+        loop {
+            let page: Vec<SignatureInfo> = self
+                .rpc
+                .send(
+                    RpcRequest::GetSignaturesForAddress,
+                    json!([
+                        PROGRAM_ID.to_string(),
+                        {
+                            "limit": SIGNATURES_PER_REQUEST,
+                            "before": before,
+                            "until": until,
+                            "commitment": self.commitment,
+                        }
+                    ]),
+                )
+                .map_err(|e| IndexerError::Rpc(e.to_string()))?;
+            let done = page.len() < SIGNATURES_PER_REQUEST;
+            before = page.last().map(|info| info.signature.clone());
+            newest_first.extend(page.into_iter().map(|info| info.signature));
+            if done {
+                break;
+            }
+        }
+        debug!(count = newest_first.len(), "listed new signatures");
+        Ok(newest_first.into_iter().rev().collect())
+    }
+
+    fn fetch_transaction(&self, signature: &str) -> Result<RawTransaction, IndexerError> {
+        let tx: Option<RawTransaction> = self
+            .rpc
+            .send(
+                RpcRequest::GetTransaction,
+                json!([
+                    signature,
+                    {
+                        "encoding": "json",
+                        "maxSupportedTransactionVersion": 0,
+                        "commitment": self.commitment,
+                    }
+                ]),
+            )
+            .map_err(|e| IndexerError::Rpc(e.to_string()))?;
+        tx.ok_or_else(|| IndexerError::Rpc(format!("transaction {signature} not found")))
+    }
+}
+
+impl LedgerSource for RpcSource {
+    fn fetch_after(
+        &mut self,
+        cursor: Option<&Cursor>,
+        limit: usize,
+    ) -> Result<Vec<RawTransaction>, IndexerError> {
+        if self.pending.is_empty() {
+            self.pending = self.list_signatures(cursor.map(|c| c.signature.as_str()))?;
+        }
+        // Only drop signatures once fetched, so a failed fetch is retried
+        let transactions = self
+            .pending
+            .iter()
+            .take(limit)
+            .map(|signature| self.fetch_transaction(signature))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.pending.drain(..transactions.len());
+        Ok(transactions)
+    }
+}
+
+/// Reads a JSON Lines file of `getTransaction` results (`"encoding": "json"`),
+/// oldest first, e.g. exported from an archive node or a Geyser plugin
+pub struct SnapshotSource {
+    transactions: Vec<RawTransaction>,
+}
+
+impl SnapshotSource {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, IndexerError> {
+        let path = path.as_ref();
+        let raw = fs::read_to_string(path)
+            .map_err(|e| IndexerError::Snapshot(format!("{}: {e}", path.display())))?;
+        Self::from_json_lines(&raw)
+    }
+
+    pub fn from_json_lines(raw: &str) -> Result<Self, IndexerError> {
+        let mut transactions = raw
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| IndexerError::Snapshot(format!("line {}: {e}", i + 1)))
+            })
+            .collect::<Result<Vec<RawTransaction>, _>>()?;
+        // Stable, so transactions within a slot keep their file order
+        transactions.sort_by_key(|tx| tx.slot);
+        Ok(Self { transactions })
+    }
+}
+
+impl LedgerSource for SnapshotSource {
+    fn fetch_after(
+        &mut self,
+        cursor: Option<&Cursor>,
+        limit: usize,
+    ) -> Result<Vec<RawTransaction>, IndexerError> {
+        let start = match cursor {
+            None => 0,
+            Some(cursor) => {
+                self.transactions
+                    .iter()
+                    .position(|tx| tx.signature() == cursor.signature)
+                    .ok_or_else(|| IndexerError::CursorNotFound(cursor.signature.clone()))?
+                    + 1
+            }
+        };
+        Ok(self
+            .transactions
+            .iter()
+            .skip(start)
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::tests::cpi_transaction;
+
+    #[test]
+    fn test_snapshot_orders_and_resumes() {
+        let raw = [
+            cpi_transaction("c", 30, &[]),
+            cpi_transaction("a", 10, &[]),
+            String::new(),
+            cpi_transaction("b", 20, &[]),
+        ]
+        .join("\n");
+        let mut source = SnapshotSource::from_json_lines(&raw).unwrap();
+
+        let first: Vec<String> = source
+            .fetch_after(None, 2)
+            .unwrap()
+            .iter()
+            .map(|tx| tx.signature().to_string())
+            .collect();
+        assert_eq!(first, vec!["a", "b"]);
+
+        let cursor = Cursor {
+            signature: "b".to_string(),
+            slot: 20,
+        };
+        let rest = source.fetch_after(Some(&cursor), 10).unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].signature(), "c");
+
+        let unknown = Cursor {
+            signature: "zzz".to_string(),
+            slot: 0,
+        };
+        assert!(matches!(
+            source.fetch_after(Some(&unknown), 10),
+            Err(IndexerError::CursorNotFound(_))
+        ));
+    }
+}
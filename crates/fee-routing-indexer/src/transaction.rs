@@ -0,0 +1,267 @@
+//! The subset of a `getTransaction` response the indexer reads.
+//!
+//! Responses are requested with `"encoding": "json"`, so inner instruction
+//! data is base58 and program ids are indexes into the static account keys
+//! followed by any lookup-table-loaded addresses. Snapshots store the same
+//! JSON, one transaction per line.
+
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use fee_routing_client::{
+    events::{decode_logs, FeeRoutingEvent},
+    PROGRAM_ID,
+};
+use serde::Deserialize;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawTransaction {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub transaction: RawTransactionBody,
+    pub meta: Option<RawMeta>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawTransactionBody {
+    pub signatures: Vec<String>,
+    pub message: RawMessage,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawMessage {
+    pub account_keys: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawMeta {
+    pub err: Option<serde_json::Value>,
+    #[serde(default)]
+    pub log_messages: Option<Vec<String>>,
+    #[serde(default)]
+    pub inner_instructions: Option<Vec<RawInnerInstructions>>,
+    #[serde(default)]
+    pub loaded_addresses: Option<RawLoadedAddresses>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RawInnerInstructions {
+    pub instructions: Vec<RawInstruction>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawInstruction {
+    pub program_id_index: usize,
+    /// Base58 instruction data
+    pub data: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct RawLoadedAddresses {
+    #[serde(default)]
+    pub writable: Vec<String>,
+    #[serde(default)]
+    pub readonly: Vec<String>,
+}
+
+/// A transaction reduced to what the ledger needs
+#[derive(Clone, Debug)]
+pub struct IndexedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// False for failed transactions, whose events never took effect
+    pub succeeded: bool,
+    /// Fee-routing events in emission order
+    pub events: Vec<FeeRoutingEvent>,
+}
+
+impl RawTransaction {
+    pub fn signature(&self) -> &str {
+        self.transaction
+            .signatures
+            .first()
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    /// Static keys, then loaded writable, then loaded readonly addresses
+    fn account_keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self
+            .transaction
+            .message
+            .account_keys
+            .iter()
+            .map(String::as_str)
+            .collect();
+        if let Some(loaded) = self.meta.as_ref().and_then(|m| m.loaded_addresses.as_ref()) {
+            keys.extend(loaded.writable.iter().map(String::as_str));
+            keys.extend(loaded.readonly.iter().map(String::as_str));
+        }
+        keys
+    }
+
+    /// Decode events from self-CPI inner instructions and `Program data:` logs
+    pub fn index(&self) -> IndexedTransaction {
+        let meta = self.meta.clone().unwrap_or_default();
+        let succeeded = meta.err.is_none();
+        let mut events = Vec::new();
+
+        if succeeded {
+            let keys = self.account_keys();
+            let is_program = |index: usize| {
+                keys.get(index)
+                    .and_then(|key| Pubkey::from_str(key).ok())
+                    .is_some_and(|key| key == PROGRAM_ID)
+            };
+            for inner in meta.inner_instructions.iter().flatten() {
+                for ix in &inner.instructions {
+                    if !is_program(ix.program_id_index) {
+                        continue;
+                    }
+                    let Ok(data) = bs58::decode(&ix.data).into_vec() else {
+                        continue;
+                    };
+                    events.extend(FeeRoutingEvent::from_cpi_data(&data));
+                }
+            }
+            // Builds with the `log-events` feature emit through logs instead,
+            // counted only inside the program's own frames: the transaction
+            // merely lists the program, and any other program can log
+            events.extend(decode_logs(
+                meta.log_messages.as_deref().unwrap_or_default(),
+            ));
+        }
+
+        IndexedTransaction {
+            signature: self.signature().to_string(),
+            slot: self.slot,
+            block_time: self.block_time,
+            succeeded,
+            events,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use anchor_lang::{event::EVENT_IX_TAG_LE, Event};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use fee_routing_client::events::QuoteFeesClaimed;
+    use serde_json::json;
+
+    /// `getTransaction` JSON carrying `events` as self-CPI inner instructions
+    pub(crate) fn cpi_transaction(signature: &str, slot: u64, events: &[Vec<u8>]) -> String {
+        let instructions: Vec<_> = events
+            .iter()
+            .map(|event| {
+                let mut data = EVENT_IX_TAG_LE.to_vec();
+                data.extend(event);
+                json!({
+                    "programIdIndex": 2,
+                    "accounts": [1],
+                    "data": bs58::encode(data).into_string(),
+                    "stackHeight": 2
+                })
+            })
+            .collect();
+        json!({
+            "slot": slot,
+            "blockTime": 1_700_000_000 + slot as i64,
+            "transaction": {
+                "signatures": [signature],
+                "message": {
+                    "accountKeys": [Pubkey::new_unique().to_string()],
+                    "instructions": []
+                }
+            },
+            "meta": {
+                "err": null,
+                "logMessages": [],
+                "innerInstructions": [{"index": 0, "instructions": instructions}],
+                "loadedAddresses": {
+                    "writable": [Pubkey::new_unique().to_string()],
+                    "readonly": [PROGRAM_ID.to_string()]
+                }
+            },
+            "version": 0
+        })
+        .to_string()
+    }
+
+    fn claimed(day: u64) -> QuoteFeesClaimed {
+        QuoteFeesClaimed {
+            amount: 1_000,
+            timestamp: 1_700_000_000,
+            distribution_day: day,
+        }
+    }
+
+    #[test]
+    fn test_index_cpi_events_through_loaded_addresses() {
+        let raw: RawTransaction =
+            serde_json::from_str(&cpi_transaction("sig", 7, &[claimed(3).data()])).unwrap();
+        let indexed = raw.index();
+
+        assert_eq!(indexed.signature, "sig");
+        assert!(indexed.succeeded);
+        assert!(matches!(
+            indexed.events.as_slice(),
+            [FeeRoutingEvent::QuoteFeesClaimed(e)] if e.distribution_day == 3
+        ));
+    }
+
+    /// `getTransaction` JSON whose only logs are `logs`
+    fn log_transaction(logs: Vec<String>) -> RawTransaction {
+        serde_json::from_value(json!({
+            "slot": 1,
+            "blockTime": null,
+            "transaction": {"signatures": ["a"], "message": {"accountKeys": []}},
+            "meta": {"err": null, "logMessages": logs}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_index_log_events_and_skip_failed() {
+        let line = format!("Program data: {}", STANDARD.encode(claimed(1).data()));
+        let mut raw = log_transaction(vec![
+            format!("Program {PROGRAM_ID} invoke [1]"),
+            line,
+            format!("Program {PROGRAM_ID} success"),
+        ]);
+        assert_eq!(raw.index().events.len(), 1);
+
+        raw.meta.as_mut().unwrap().err = Some(json!({"InstructionError": [0, "Custom"]}));
+        let indexed = raw.index();
+        assert!(!indexed.succeeded);
+        assert!(indexed.events.is_empty());
+    }
+
+    #[test]
+    fn test_index_ignores_forged_log_events() {
+        // Another program in a transaction that lists fee-routing (so it is
+        // returned for the program's address) logs a fake day close
+        let forger = Pubkey::new_unique();
+        let line = format!("Program data: {}", STANDARD.encode(claimed(1).data()));
+        let raw = log_transaction(vec![
+            format!("Program {forger} invoke [1]"),
+            line.clone(),
+            format!("Program {forger} success"),
+            format!("Program {PROGRAM_ID} invoke [1]"),
+            format!("Program {forger} invoke [2]"),
+            line,
+            format!("Program {forger} success"),
+            format!("Program {PROGRAM_ID} success"),
+        ]);
+        let indexed = raw.index();
+        assert!(indexed.succeeded);
+        assert!(indexed.events.is_empty());
+    }
+}
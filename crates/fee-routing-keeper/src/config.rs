@@ -0,0 +1,256 @@
+//! Keeper configuration, loaded from TOML.
+//!
+//! ```toml
+//! rpc_url = "http://127.0.0.1:8899"
+//! keypair = "/home/keeper/.config/solana/keeper.json"
+//! lookup_tables = ["<table address>"]
+//!
+//! [accounts]
+//! vault = "..."
+//! pool = "..."
+//! # remaining DistributeFeesAccounts fields, caller excluded
+//!
+//! [[investors]]
+//! stream = "..."
+//! ata = "..."
+//! ```
+
+use std::{path::Path, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use fee_routing_client::instructions::{DistributeFeesAccounts, InvestorAccounts};
+use serde::{Deserialize, Deserializer};
+use solana_commitment_config::CommitmentConfig;
+
+use crate::KeeperError;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeeperConfig {
+    pub rpc_url: String,
+    /// Path to the keypair that signs and pays for crank transactions
+    pub keypair: String,
+    #[serde(default = "default_commitment")]
+    pub commitment: String,
+    /// Upper bound on how long the daemon sleeps between checks
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// Priority fee in micro-lamports per compute unit
+    #[serde(default)]
+    pub compute_unit_price: Option<u64>,
+    /// Attempts per page before giving up on the tick
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before retry `n` is `n * retry_backoff_ms`
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    /// Stop after this many pages per tick (the rest resume on the next tick)
+    #[serde(default)]
+    pub max_pages_per_tick: Option<usize>,
+    /// Lookup tables holding the crank accounts; empty sends legacy transactions
+    #[serde(default, deserialize_with = "pubkeys")]
+    pub lookup_tables: Vec<Pubkey>,
+    pub accounts: AccountsConfig,
+    #[serde(default)]
+    pub investors: Vec<InvestorConfig>,
+}
+
+/// `DistributeFeesAccounts` without the caller, which is the keeper keypair
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountsConfig {
+    #[serde(deserialize_with = "pubkey")]
+    pub vault: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub pool: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub position: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub position_nft_account: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub treasury_token_a: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub treasury_token_b: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub pool_token_a_vault: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub pool_token_b_vault: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub token_a_mint: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub token_b_mint: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub token_a_program: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub token_b_program: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub creator_ata: Pubkey,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InvestorConfig {
+    #[serde(deserialize_with = "pubkey")]
+    pub stream: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub ata: Pubkey,
+}
+
+impl KeeperConfig {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, KeeperError> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| KeeperError::Config(format!("{}: {e}", path.display())))?;
+        Self::from_toml(&raw)
+    }
+
+    pub fn from_toml(raw: &str) -> Result<Self, KeeperError> {
+        let config: Self = toml::from_str(raw).map_err(|e| KeeperError::Config(e.to_string()))?;
+        config.commitment_config()?;
+        Ok(config)
+    }
+
+    pub fn commitment_config(&self) -> Result<CommitmentConfig, KeeperError> {
+        CommitmentConfig::from_str(&self.commitment)
+            .map_err(|_| KeeperError::Config(format!("unknown commitment {:?}", self.commitment)))
+    }
+
+    pub fn distribute_accounts(&self, caller: Pubkey) -> DistributeFeesAccounts {
+        let a = &self.accounts;
+        DistributeFeesAccounts {
+            caller,
+            vault: a.vault,
+            pool: a.pool,
+            position: a.position,
+            position_nft_account: a.position_nft_account,
+            treasury_token_a: a.treasury_token_a,
+            treasury_token_b: a.treasury_token_b,
+            pool_token_a_vault: a.pool_token_a_vault,
+            pool_token_b_vault: a.pool_token_b_vault,
+            token_a_mint: a.token_a_mint,
+            token_b_mint: a.token_b_mint,
+            token_a_program: a.token_a_program,
+            token_b_program: a.token_b_program,
+            creator_ata: a.creator_ata,
+        }
+    }
+
+    pub fn investor_accounts(&self) -> Vec<InvestorAccounts> {
+        self.investors
+            .iter()
+            .map(|inv| InvestorAccounts {
+                stream: inv.stream,
+                ata: inv.ata,
+            })
+            .collect()
+    }
+}
+
+fn default_commitment() -> String {
+    "confirmed".to_string()
+}
+
+fn default_poll_interval_secs() -> u64 {
+    60
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    2_000
+}
+
+fn pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+    let raw = String::deserialize(deserializer)?;
+    Pubkey::from_str(&raw).map_err(serde::de::Error::custom)
+}
+
+fn pubkeys<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Pubkey>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|raw| Pubkey::from_str(raw).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> String {
+        let key = || Pubkey::new_unique().to_string();
+        format!(
+            r#"
+rpc_url = "http://127.0.0.1:8899"
+keypair = "keeper.json"
+lookup_tables = ["{}"]
+
+[accounts]
+vault = "{}"
+pool = "{}"
+position = "{}"
+position_nft_account = "{}"
+treasury_token_a = "{}"
+treasury_token_b = "{}"
+pool_token_a_vault = "{}"
+pool_token_b_vault = "{}"
+token_a_mint = "{}"
+token_b_mint = "{}"
+token_a_program = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+token_b_program = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+creator_ata = "{}"
+
+[[investors]]
+stream = "{}"
+ata = "{}"
+"#,
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key()
+        )
+    }
+
+    #[test]
+    fn test_parses_config_with_defaults() {
+        let config = KeeperConfig::from_toml(&sample()).unwrap();
+
+        assert_eq!(
+            config.commitment_config().unwrap(),
+            CommitmentConfig::confirmed()
+        );
+        assert_eq!(config.max_attempts, 5);
+        assert_eq!(config.lookup_tables.len(), 1);
+        assert_eq!(config.investor_accounts().len(), 1);
+
+        let caller = Pubkey::new_unique();
+        assert_eq!(config.distribute_accounts(caller).caller, caller);
+    }
+
+    #[test]
+    fn test_rejects_bad_pubkey_and_commitment() {
+        // '0', 'O', 'I' and 'l' are not base58
+        let bad_key = sample().replace("creator_ata = \"", "creator_ata = \"0OIl");
+        assert!(KeeperConfig::from_toml(&bad_key).is_err());
+
+        let bad_commitment = sample().replace(
+            "keypair = \"keeper.json\"",
+            "keypair = \"keeper.json\"\ncommitment = \"eventually\"",
+        );
+        assert!(matches!(
+            KeeperConfig::from_toml(&bad_commitment),
+            Err(KeeperError::Config(_))
+        ));
+    }
+}
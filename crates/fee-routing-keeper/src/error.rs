@@ -0,0 +1,37 @@
+use anchor_lang::prelude::Pubkey;
+use fee_routing_client::{lookup_table::LookupTableError, planner::PlanError};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum KeeperError {
+    #[error("rpc error: {0}")]
+    Rpc(String),
+
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+
+    #[error("failed to decode account {0}")]
+    Decode(Pubkey),
+
+    #[error(transparent)]
+    Plan(#[from] PlanError),
+
+    #[error(transparent)]
+    LookupTable(#[from] LookupTableError),
+
+    #[error("failed to sign transaction: {0}")]
+    Signing(String),
+
+    #[error("progress is at page {current_page} but the plan only has {planned} pages; investor set changed mid-day?")]
+    PlanMismatch { current_page: u16, planned: usize },
+
+    #[error("page {page_index} not confirmed after {attempts} attempts: {last_error}")]
+    RetriesExhausted {
+        page_index: u16,
+        attempts: u32,
+        last_error: String,
+    },
+
+    #[error("invalid config: {0}")]
+    Config(String),
+}
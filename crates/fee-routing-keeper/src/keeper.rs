@@ -0,0 +1,751 @@
+//! Crank scheduling and page submission.
+
+use std::{thread, time::Duration};
+
+use anchor_lang::prelude::Pubkey;
+use fee_routing_client::{
+    accounts::{
+        decode_investor_ledger, decode_policy, decode_progress, next_window_ts, Policy, Progress,
+    },
+    instructions::{DistributeFeesAccounts, InvestorAccounts},
+    lookup_table::decode_lookup_table,
+    pda,
+    planner::{plan_pages, PlannedPage, PlannerConfig},
+};
+use solana_keypair::Keypair;
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_transaction::versioned::VersionedTransaction;
+use tracing::{info, warn};
+
+use crate::{config::KeeperConfig, rpc::KeeperRpc, KeeperError};
+
+/// What the keeper should do given the current `Progress` and cluster time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Nothing to do until the next window opens
+    Wait { until: i64 },
+    /// The window is open: claim and run a new day from page 0
+    StartDay,
+    /// A day is open but unfinished: continue from `page`
+    Resume { page: u16 },
+}
+
+pub fn next_action(progress: &Progress, now: i64) -> Action {
+    let window_opens = next_window_ts(progress);
+    if now >= window_opens {
+        Action::StartDay
+    } else if progress.current_page > 0 && !progress.creator_payout_sent {
+        Action::Resume {
+            page: progress.current_page,
+        }
+    } else {
+        Action::Wait {
+            until: window_opens,
+        }
+    }
+}
+
+/// Retry behaviour for a single page
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    /// Delay before retry `n` is `n * backoff`
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Result of one keeper tick
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TickOutcome {
+    Waiting { until: i64 },
+    Ran(DaySummary),
+}
+
+/// What a tick submitted, plus the resulting day totals from `Progress`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DaySummary {
+    pub day: u64,
+    pub first_page: u16,
+    pub pages_planned: usize,
+    pub signatures: Vec<Signature>,
+    pub day_complete: bool,
+    pub claimed_quote: u64,
+    pub distributed_to_investors: u64,
+    pub carry_over: u64,
+}
+
+pub struct Keeper<R> {
+    rpc: R,
+    payer: Keypair,
+    accounts: DistributeFeesAccounts,
+    investors: Vec<InvestorAccounts>,
+    lookup_tables: Vec<Pubkey>,
+    planner: PlannerConfig,
+    retry: RetryPolicy,
+    max_pages_per_tick: Option<usize>,
+}
+
+impl<R: KeeperRpc> Keeper<R> {
+    /// `accounts.caller` is replaced by the payer's pubkey
+    pub fn new(
+        rpc: R,
+        payer: Keypair,
+        mut accounts: DistributeFeesAccounts,
+        investors: Vec<InvestorAccounts>,
+    ) -> Self {
+        accounts.caller = payer.pubkey();
+        Self {
+            rpc,
+            payer,
+            accounts,
+            investors,
+            lookup_tables: Vec::new(),
+            planner: PlannerConfig::default(),
+            retry: RetryPolicy::default(),
+            max_pages_per_tick: None,
+        }
+    }
+
+    /// Keeper configured from a [`KeeperConfig`]
+    pub fn from_config(rpc: R, payer: Keypair, config: &KeeperConfig) -> Self {
+        Self::new(
+            rpc,
+            payer,
+            config.distribute_accounts(Pubkey::default()),
+            config.investor_accounts(),
+        )
+        .with_lookup_tables(config.lookup_tables.clone())
+        .with_planner(PlannerConfig {
+            compute_unit_price: config.compute_unit_price,
+            ..Default::default()
+        })
+        .with_retry(RetryPolicy {
+            max_attempts: config.max_attempts,
+            backoff: Duration::from_millis(config.retry_backoff_ms),
+        })
+        .with_max_pages_per_tick(config.max_pages_per_tick)
+    }
+
+    pub fn with_lookup_tables(mut self, tables: Vec<Pubkey>) -> Self {
+        self.lookup_tables = tables;
+        self
+    }
+
+    pub fn with_planner(mut self, planner: PlannerConfig) -> Self {
+        self.planner = planner;
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_max_pages_per_tick(mut self, max_pages: Option<usize>) -> Self {
+        self.max_pages_per_tick = max_pages;
+        self
+    }
+
+    pub fn rpc(&self) -> &R {
+        &self.rpc
+    }
+
+    pub fn fetch_progress(&self) -> Result<Progress, KeeperError> {
+        let key = pda::progress().0;
+        let data = self
+            .rpc
+            .get_account_data(&key)?
+            .ok_or(KeeperError::AccountNotFound(key))?;
+        decode_progress(&data).map_err(|_| KeeperError::Decode(key))
+    }
+
+    pub fn fetch_policy(&self) -> Result<Policy, KeeperError> {
+        let key = pda::policy().0;
+        let data = self
+            .rpc
+            .get_account_data(&key)?
+            .ok_or(KeeperError::AccountNotFound(key))?;
+        decode_policy(&data).map_err(|_| KeeperError::Decode(key))
+    }
+
+    /// Plan the pages of a day opening now against the current lookup tables
+    pub fn plan(&self) -> Result<(PlannerConfig, Vec<PlannedPage>), KeeperError> {
+        let progress = self.fetch_progress()?;
+        let now = self.rpc.get_unix_timestamp()?;
+        let (investors, _) = self.day_investors(&progress, now)?;
+        self.plan_investors(&investors)
+    }
+
+    /// Investors a day opened at `day_start` pages through, and whether it
+    /// runs after sunset
+    ///
+    /// After sunset the program no longer reads streams, so only investors
+    /// with an owed balance left to flush are worth a page.
+    fn day_investors(
+        &self,
+        progress: &Progress,
+        day_start: i64,
+    ) -> Result<(Vec<InvestorAccounts>, bool), KeeperError> {
+        let policy = self.fetch_policy()?;
+        if !policy.sunset(progress.fully_vested_at, day_start) {
+            return Ok((self.investors.clone(), false));
+        }
+
+        let mut owed = Vec::new();
+        for investor in &self.investors {
+            let key = investor.ledger();
+            let data = self
+                .rpc
+                .get_account_data(&key)?
+                .ok_or(KeeperError::AccountNotFound(key))?;
+            let ledger = decode_investor_ledger(&data).map_err(|_| KeeperError::Decode(key))?;
+            if ledger.owed > 0 {
+                owed.push(*investor);
+            }
+        }
+        Ok((owed, true))
+    }
+
+    fn plan_investors(
+        &self,
+        investors: &[InvestorAccounts],
+    ) -> Result<(PlannerConfig, Vec<PlannedPage>), KeeperError> {
+        let mut planner = self.planner.clone();
+        planner.lookup_tables = self
+            .lookup_tables
+            .iter()
+            .map(|key| {
+                let data = self
+                    .rpc
+                    .get_account_data(key)?
+                    .ok_or(KeeperError::AccountNotFound(*key))?;
+                Ok(decode_lookup_table(*key, &data)?)
+            })
+            .collect::<Result<_, KeeperError>>()?;
+
+        let pages = plan_pages(&self.accounts, investors, &planner)?;
+        Ok((planner, pages))
+    }
+
+    /// Check the schedule once and run whatever pages are due
+    pub fn tick(&self) -> Result<TickOutcome, KeeperError> {
+        let progress = self.fetch_progress()?;
+        let now = self.rpc.get_unix_timestamp()?;
+
+        match next_action(&progress, now) {
+            Action::Wait { until } => Ok(TickOutcome::Waiting { until }),
+            Action::StartDay => {
+                if progress.current_page > 0 && !progress.creator_payout_sent {
+                    warn!(
+                        day = progress.current_day,
+                        current_page = progress.current_page,
+                        "previous day never closed; window elapsed, starting a new day"
+                    );
+                }
+                self.run_pages(&progress, 0, progress.current_day + 1, now)
+                    .map(TickOutcome::Ran)
+            }
+            Action::Resume { page } => {
+                info!(day = progress.current_day, page, "resuming open day");
+                self.run_pages(
+                    &progress,
+                    page,
+                    progress.current_day,
+                    progress.last_distribution_ts,
+                )
+                .map(TickOutcome::Ran)
+            }
+        }
+    }
+
+    fn run_pages(
+        &self,
+        progress: &Progress,
+        first_page: u16,
+        day: u64,
+        day_start: i64,
+    ) -> Result<DaySummary, KeeperError> {
+        let (investors, sunset) = self.day_investors(progress, day_start)?;
+        let (planner, mut pages) = self.plan_investors(&investors)?;
+        if sunset {
+            // Owed balances paid on earlier pages have dropped out, so the
+            // rest of the day is planned afresh from `first_page`
+            for page in &mut pages {
+                page.page_index += first_page;
+            }
+        } else if first_page as usize >= pages.len() {
+            return Err(KeeperError::PlanMismatch {
+                current_page: first_page,
+                planned: pages.len(),
+            });
+        } else {
+            pages.drain(..first_page as usize);
+        }
+        let pages_planned = first_page as usize + pages.len();
+
+        info!(
+            day,
+            first_page,
+            pages = pages_planned,
+            investors = investors.len(),
+            sunset,
+            lookup_tables = planner.lookup_tables.len(),
+            "running crank"
+        );
+
+        let limit = self.max_pages_per_tick.unwrap_or(usize::MAX);
+        let mut signatures = Vec::new();
+        for page in pages.iter().take(limit) {
+            let signature = self.submit_page(page, &planner, day)?;
+            info!(
+                day,
+                page = page.page_index,
+                is_final_page = page.is_final_page,
+                investors = page.investors.len(),
+                compute_unit_limit = page.compute_unit_limit,
+                transaction_size = page.transaction_size,
+                %signature,
+                "page confirmed"
+            );
+            signatures.push(signature);
+        }
+
+        let progress = self.fetch_progress()?;
+        // Buckets that overflow can only come from a corrupt account
+        let carry_over = progress
+            .buckets
+            .carry_over()
+            .map_err(|_| KeeperError::Decode(pda::progress().0))?;
+        let summary = DaySummary {
+            day,
+            first_page,
+            pages_planned,
+            signatures,
+            day_complete: progress.current_day == day && progress.creator_payout_sent,
+            claimed_quote: progress.day_claimed_quote,
+            distributed_to_investors: progress.daily_distributed_to_investors,
+            carry_over,
+        };
+        info!(
+            day = summary.day,
+            pages_sent = summary.signatures.len(),
+            day_complete = summary.day_complete,
+            claimed_quote = summary.claimed_quote,
+            distributed_to_investors = summary.distributed_to_investors,
+            carry_over = summary.carry_over,
+            "crank summary"
+        );
+        Ok(summary)
+    }
+
+    /// Submit one page, retrying until confirmed
+    ///
+    /// A send can fail after the transaction actually landed (e.g. a confirmation
+    /// timeout), so after each failure `Progress` is re-read and the page counts
+    /// as done if the cursor moved past it.
+    fn submit_page(
+        &self,
+        page: &PlannedPage,
+        planner: &PlannerConfig,
+        day: u64,
+    ) -> Result<Signature, KeeperError> {
+        let mut last_error = String::new();
+
+        for attempt in 1..=self.retry.max_attempts {
+            match self.send_page(page, planner) {
+                Ok(signature) => return Ok(signature),
+                Err((signature, err)) => {
+                    warn!(page = page.page_index, attempt, error = %err, "page submission failed");
+                    last_error = err.to_string();
+
+                    if let Ok(progress) = self.fetch_progress() {
+                        if page_landed(&progress, day, page.page_index) {
+                            if let Some(signature) = signature {
+                                info!(page = page.page_index, "page landed despite error");
+                                return Ok(signature);
+                            }
+                        }
+                    }
+                }
+            }
+
+            if attempt < self.retry.max_attempts {
+                thread::sleep(self.retry.backoff * attempt);
+            }
+        }
+
+        Err(KeeperError::RetriesExhausted {
+            page_index: page.page_index,
+            attempts: self.retry.max_attempts,
+            last_error,
+        })
+    }
+
+    fn send_page(
+        &self,
+        page: &PlannedPage,
+        planner: &PlannerConfig,
+    ) -> Result<Signature, (Option<Signature>, KeeperError)> {
+        let blockhash = self.rpc.get_latest_blockhash().map_err(|e| (None, e))?;
+        let message = page
+            .message(&self.accounts, planner, blockhash)
+            .map_err(|e| (None, KeeperError::Plan(e.into())))?;
+        let tx = VersionedTransaction::try_new(message, &[&self.payer])
+            .map_err(|e| (None, KeeperError::Signing(e.to_string())))?;
+
+        let signature = tx.signatures[0];
+        self.rpc
+            .send_and_confirm(&tx)
+            .map_err(|e| (Some(signature), e))
+    }
+}
+
+/// Whether `page_index` of `day` has already been applied on chain
+pub fn page_landed(progress: &Progress, day: u64, page_index: u16) -> bool {
+    progress.current_day == day && progress.current_page > page_index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{solana_program::hash::Hash, AccountSerialize, AnchorDeserialize};
+    use anchor_spl::token;
+    use fee_routing_client::accounts::InvestorLedger;
+    use std::{
+        cell::{Cell, RefCell},
+        collections::{HashMap, VecDeque},
+    };
+
+    const DAY: i64 = 86_400;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Failure {
+        /// Rejected before landing
+        Reject,
+        /// Lands, but the client sees an error (e.g. confirmation timeout)
+        LandThenError,
+    }
+
+    struct MockRpc {
+        progress: RefCell<Progress>,
+        policy: RefCell<Policy>,
+        ledgers: RefCell<HashMap<Pubkey, InvestorLedger>>,
+        now: Cell<i64>,
+        sent: RefCell<Vec<(u16, bool)>>,
+        failures: RefCell<VecDeque<Failure>>,
+    }
+
+    impl MockRpc {
+        fn new(progress: Progress, now: i64) -> Self {
+            Self {
+                progress: RefCell::new(progress),
+                policy: RefCell::new(policy()),
+                ledgers: RefCell::new(HashMap::new()),
+                now: Cell::new(now),
+                sent: RefCell::new(Vec::new()),
+                failures: RefCell::new(VecDeque::new()),
+            }
+        }
+
+        /// Minimal model of the program's page sequencing
+        fn apply(&self, page_index: u16, is_final_page: bool) -> Result<(), KeeperError> {
+            let mut progress = self.progress.borrow_mut();
+            let now = self.now.get();
+            let is_new_day = now >= next_window_ts(&progress);
+            if page_index == 0 {
+                if !is_new_day {
+                    return Err(KeeperError::Rpc("DistributionWindowNotElapsed".into()));
+                }
+                progress.current_day += 1;
+                progress.last_distribution_ts = now;
+                progress.current_page = 0;
+                progress.creator_payout_sent = false;
+            } else if is_new_day || page_index != progress.current_page {
+                return Err(KeeperError::Rpc("InvalidPageIndex".into()));
+            }
+            progress.current_page += 1;
+            if is_final_page {
+                progress.creator_payout_sent = true;
+            }
+            Ok(())
+        }
+    }
+
+    impl KeeperRpc for MockRpc {
+        fn get_account_data(&self, key: &Pubkey) -> Result<Option<Vec<u8>>, KeeperError> {
+            let mut data = Vec::new();
+            if *key == pda::progress().0 {
+                self.progress.borrow().try_serialize(&mut data).unwrap();
+            } else if *key == pda::policy().0 {
+                self.policy.borrow().try_serialize(&mut data).unwrap();
+            } else if let Some(ledger) = self.ledgers.borrow().get(key) {
+                ledger.try_serialize(&mut data).unwrap();
+            } else {
+                return Ok(None);
+            }
+            Ok(Some(data))
+        }
+
+        fn get_unix_timestamp(&self) -> Result<i64, KeeperError> {
+            Ok(self.now.get())
+        }
+
+        fn get_latest_blockhash(&self) -> Result<Hash, KeeperError> {
+            Ok(Hash::new_unique())
+        }
+
+        fn send_and_confirm(&self, tx: &VersionedTransaction) -> Result<Signature, KeeperError> {
+            let keys = tx.message.static_account_keys();
+            let ix = tx
+                .message
+                .instructions()
+                .iter()
+                .find(|ix| keys[ix.program_id_index as usize] == fee_routing::ID)
+                .unwrap();
+            let args =
+                fee_routing::instruction::DistributeFees::try_from_slice(&ix.data[8..]).unwrap();
+
+            match self.failures.borrow_mut().pop_front() {
+                Some(Failure::Reject) => return Err(KeeperError::Rpc("blockhash expired".into())),
+                Some(Failure::LandThenError) => {
+                    self.apply(args.page_index, args.is_final_page)?;
+                    self.sent
+                        .borrow_mut()
+                        .push((args.page_index, args.is_final_page));
+                    return Err(KeeperError::Rpc("confirmation timed out".into()));
+                }
+                None => {}
+            }
+
+            self.apply(args.page_index, args.is_final_page)?;
+            self.sent
+                .borrow_mut()
+                .push((args.page_index, args.is_final_page));
+            Ok(tx.signatures[0])
+        }
+    }
+
+    fn progress() -> Progress {
+        Progress {
+            last_distribution_ts: 0,
+            current_day: 0,
+            daily_distributed_to_investors: 0,
+            current_page: 0,
+            pages_processed_today: 0,
+            total_investors: 0,
+            creator_payout_sent: false,
+            has_base_fees: false,
+            total_rounding_dust: 0,
+            day_claimed_quote: 0,
+            day_carry_over_in: 0,
+            day_total_locked: 0,
+            day_rounding_dust: 0,
+            buckets: Default::default(),
+            day_owed_released: 0,
+            registered_deposits: 0,
+            registered_streams: 0,
+            day_streams_read: 0,
+            fully_vested_at: 0,
+            registered_locked: 0,
+            day_locked_basis: 0,
+            day_eligible_share_bps: 0,
+            day_investor_allocation: 0,
+            bump: 255,
+        }
+    }
+
+    fn policy() -> Policy {
+        Policy {
+            y0: 1_000_000,
+            investor_fee_share_bps: 5_000,
+            daily_cap_lamports: 0,
+            min_payout_lamports: 0,
+            quote_mint: Pubkey::new_unique(),
+            creator_wallet: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            allocation_mode: Default::default(),
+            rounding_mode: Default::default(),
+            max_investor_payout_bps: 0,
+            max_investor_payout_lamports: 0,
+            cap_excess_mode: Default::default(),
+            locked_mode: Default::default(),
+            y0_mode: Default::default(),
+            fee_share_schedule: Vec::new(),
+            policy_change_delay_seconds: 0,
+            pending_fields: 0,
+            next_policy_change_id: 0,
+            end_ts: 0,
+            bump: 255,
+        }
+    }
+
+    fn keeper(rpc: MockRpc, investors: usize) -> Keeper<MockRpc> {
+        let accounts = DistributeFeesAccounts {
+            caller: Pubkey::default(),
+            vault: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            position: Pubkey::new_unique(),
+            position_nft_account: Pubkey::new_unique(),
+            treasury_token_a: Pubkey::new_unique(),
+            treasury_token_b: Pubkey::new_unique(),
+            pool_token_a_vault: Pubkey::new_unique(),
+            pool_token_b_vault: Pubkey::new_unique(),
+            token_a_mint: Pubkey::new_unique(),
+            token_b_mint: Pubkey::new_unique(),
+            token_a_program: token::ID,
+            token_b_program: token::ID,
+            creator_ata: Pubkey::new_unique(),
+        };
+        let investors = (0..investors)
+            .map(|_| InvestorAccounts {
+                stream: Pubkey::new_unique(),
+                ata: Pubkey::new_unique(),
+            })
+            .collect();
+
+        Keeper::new(rpc, Keypair::new(), accounts, investors).with_retry(RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::ZERO,
+        })
+    }
+
+    #[test]
+    fn test_next_action() {
+        let mut p = progress();
+        p.last_distribution_ts = 1_000;
+        assert_eq!(next_action(&p, 1_000 + DAY), Action::StartDay);
+        assert_eq!(next_action(&p, 1_000), Action::Wait { until: 1_000 + DAY });
+
+        p.current_page = 2;
+        assert_eq!(next_action(&p, 1_000), Action::Resume { page: 2 });
+
+        p.creator_payout_sent = true;
+        assert_eq!(next_action(&p, 1_000), Action::Wait { until: 1_000 + DAY });
+    }
+
+    #[test]
+    fn test_runs_full_day_then_waits() {
+        let keeper = keeper(MockRpc::new(progress(), 10 * DAY), 10);
+        let pages = keeper.plan().unwrap().1.len();
+        assert!(pages > 1);
+
+        let TickOutcome::Ran(summary) = keeper.tick().unwrap() else {
+            panic!("expected a run");
+        };
+        assert_eq!(summary.day, 1);
+        assert!(summary.day_complete);
+        assert_eq!(summary.signatures.len(), pages);
+
+        let sent = keeper.rpc().sent.borrow().clone();
+        assert_eq!(sent.len(), pages);
+        assert!(sent
+            .iter()
+            .enumerate()
+            .all(|(i, (page, _))| *page as usize == i));
+        assert_eq!(sent.iter().filter(|(_, last)| *last).count(), 1);
+
+        assert_eq!(
+            keeper.tick().unwrap(),
+            TickOutcome::Waiting { until: 11 * DAY }
+        );
+    }
+
+    #[test]
+    fn test_resumes_from_current_page() {
+        let keeper =
+            keeper(MockRpc::new(progress(), 10 * DAY), 10).with_max_pages_per_tick(Some(1));
+
+        let TickOutcome::Ran(first) = keeper.tick().unwrap() else {
+            panic!("expected a run");
+        };
+        assert!(!first.day_complete);
+        assert_eq!(keeper.fetch_progress().unwrap().current_page, 1);
+
+        // Simulates a restart: a fresh tick picks up at page 1 of the same day
+        let keeper = keeper.with_max_pages_per_tick(None);
+        let TickOutcome::Ran(second) = keeper.tick().unwrap() else {
+            panic!("expected a run");
+        };
+        assert_eq!(second.day, 1);
+        assert_eq!(second.first_page, 1);
+        assert!(second.day_complete);
+        assert_eq!(keeper.rpc().sent.borrow()[1].0, 1);
+    }
+
+    #[test]
+    fn test_retries_transient_failures() {
+        let rpc = MockRpc::new(progress(), 10 * DAY);
+        rpc.failures
+            .borrow_mut()
+            .extend([Failure::Reject, Failure::LandThenError]);
+        let keeper = keeper(rpc, 3);
+
+        let TickOutcome::Ran(summary) = keeper.tick().unwrap() else {
+            panic!("expected a run");
+        };
+        assert!(summary.day_complete);
+        // The landed-then-errored page must not be resent
+        let sent = keeper.rpc().sent.borrow().clone();
+        let unique: std::collections::HashSet<_> = sent.iter().collect();
+        assert_eq!(unique.len(), sent.len());
+    }
+
+    #[test]
+    fn test_sunset_day_pages_only_owed_investors() {
+        let rpc = MockRpc::new(progress(), 10 * DAY);
+        rpc.policy.borrow_mut().end_ts = 5 * DAY;
+        let keeper = keeper(rpc, 10);
+        for (i, investor) in keeper.investors.iter().enumerate() {
+            let ledger = InvestorLedger {
+                stream: investor.stream,
+                recipient: Pubkey::new_unique(),
+                owed: if i == 7 { 500 } else { 0 },
+                deposited: 0,
+                locked: 0,
+                last_read_day: 0,
+                bump: 255,
+            };
+            keeper
+                .rpc()
+                .ledgers
+                .borrow_mut()
+                .insert(investor.ledger(), ledger);
+        }
+
+        let (_, pages) = keeper.plan().unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].investors, [keeper.investors[7]]);
+
+        let TickOutcome::Ran(summary) = keeper.tick().unwrap() else {
+            panic!("expected a run");
+        };
+        assert!(summary.day_complete);
+        assert_eq!(*keeper.rpc().sent.borrow(), [(0, true)]);
+    }
+
+    #[test]
+    fn test_gives_up_after_max_attempts() {
+        let rpc = MockRpc::new(progress(), 10 * DAY);
+        rpc.failures.borrow_mut().extend([Failure::Reject; 3]);
+        let keeper = keeper(rpc, 3);
+
+        assert!(matches!(
+            keeper.tick(),
+            Err(KeeperError::RetriesExhausted {
+                page_index: 0,
+                attempts: 3,
+                ..
+            })
+        ));
+        assert_eq!(keeper.fetch_progress().unwrap().current_day, 0);
+    }
+}
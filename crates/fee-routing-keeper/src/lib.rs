@@ -0,0 +1,17 @@
+//! Keeper daemon for the fee-routing `distribute_fees` crank.
+//!
+//! The keeper polls `Progress`, waits for the 24h window, plans the day's pages
+//! with [`fee_routing_client::planner`] and submits them in order, retrying
+//! each page until it is confirmed. After a crash it picks up from
+//! `Progress.current_page`, so a day is never started twice.
+//!
+//! - [`config`]: TOML configuration (RPC, keypair, accounts, investors)
+//! - [`rpc`]: the [`rpc::KeeperRpc`] abstraction and its `RpcClient` implementation
+//! - [`keeper`]: scheduling, page submission and resume logic
+
+pub mod config;
+pub mod error;
+pub mod keeper;
+pub mod rpc;
+
+pub use error::KeeperError;
@@ -0,0 +1,117 @@
+use std::{
+    path::PathBuf,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Parser, ValueEnum};
+use fee_routing_keeper::{
+    config::KeeperConfig,
+    keeper::{Keeper, TickOutcome},
+};
+use solana_keypair::read_keypair_file;
+use solana_rpc_client::rpc_client::RpcClient;
+use tracing::{error, info};
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "fee-routing-keeper",
+    about = "Runs the fee-routing daily distribution crank"
+)]
+struct Args {
+    /// Path to the keeper TOML config
+    #[arg(long, short)]
+    config: PathBuf,
+
+    /// Run a single tick and exit instead of looping
+    #[arg(long)]
+    once: bool,
+
+    /// Print the page plan and exit without sending anything
+    #[arg(long)]
+    dry_run: bool,
+
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum LogFormat {
+    Text,
+    Json,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    init_tracing(args.log_format);
+
+    let config = KeeperConfig::load(&args.config)?;
+    let payer = read_keypair_file(&config.keypair)
+        .map_err(|e| anyhow!("failed to read keypair {}: {e}", config.keypair))?;
+    let rpc = RpcClient::new_with_commitment(config.rpc_url.clone(), config.commitment_config()?);
+
+    let keeper = Keeper::from_config(rpc, payer, &config);
+
+    if args.dry_run {
+        let (_, pages) = keeper.plan().context("planning pages")?;
+        for page in &pages {
+            info!(
+                page = page.page_index,
+                is_final_page = page.is_final_page,
+                investors = page.investors.len(),
+                compute_unit_limit = page.compute_unit_limit,
+                transaction_size = page.transaction_size,
+                account_locks = page.account_locks,
+                "planned page"
+            );
+        }
+        return Ok(());
+    }
+
+    let poll_interval = Duration::from_secs(config.poll_interval_secs);
+    loop {
+        let sleep_for = match keeper.tick() {
+            Ok(TickOutcome::Waiting { until }) => {
+                let wait = until.saturating_sub(unix_now()).max(0) as u64;
+                info!(
+                    next_window_ts = until,
+                    wait_secs = wait,
+                    "waiting for window"
+                );
+                // Cluster time can drift from local time; wake at least every poll interval
+                Duration::from_secs(wait + 1).min(poll_interval)
+            }
+            Ok(TickOutcome::Ran(_)) => Duration::ZERO,
+            Err(err) => {
+                error!(error = %err, "tick failed");
+                if args.once {
+                    return Err(err.into());
+                }
+                poll_interval
+            }
+        };
+
+        if args.once {
+            return Ok(());
+        }
+        thread::sleep(sleep_for);
+    }
+}
+
+fn init_tracing(format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().flatten_event(true).init(),
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,53 @@
+//! RPC access used by the keeper.
+//!
+//! The keeper only needs four calls, so it talks to the cluster through
+//! [`KeeperRpc`]. That keeps the scheduling and retry logic testable without a
+//! validator; production uses the blocking `RpcClient` implementation below.
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{clock::Clock, hash::Hash, sysvar},
+};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_signature::Signature;
+use solana_transaction::versioned::VersionedTransaction;
+
+use crate::KeeperError;
+
+pub trait KeeperRpc {
+    /// Account data, or `None` if the account doesn't exist
+    fn get_account_data(&self, key: &Pubkey) -> Result<Option<Vec<u8>>, KeeperError>;
+
+    /// Cluster `Clock::unix_timestamp`, which is what the program gates on
+    fn get_unix_timestamp(&self) -> Result<i64, KeeperError>;
+
+    fn get_latest_blockhash(&self) -> Result<Hash, KeeperError>;
+
+    /// Send and wait for confirmation at the client's commitment
+    fn send_and_confirm(&self, tx: &VersionedTransaction) -> Result<Signature, KeeperError>;
+}
+
+impl KeeperRpc for RpcClient {
+    fn get_account_data(&self, key: &Pubkey) -> Result<Option<Vec<u8>>, KeeperError> {
+        self.get_account_with_commitment(key, self.commitment())
+            .map(|response| response.value.map(|account| account.data))
+            .map_err(|e| KeeperError::Rpc(e.to_string()))
+    }
+
+    fn get_unix_timestamp(&self) -> Result<i64, KeeperError> {
+        let data = KeeperRpc::get_account_data(self, &sysvar::clock::ID)?
+            .ok_or(KeeperError::AccountNotFound(sysvar::clock::ID))?;
+        let clock: Clock =
+            bincode::deserialize(&data).map_err(|_| KeeperError::Decode(sysvar::clock::ID))?;
+        Ok(clock.unix_timestamp)
+    }
+
+    fn get_latest_blockhash(&self) -> Result<Hash, KeeperError> {
+        RpcClient::get_latest_blockhash(self).map_err(|e| KeeperError::Rpc(e.to_string()))
+    }
+
+    fn send_and_confirm(&self, tx: &VersionedTransaction) -> Result<Signature, KeeperError> {
+        self.send_and_confirm_transaction(tx)
+            .map_err(|e| KeeperError::Rpc(e.to_string()))
+    }
+}
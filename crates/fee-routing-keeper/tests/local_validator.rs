@@ -0,0 +1,83 @@
+//! End-to-end crank runs against a local test validator.
+//!
+//! These need a validator with the fee-routing program deployed, the CP-AMM
+//! and Streamflow programs cloned (see `validator-command.sh`), and policy,
+//! progress, history and the honorary position initialized for the pool in
+//! the keeper config. They are ignored by default:
+//!
+//! ```sh
+//! FEE_ROUTING_KEEPER_CONFIG=keeper.local.toml \
+//!     cargo test -p fee-routing-keeper --test local_validator -- --ignored --test-threads=1
+//! ```
+
+use std::time::Duration;
+
+use fee_routing_keeper::{
+    config::KeeperConfig,
+    keeper::{next_action, Action, Keeper, RetryPolicy, TickOutcome},
+};
+use solana_keypair::read_keypair_file;
+use solana_rpc_client::rpc_client::RpcClient;
+
+fn keeper(max_pages_per_tick: Option<usize>) -> Keeper<RpcClient> {
+    let path = std::env::var("FEE_ROUTING_KEEPER_CONFIG")
+        .expect("FEE_ROUTING_KEEPER_CONFIG must point at a keeper config for the local validator");
+    let config = KeeperConfig::load(path).unwrap();
+    let payer = read_keypair_file(&config.keypair).unwrap();
+    let rpc =
+        RpcClient::new_with_commitment(config.rpc_url.clone(), config.commitment_config().unwrap());
+
+    Keeper::from_config(rpc, payer, &config)
+        .with_retry(RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::from_millis(500),
+        })
+        .with_max_pages_per_tick(max_pages_per_tick)
+}
+
+#[test]
+#[ignore = "requires a prepared local validator"]
+fn test_crank_runs_day_then_waits() {
+    let keeper = keeper(None);
+    let before = keeper.fetch_progress().unwrap();
+
+    let TickOutcome::Ran(summary) = keeper.tick().unwrap() else {
+        panic!("window not open; restart the validator with --reset");
+    };
+    assert_eq!(summary.day, before.current_day + 1);
+    assert!(summary.day_complete);
+    assert_eq!(summary.signatures.len(), summary.pages_planned);
+
+    assert!(matches!(
+        keeper.tick().unwrap(),
+        TickOutcome::Waiting { .. }
+    ));
+}
+
+#[test]
+#[ignore = "requires a prepared local validator with at least two pages of investors"]
+fn test_crank_resumes_after_partial_day() {
+    let partial = keeper(Some(1));
+    let TickOutcome::Ran(first) = partial.tick().unwrap() else {
+        panic!("window not open; restart the validator with --reset");
+    };
+    assert!(
+        !first.day_complete,
+        "config must produce more than one page"
+    );
+
+    let progress = partial.fetch_progress().unwrap();
+    assert_eq!(progress.current_page, 1);
+    assert!(matches!(
+        next_action(&progress, progress.last_distribution_ts + 1),
+        Action::Resume { page: 1 }
+    ));
+
+    // A fresh keeper stands in for a restarted process
+    let TickOutcome::Ran(rest) = keeper(None).tick().unwrap() else {
+        panic!("expected the open day to resume");
+    };
+    assert_eq!(rest.day, first.day);
+    assert_eq!(rest.first_page, 1);
+    assert!(rest.day_complete);
+}
@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SimulationError {
+    #[error("invalid input: {0}")]
+    Input(String),
+
+    #[error("stream {name:?}: {reason}")]
+    InvalidStream { name: String, reason: String },
+
+    /// The program would reject this page; the simulation stops here too
+    #[error("day {day} page {page} would fail on-chain: {reason}")]
+    Page { day: u64, page: u16, reason: String },
+
+    #[error("failed to write report: {0}")]
+    Output(String),
+}
@@ -0,0 +1,455 @@
+//! Simulation input, loaded from TOML or JSON.
+//!
+//! ```toml
+//! start_ts = 1_735_689_600
+//! investors_per_page = 4
+//! # claimed quote fees for each simulated day
+//! fees = [1_000_000, 2_500_000, 0, 800_000]
+//!
+//! [policy]
+//! y0 = 10_000_000
+//! investor_fee_share_bps = 5000
+//! daily_cap_lamports = 0
+//! min_payout_lamports = 1_000
+//! allocation_mode = "full_precision"  # default "legacy"
+//! rounding_mode = "largest_remainder"  # default "floor"
+//! max_investor_payout_bps = 2_500  # default 0, no cap
+//! cap_excess_mode = "creator"  # default "redistribute"
+//! locked_mode = "window_average"  # default "instant"
+//! y0_mode = "stream_deposits"  # default "fixed"
+//! # from each start_ts on, the share drops to share_bps
+//! fee_share_schedule = [
+//!     { start_ts = 1_751_328_000, share_bps = 4000 },
+//!     { start_ts = 1_767_225_600, share_bps = 2000 },
+//! ]
+//! end_ts = 1_798_761_600  # default 0, sunset only once fully vested
+//!
+//! [[streams]]
+//! name = "alice"
+//! # breaks largest-remainder ties like the stream account key does on-chain
+//! address = "7YttLkHDoNj9wyDur5pM1ejNaAvT9X4eqaYcHQqtj2G5"
+//! net_amount_deposited = 6_000_000
+//! start_time = 1_735_689_600
+//! period = 86_400
+//! amount_per_period = 100_000
+//!
+//! [[streams]]
+//! name = "bob"
+//! # base64 Streamflow Contract account data, e.g. from `solana account --output json`
+//! account_data = "..."
+//! ```
+
+use std::{path::Path, str::FromStr};
+
+use anchor_lang::{prelude::Pubkey, AnchorDeserialize};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use fee_routing::{
+    math::DistributionMath,
+    state::{
+        AllocationMode, CapExcessMode, FeeShareStep, LockedMode, Policy, RoundingMode, Y0Mode,
+    },
+};
+use serde::Deserialize;
+use streamflow_sdk::state::Contract;
+
+use crate::SimulationError;
+
+/// Investors per page when none is given: the legacy-transaction capacity
+/// measured by the client planner (a lookup table raises it to 13)
+pub const DEFAULT_INVESTORS_PER_PAGE: usize = 2;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SimulationInput {
+    /// Crank time of the first simulated day; later days follow every 24h
+    pub start_ts: i64,
+    /// Claimed quote fees per day, one entry per simulated day
+    pub fees: Vec<u64>,
+    /// Carry-over already sitting in Progress before day one
+    #[serde(default)]
+    pub carry_over_lamports: u64,
+    #[serde(default = "default_investors_per_page")]
+    pub investors_per_page: usize,
+    pub policy: PolicyInput,
+    /// Investors in crank order
+    #[serde(default)]
+    pub streams: Vec<StreamInput>,
+}
+
+/// The `Policy` fields the distribution math reads
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyInput {
+    pub y0: u64,
+    pub investor_fee_share_bps: u16,
+    #[serde(default)]
+    pub daily_cap_lamports: u64,
+    #[serde(default)]
+    pub min_payout_lamports: u64,
+    #[serde(default)]
+    pub allocation_mode: AllocationModeInput,
+    #[serde(default)]
+    pub rounding_mode: RoundingModeInput,
+    #[serde(default)]
+    pub max_investor_payout_bps: u16,
+    #[serde(default)]
+    pub max_investor_payout_lamports: u64,
+    #[serde(default)]
+    pub cap_excess_mode: CapExcessModeInput,
+    #[serde(default)]
+    pub locked_mode: LockedModeInput,
+    /// `stream_deposits` replaces `y0` with the sum of every stream's deposit
+    #[serde(default)]
+    pub y0_mode: Y0ModeInput,
+    /// Steps replacing `investor_fee_share_bps` from their start times on
+    #[serde(default)]
+    pub fee_share_schedule: Vec<FeeShareStepInput>,
+    /// Unix time distribution sunsets at; 0 runs until the streams fully vest
+    #[serde(default)]
+    pub end_ts: i64,
+}
+
+/// [`FeeShareStep`] as written in simulation inputs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeeShareStepInput {
+    pub start_ts: i64,
+    pub share_bps: u16,
+}
+
+impl From<FeeShareStepInput> for FeeShareStep {
+    fn from(step: FeeShareStepInput) -> Self {
+        FeeShareStep {
+            start_ts: step.start_ts,
+            share_bps: step.share_bps,
+        }
+    }
+}
+
+/// [`AllocationMode`] as written in simulation inputs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AllocationModeInput {
+    #[default]
+    Legacy,
+    FullPrecision,
+}
+
+impl From<AllocationModeInput> for AllocationMode {
+    fn from(mode: AllocationModeInput) -> Self {
+        match mode {
+            AllocationModeInput::Legacy => AllocationMode::Legacy,
+            AllocationModeInput::FullPrecision => AllocationMode::FullPrecision,
+        }
+    }
+}
+
+/// [`RoundingMode`] as written in simulation inputs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingModeInput {
+    #[default]
+    Floor,
+    LargestRemainder,
+}
+
+impl From<RoundingModeInput> for RoundingMode {
+    fn from(mode: RoundingModeInput) -> Self {
+        match mode {
+            RoundingModeInput::Floor => RoundingMode::Floor,
+            RoundingModeInput::LargestRemainder => RoundingMode::LargestRemainder,
+        }
+    }
+}
+
+/// [`LockedMode`] as written in simulation inputs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockedModeInput {
+    #[default]
+    Instant,
+    WindowAverage,
+}
+
+impl From<LockedModeInput> for LockedMode {
+    fn from(mode: LockedModeInput) -> Self {
+        match mode {
+            LockedModeInput::Instant => LockedMode::Instant,
+            LockedModeInput::WindowAverage => LockedMode::WindowAverage,
+        }
+    }
+}
+
+/// [`Y0Mode`] as written in simulation inputs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Y0ModeInput {
+    #[default]
+    Fixed,
+    StreamDeposits,
+}
+
+impl From<Y0ModeInput> for Y0Mode {
+    fn from(mode: Y0ModeInput) -> Self {
+        match mode {
+            Y0ModeInput::Fixed => Y0Mode::Fixed,
+            Y0ModeInput::StreamDeposits => Y0Mode::StreamDeposits,
+        }
+    }
+}
+
+/// [`CapExcessMode`] as written in simulation inputs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapExcessModeInput {
+    #[default]
+    Redistribute,
+    Creator,
+}
+
+impl From<CapExcessModeInput> for CapExcessMode {
+    fn from(mode: CapExcessModeInput) -> Self {
+        match mode {
+            CapExcessModeInput::Redistribute => CapExcessMode::Redistribute,
+            CapExcessModeInput::Creator => CapExcessMode::Creator,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StreamInput {
+    /// Label used in the report, e.g. the stream address or investor name
+    pub name: String,
+    /// Stream account address; only breaks largest-remainder ties, which go
+    /// by crank order among streams without one
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(flatten)]
+    pub source: StreamSource,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum StreamSource {
+    /// Raw Streamflow `Contract` account, read exactly as the program does
+    Account { account_data: String },
+    /// Plain linear vesting schedule
+    Schedule(LinearSchedule),
+}
+
+/// A Streamflow stream without pauses or rate changes
+///
+/// Field names and semantics follow Streamflow's `CreateParams`.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LinearSchedule {
+    pub net_amount_deposited: u64,
+    pub start_time: u64,
+    pub period: u64,
+    pub amount_per_period: u64,
+    /// Cliff timestamp
+    #[serde(default)]
+    pub cliff: u64,
+    /// Amount released at the cliff
+    #[serde(default)]
+    pub cliff_amount: u64,
+}
+
+impl LinearSchedule {
+    /// Still-locked amount at `now`, mirroring Streamflow's
+    /// `vested_available` and `cliff_available`
+    pub fn locked_at(&self, now: u64) -> u64 {
+        let cliff = if self.cliff > now {
+            0
+        } else {
+            self.cliff_amount
+        };
+        let vested = if now < self.start_time || self.period == 0 || self.amount_per_period == 0 {
+            0
+        } else {
+            ((now - self.start_time) / self.period)
+                .saturating_mul(self.amount_per_period)
+                .min(self.net_amount_deposited.saturating_sub(cliff))
+        };
+        self.net_amount_deposited
+            .saturating_sub(vested.saturating_add(cliff))
+    }
+}
+
+/// A stream ready to be evaluated at any timestamp
+#[derive(Clone, Debug)]
+pub(crate) enum Stream {
+    Contract(Box<Contract>),
+    Schedule(LinearSchedule),
+}
+
+impl Stream {
+    pub(crate) fn locked_at(&self, now: u64) -> Result<u64, anchor_lang::error::Error> {
+        match self {
+            Stream::Contract(contract) => DistributionMath::locked_amount(contract, now),
+            Stream::Schedule(schedule) => Ok(schedule.locked_at(now)),
+        }
+    }
+
+    /// `net_amount_deposited`, what the stream's ledger registers
+    pub(crate) fn deposited(&self) -> u64 {
+        match self {
+            Stream::Contract(contract) => contract.ix.net_amount_deposited,
+            Stream::Schedule(schedule) => schedule.net_amount_deposited,
+        }
+    }
+}
+
+impl StreamInput {
+    pub(crate) fn stream(&self) -> Result<Stream, SimulationError> {
+        let invalid = |reason: String| SimulationError::InvalidStream {
+            name: self.name.clone(),
+            reason,
+        };
+        match &self.source {
+            StreamSource::Account { account_data } => {
+                let data = STANDARD
+                    .decode(account_data.trim())
+                    .map_err(|e| invalid(format!("account_data is not base64: {e}")))?;
+                let contract = Contract::try_from_slice(&data)
+                    .map_err(|e| invalid(format!("not a Streamflow contract: {e}")))?;
+                Ok(Stream::Contract(Box::new(contract)))
+            }
+            StreamSource::Schedule(schedule) => Ok(Stream::Schedule(*schedule)),
+        }
+    }
+
+    pub(crate) fn address(&self) -> Result<Pubkey, SimulationError> {
+        self.address
+            .as_deref()
+            .map_or(Ok(Pubkey::default()), Pubkey::from_str)
+            .map_err(|e| SimulationError::InvalidStream {
+                name: self.name.clone(),
+                reason: format!("address is not a pubkey: {e}"),
+            })
+    }
+}
+
+impl SimulationInput {
+    /// Load from a `.json` file, or TOML for any other extension
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SimulationError> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| SimulationError::Input(format!("{}: {e}", path.display())))?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json(&raw)
+        } else {
+            Self::from_toml(&raw)
+        }
+    }
+
+    pub fn from_toml(raw: &str) -> Result<Self, SimulationError> {
+        toml::from_str(raw).map_err(|e| SimulationError::Input(e.to_string()))
+    }
+
+    pub fn from_json(raw: &str) -> Result<Self, SimulationError> {
+        serde_json::from_str(raw).map_err(|e| SimulationError::Input(e.to_string()))
+    }
+
+    /// The on-chain `Policy` these parameters describe
+    pub fn policy(&self) -> Policy {
+        Policy {
+            y0: self.policy.y0,
+            investor_fee_share_bps: self.policy.investor_fee_share_bps,
+            daily_cap_lamports: self.policy.daily_cap_lamports,
+            min_payout_lamports: self.policy.min_payout_lamports,
+            quote_mint: Pubkey::default(),
+            creator_wallet: Pubkey::default(),
+            authority: Pubkey::default(),
+            allocation_mode: self.policy.allocation_mode.into(),
+            rounding_mode: self.policy.rounding_mode.into(),
+            max_investor_payout_bps: self.policy.max_investor_payout_bps,
+            max_investor_payout_lamports: self.policy.max_investor_payout_lamports,
+            cap_excess_mode: self.policy.cap_excess_mode.into(),
+            locked_mode: self.policy.locked_mode.into(),
+            y0_mode: self.policy.y0_mode.into(),
+            fee_share_schedule: self
+                .policy
+                .fee_share_schedule
+                .iter()
+                .copied()
+                .map(Into::into)
+                .collect(),
+            policy_change_delay_seconds: 0,
+            pending_fields: 0,
+            next_policy_change_id: 0,
+            end_ts: self.policy.end_ts,
+            bump: 0,
+        }
+    }
+}
+
+fn default_investors_per_page() -> usize {
+    DEFAULT_INVESTORS_PER_PAGE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_schedule_locked() {
+        let schedule = LinearSchedule {
+            net_amount_deposited: 1_000,
+            start_time: 100,
+            period: 10,
+            amount_per_period: 100,
+            cliff: 150,
+            cliff_amount: 200,
+        };
+
+        assert_eq!(schedule.locked_at(50), 1_000);
+        // two periods vested, cliff not reached
+        assert_eq!(schedule.locked_at(125), 800);
+        // five periods plus the cliff
+        assert_eq!(schedule.locked_at(150), 300);
+        // vesting never exceeds what's left after the cliff
+        assert_eq!(schedule.locked_at(10_000), 0);
+    }
+
+    #[test]
+    fn test_parse_toml_and_json() {
+        let toml = r#"
+start_ts = 0
+fees = [100, 200]
+
+[policy]
+y0 = 1000
+investor_fee_share_bps = 5000
+
+[[streams]]
+name = "alice"
+net_amount_deposited = 500
+start_time = 0
+period = 86400
+amount_per_period = 10
+
+[[streams]]
+name = "bob"
+account_data = "AAAA"
+"#;
+        let input = SimulationInput::from_toml(toml).unwrap();
+        assert_eq!(input.investors_per_page, DEFAULT_INVESTORS_PER_PAGE);
+        assert!(matches!(input.streams[0].source, StreamSource::Schedule(_)));
+        assert!(matches!(
+            input.streams[1].source,
+            StreamSource::Account { .. }
+        ));
+        // "AAAA" decodes to three zero bytes, which isn't a contract
+        assert!(matches!(
+            input.streams[1].stream(),
+            Err(SimulationError::InvalidStream { .. })
+        ));
+
+        let json = r#"{"start_ts": 0, "fees": [1], "investors_per_page": 2,
+            "policy": {"y0": 1, "investor_fee_share_bps": 1}}"#;
+        let input = SimulationInput::from_json(json).unwrap();
+        assert_eq!(input.investors_per_page, 2);
+        assert!(input.streams.is_empty());
+    }
+}
@@ -0,0 +1,407 @@
+//! Invariants the distribution must keep, checkable against any page, day or
+//! simulation report.
+//!
+//! Property tests drive these with random inputs; operators can run them on a
+//! simulation before rolling out a policy. Every check returns the violations
+//! it found, so an empty list means the invariant holds.
+//!
+//! The arithmetic is overflow-free within these bounds, which the handler
+//! already enforces or the token supply implies:
+//!
+//! - `y0 >= 1`, and a page's locked amounts and the day's locked basis each
+//!   sum to at most `y0`
+//! - claimed fees plus the carried-over buckets and owed balances fit in a `u64`
+//! - a page's investor budget is at most the investor share of the day's pool
+//! - at most [`MAX_INVESTORS_PER_PAGE`] investors per page
+
+use anchor_lang::prelude::Pubkey;
+use fee_routing::{
+    constants::{BPS_DENOMINATOR, MAX_INVESTORS_PER_PAGE},
+    math::{DayTerms, DistributionMath, LedgerBalance, PageDistribution},
+    state::{CapExcessMode, Policy, RoundingMode},
+};
+use thiserror::Error;
+
+use crate::simulate::{DayReport, SimulationReport};
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum Violation {
+    #[error("page pays {paid} but only {distributable} is distributable")]
+    PayoutsExceedDistributable { paid: u64, distributable: u64 },
+
+    /// Largest-remainder rounding must hand out every lamport
+    #[error("largest-remainder page allotted {allotted} of {distributable} distributable")]
+    RemainderNotDistributed { allotted: u64, distributable: u64 },
+
+    #[error("investor {investor} was allotted {payout}, above the per-investor cap of {limit}")]
+    PayoutAboveInvestorCap {
+        investor: usize,
+        payout: u64,
+        limit: u64,
+    },
+
+    /// Under `CapExcessMode::Redistribute` only a page whose investors are
+    /// all at the cap may send excess to the creator
+    #[error(
+        "{excess} over the per-investor cap went to the creator while investors were under it"
+    )]
+    CapExcessNotRedistributed { excess: u64 },
+
+    #[error("investor allocation {allocation} exceeds the policy share or budget of {limit}")]
+    AllocationAboveShare { allocation: u64, limit: u64 },
+
+    #[error("page accounts for {accounted} of {available} allocated")]
+    PageNotConserved { accounted: u128, available: u64 },
+
+    #[error("investors were paid {distributed}, above the daily cap of {cap}")]
+    DailyCapExceeded { distributed: u64, cap: u64 },
+
+    /// Investors (without released owed balances) + creator + carry-over +
+    /// newly owed must equal claimed fees plus the carry-over the day started with
+    #[error("day accounts for {accounted} of {available} claimed plus carried in")]
+    DayNotConserved { accounted: i128, available: i128 },
+
+    /// What an investor was paid plus what it is still owed must equal every
+    /// payout it was allotted
+    #[error("investor {stream} was paid {paid} and is owed {owed} of {allotted} allotted")]
+    InvestorNotConserved {
+        stream: String,
+        paid: u64,
+        owed: u64,
+        allotted: u64,
+    },
+
+    #[error("investor {investor}'s payout fell from {payout} to {bumped_payout} when its locked amount grew")]
+    NotMonotonic {
+        investor: usize,
+        payout: u64,
+        bumped_payout: u64,
+    },
+}
+
+/// Per-page invariants: payouts within the distributable amount (all of it
+/// under largest-remainder rounding) and the per-investor caps, allocation
+/// within the policy share of the pool and the investor budget, every
+/// lamport of the allocation paid, owed, carried or sent to the creator, and
+/// the daily cap respected given what earlier pages paid out of their
+/// allocations
+pub fn check_page(
+    policy: &Policy,
+    page: &PageDistribution,
+    already_distributed_today: u64,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    let paid: u128 = page
+        .shares
+        .iter()
+        .map(|s| u128::from(s.transfer_amount()))
+        .sum();
+    let floored: u128 = page.shares.iter().map(|s| u128::from(s.payout)).sum();
+    if floored > u128::from(page.distributable) || paid != u128::from(page.page_total_distributed) {
+        violations.push(Violation::PayoutsExceedDistributable {
+            paid: u64::try_from(floored).unwrap_or(u64::MAX),
+            distributable: page.distributable,
+        });
+    }
+
+    let allotted = floored + u128::from(page.payout_cap_excess);
+    if policy.rounding_mode == RoundingMode::LargestRemainder
+        && page.total_locked > 0
+        && allotted != u128::from(page.distributable)
+    {
+        violations.push(Violation::RemainderNotDistributed {
+            allotted: u64::try_from(allotted).unwrap_or(u64::MAX),
+            distributable: page.distributable,
+        });
+    }
+
+    if let Some(limit) = page.payout_limit {
+        // A stream is on one page a day, so the page's transfer is its day's
+        // and released owed balances count against the cap too
+        for (investor, share) in page.shares.iter().enumerate() {
+            let payout = share.payout.max(share.transfer_amount());
+            if payout > limit {
+                violations.push(Violation::PayoutAboveInvestorCap {
+                    investor,
+                    payout,
+                    limit,
+                });
+            }
+        }
+        let under_cap = page.shares.iter().any(|s| s.locked > 0 && s.payout < limit);
+        if policy.cap_excess_mode == CapExcessMode::Redistribute
+            && page.payout_cap_excess > 0
+            && under_cap
+        {
+            violations.push(Violation::CapExcessNotRedistributed {
+                excess: page.payout_cap_excess,
+            });
+        }
+    }
+
+    let limit = (u128::from(page.day_pool) * u128::from(policy.investor_fee_share_bps)
+        / u128::from(BPS_DENOMINATOR))
+    .min(u128::from(page.investor_budget));
+    if u128::from(page.investor_allocation) > limit {
+        violations.push(Violation::AllocationAboveShare {
+            allocation: page.investor_allocation,
+            limit: limit as u64,
+        });
+    }
+
+    // Releasing more than the page paid leaves nothing to account for
+    let allocation_paid = page.allocation_paid().unwrap_or_default();
+    let accounted = u128::from(allocation_paid)
+        + u128::from(page.below_minimum_dust)
+        + u128::from(page.cap_carry_over)
+        + u128::from(page.payout_cap_excess)
+        + u128::from(page.rounding_dust);
+    if accounted != u128::from(page.investor_allocation) {
+        violations.push(Violation::PageNotConserved {
+            accounted,
+            available: page.investor_allocation,
+        });
+    }
+
+    if policy.daily_cap_lamports > 0 {
+        let distributed = already_distributed_today.saturating_add(allocation_paid);
+        if distributed > policy.daily_cap_lamports {
+            violations.push(Violation::DailyCapExceeded {
+                distributed,
+                cap: policy.daily_cap_lamports,
+            });
+        }
+    }
+
+    violations
+}
+
+/// One page's arguments to [`DistributionMath::distribute_page`]
+#[derive(Clone, Copy, Debug)]
+pub struct PageInput<'a> {
+    pub day: DayTerms,
+    pub investor_budget: u64,
+    pub locked_amounts: &'a [u64],
+    pub stream_keys: &'a [Pubkey],
+    pub balances: &'a [LedgerBalance],
+    pub already_distributed_today: u64,
+}
+
+impl PageInput<'_> {
+    /// Run the page with `locked_amounts` in place of the input's
+    pub fn distribute(
+        &self,
+        policy: &Policy,
+        locked_amounts: &[u64],
+    ) -> anchor_lang::Result<PageDistribution> {
+        DistributionMath::distribute_page(
+            policy,
+            &self.day,
+            self.investor_budget,
+            locked_amounts,
+            self.stream_keys,
+            self.balances,
+            self.already_distributed_today,
+        )
+    }
+}
+
+/// Growing any one investor's locked amount by `step` never lowers that
+/// investor's payout, as long as the page stays within `y0`
+pub fn check_locked_monotonicity(
+    policy: &Policy,
+    page: &PageInput,
+    step: u64,
+) -> anchor_lang::Result<Vec<Violation>> {
+    let base = page.distribute(policy, page.locked_amounts)?;
+    let headroom = policy.y0.saturating_sub(base.total_locked);
+
+    let mut violations = Vec::new();
+    for investor in 0..page.locked_amounts.len().min(MAX_INVESTORS_PER_PAGE) {
+        let mut bumped = page.locked_amounts.to_vec();
+        bumped[investor] += step.min(headroom);
+        let bumped = page.distribute(policy, &bumped)?;
+
+        let payout = base.shares[investor].payout;
+        let bumped_payout = bumped.shares[investor].payout;
+        if bumped_payout < payout {
+            violations.push(Violation::NotMonotonic {
+                investor,
+                payout,
+                bumped_payout,
+            });
+        }
+    }
+    Ok(violations)
+}
+
+/// Per-day invariants: conservation of claimed fees plus carry-in, and the
+/// daily cap across all of the day's pages
+pub fn check_day(policy: &Policy, day: &DayReport) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    // Released owed balances were allotted on earlier days
+    let allocation_paid = day.investor_total.saturating_sub(day.owed_released);
+    let available = i128::from(day.claimed_quote) + i128::from(day.carry_over_in);
+    let accounted = i128::from(allocation_paid)
+        + i128::from(day.creator_total)
+        + i128::from(day.carry_over_out)
+        + i128::from(day.below_minimum_dust);
+    if accounted != available {
+        violations.push(Violation::DayNotConserved {
+            accounted,
+            available,
+        });
+    }
+
+    if policy.daily_cap_lamports > 0 && allocation_paid > policy.daily_cap_lamports {
+        violations.push(Violation::DailyCapExceeded {
+            distributed: allocation_paid,
+            cap: policy.daily_cap_lamports,
+        });
+    }
+
+    violations
+}
+
+/// Every investor's payouts were either transferred or are still owed, so
+/// `min_payout_lamports` only ever delays a payment
+pub fn check_investors(report: &SimulationReport) -> Vec<Violation> {
+    report
+        .investors
+        .iter()
+        .filter_map(|investor| {
+            let allotted = report
+                .payouts
+                .iter()
+                .filter(|p| p.stream == investor.stream)
+                .map(|p| p.payout)
+                .sum();
+            (investor.paid + investor.owed != allotted).then(|| Violation::InvestorNotConserved {
+                stream: investor.stream.clone(),
+                paid: investor.paid,
+                owed: investor.owed,
+                allotted,
+            })
+        })
+        .collect()
+}
+
+/// [`check_day`] for every day of a simulation, as `(day, violation)`
+pub fn check_report(policy: &Policy, report: &SimulationReport) -> Vec<(u64, Violation)> {
+    report
+        .days
+        .iter()
+        .flat_map(|day| {
+            check_day(policy, day)
+                .into_iter()
+                .map(|violation| (day.day, violation))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(cap: u64) -> Policy {
+        Policy {
+            y0: 10_000,
+            investor_fee_share_bps: 5_000,
+            daily_cap_lamports: cap,
+            min_payout_lamports: 100,
+            quote_mint: Pubkey::default(),
+            creator_wallet: Pubkey::default(),
+            authority: Pubkey::default(),
+            allocation_mode: Default::default(),
+            rounding_mode: Default::default(),
+            max_investor_payout_bps: 0,
+            max_investor_payout_lamports: 0,
+            cap_excess_mode: Default::default(),
+            locked_mode: Default::default(),
+            y0_mode: Default::default(),
+            fee_share_schedule: Vec::new(),
+            policy_change_delay_seconds: 0,
+            pending_fields: 0,
+            next_policy_change_id: 0,
+            end_ts: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_checks_pass_on_real_page_and_catch_tampering() {
+        let policy = policy(4_000);
+        let day = DistributionMath::day_terms(&policy, 10_000, 6_000).unwrap();
+        let mut page = DistributionMath::distribute_page(
+            &policy,
+            &day,
+            5_000,
+            &[3_000, 2_999, 1],
+            &[],
+            &[],
+            500,
+        )
+        .unwrap();
+        assert!(check_page(&policy, &page, 500).is_empty());
+
+        page.shares[0].payout += 2;
+        page.rounding_dust += 1;
+        let violations = check_page(&policy, &page, 500);
+        assert!(matches!(
+            violations.as_slice(),
+            [
+                Violation::PayoutsExceedDistributable { .. },
+                Violation::PageNotConserved { .. }
+            ]
+        ));
+
+        // The cap counts what earlier pages paid
+        let page =
+            DistributionMath::distribute_page(&policy, &day, 5_000, &[6_000], &[], &[], 0).unwrap();
+        assert!(matches!(
+            check_page(&policy, &page, 1_000).as_slice(),
+            [Violation::DailyCapExceeded {
+                distributed: 5_000,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn test_check_day_conservation() {
+        let day = DayReport {
+            day: 3,
+            claimed_quote: 1_000,
+            carry_over_in: 50,
+            investor_total: 400,
+            creator_total: 600,
+            carry_over_out: 40,
+            below_minimum_dust: 10,
+            rounding_dust: 5,
+            ..Default::default()
+        };
+        assert!(check_day(&policy(0), &day).is_empty());
+
+        // Owed balances paid today came out of earlier days' allocations
+        let released = DayReport {
+            investor_total: 420,
+            owed_released: 20,
+            ..day.clone()
+        };
+        assert!(check_day(&policy(400), &released).is_empty());
+
+        let leaky = DayReport {
+            creator_total: 590,
+            ..day
+        };
+        assert_eq!(
+            check_day(&policy(0), &leaky),
+            vec![Violation::DayNotConserved {
+                accounted: 1_040,
+                available: 1_050,
+            }]
+        );
+    }
+}
@@ -0,0 +1,23 @@
+//! Off-chain simulator for the fee-routing distribution.
+//!
+//! Replays a series of daily fee claims against Streamflow vesting schedules
+//! and a policy, running the program's own [`fee_routing::math::DistributionMath`]
+//! and page-by-page carry-over rules, so operators can see who would get what
+//! before changing `investor_fee_share_bps` or `daily_cap_lamports`.
+//!
+//! - [`input`]: simulation input (policy, fee series, streams), TOML or JSON
+//! - [`simulate`]: the day-by-day replay and its report types
+//! - [`report`]: CSV and JSON output
+//! - [`invariants`]: conservation, cap and monotonicity checks for pages,
+//!   days and whole reports
+
+pub mod error;
+pub mod input;
+pub mod invariants;
+pub mod report;
+pub mod simulate;
+
+pub use error::SimulationError;
+pub use input::SimulationInput;
+pub use report::CsvTable;
+pub use simulate::{simulate, SimulationReport};
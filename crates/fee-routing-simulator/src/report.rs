@@ -0,0 +1,137 @@
+//! CSV, JSON and text rendering of a [`SimulationReport`].
+
+use std::{fmt, io::Write};
+
+use serde::Serialize;
+
+use crate::{SimulationError, SimulationReport};
+
+/// Which rows a CSV export contains
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsvTable {
+    /// One row per closed day
+    Days,
+    /// One row per investor per day
+    Payouts,
+    /// One row per investor with totals over the simulation
+    Investors,
+}
+
+impl SimulationReport {
+    pub fn write_csv(&self, table: CsvTable, out: impl Write) -> Result<(), SimulationError> {
+        let mut writer = csv::Writer::from_writer(out);
+        match table {
+            CsvTable::Days => serialize_rows(&mut writer, &self.days)?,
+            CsvTable::Payouts => serialize_rows(&mut writer, &self.payouts)?,
+            CsvTable::Investors => serialize_rows(&mut writer, &self.investors)?,
+        }
+        writer
+            .flush()
+            .map_err(|e| SimulationError::Output(e.to_string()))
+    }
+
+    pub fn write_json(&self, out: impl Write) -> Result<(), SimulationError> {
+        serde_json::to_writer_pretty(out, self).map_err(|e| SimulationError::Output(e.to_string()))
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>5} {:>5} {:>14} {:>14} {:>6} {:>14} {:>14} {:>8} {:>14}",
+            "day",
+            "pages",
+            "claimed",
+            "carry_in",
+            "bps",
+            "investors",
+            "creator",
+            "dust",
+            "carry_out"
+        )?;
+        for d in &self.days {
+            writeln!(
+                f,
+                "{:>5} {:>5} {:>14} {:>14} {:>6} {:>14} {:>14} {:>8} {:>14}",
+                d.day,
+                d.pages,
+                d.claimed_quote,
+                d.carry_over_in,
+                d.eligible_share_bps,
+                d.investor_total,
+                d.creator_total,
+                d.rounding_dust,
+                d.carry_over_out
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "{:<44} {:>14} {:>14}", "investor", "paid", "owed")?;
+        for i in &self.investors {
+            writeln!(f, "{:<44} {:>14} {:>14}", i.stream, i.paid, i.owed)?;
+        }
+
+        let t = &self.totals;
+        writeln!(f)?;
+        writeln!(f, "claimed quote   {}", t.claimed_quote)?;
+        writeln!(f, "to investors    {}", t.investors)?;
+        writeln!(f, "to creator      {}", t.creator)?;
+        writeln!(f, "rounding dust   {}", t.rounding_dust)?;
+        writeln!(f, "carry over      {}", t.carry_over)?;
+        writeln!(f, "owed            {}", t.owed)
+    }
+}
+
+fn serialize_rows<W: Write, T: Serialize>(
+    writer: &mut csv::Writer<W>,
+    rows: &[T],
+) -> Result<(), SimulationError> {
+    rows.iter().try_for_each(|row| {
+        writer
+            .serialize(row)
+            .map_err(|e| SimulationError::Output(e.to_string()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulate::{InvestorTotal, PayoutRow};
+
+    #[test]
+    fn test_csv_headers_and_rows() {
+        let report = SimulationReport {
+            payouts: vec![PayoutRow {
+                day: 1,
+                page: 0,
+                stream: "alice, inc".to_string(),
+                locked: 10,
+                payout: 5,
+                paid: true,
+                owed_released: 0,
+                owed: 0,
+            }],
+            investors: vec![InvestorTotal {
+                stream: "alice, inc".to_string(),
+                paid: 5,
+                owed: 0,
+            }],
+            ..Default::default()
+        };
+
+        let mut out = Vec::new();
+        report.write_csv(CsvTable::Payouts, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "day,page,stream,locked,payout,paid,owed_released,owed\n1,0,\"alice, inc\",10,5,true,0,0\n"
+        );
+
+        let mut out = Vec::new();
+        report.write_csv(CsvTable::Investors, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "stream,paid,owed\n\"alice, inc\",5,0\n"
+        );
+    }
+}
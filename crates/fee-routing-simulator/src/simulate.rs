@@ -0,0 +1,610 @@
+//! Day-by-day replay of the `distribute_fees` crank.
+//!
+//! Each day mirrors the handler page by page on the same [`Buckets`] ledger:
+//! page 0 pools the day's claimed fees with everything carried over, splits
+//! the pool into investor and creator budgets and fixes the day's investor
+//! allocation against what the streams had locked when last read. Every page
+//! allocates its locked share of that out of the investor budget, and the
+//! final page pays the creator its budget plus any investor budget left.
+//! Each investor's owed balance carries across days like its `InvestorLedger`
+//! account. All arithmetic goes through [`DistributionMath::distribute_page`]
+//! and the ledger methods, so results match the program lamport for lamport.
+
+use fee_routing::{
+    constants::DISTRIBUTION_WINDOW_SECONDS,
+    errors::FeeRoutingError,
+    math::{DistributionMath, LedgerBalance},
+    state::{Buckets, LockedMode, Policy, Y0Mode},
+};
+use serde::Serialize;
+
+use crate::{input::SimulationInput, SimulationError};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SimulationReport {
+    pub days: Vec<DayReport>,
+    pub payouts: Vec<PayoutRow>,
+    /// Per-investor totals over the whole simulation, in input order
+    pub investors: Vec<InvestorTotal>,
+    pub totals: Totals,
+}
+
+/// One closed distribution day, as `CreatorPayoutDayClosed` would report it
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DayReport {
+    /// `Progress.current_day` after the day's first page
+    pub day: u64,
+    pub timestamp: i64,
+    pub pages: u16,
+    pub claimed_quote: u64,
+    pub carry_over_in: u64,
+    pub total_locked: u64,
+    pub eligible_share_bps: u64,
+    /// Transferred to investors, including `owed_released`
+    pub investor_total: u64,
+    pub creator_total: u64,
+    /// Payouts below `min_payout_lamports`, credited to owed balances
+    pub below_minimum_dust: u64,
+    /// Owed balances from earlier days paid out today
+    pub owed_released: u64,
+    /// Payouts above the per-investor caps, included in `creator_total`
+    pub payout_cap_excess: u64,
+    /// Floor-division remainder, included in `carry_over_out`
+    pub rounding_dust: u64,
+    /// Carry buckets rolled into the next day's pool
+    pub carry_over_out: u64,
+    /// The day ran after sunset: nothing locked, owed balances flushed
+    pub sunset: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PayoutRow {
+    pub day: u64,
+    pub page: u16,
+    pub stream: String,
+    pub locked: u64,
+    pub payout: u64,
+    pub paid: bool,
+    /// Owed balance paid out together with `payout`
+    pub owed_released: u64,
+    /// Owed balance after this page
+    pub owed: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct InvestorTotal {
+    pub stream: String,
+    pub paid: u64,
+    /// Below-minimum payouts still owed after the last day
+    pub owed: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Totals {
+    pub claimed_quote: u64,
+    pub investors: u64,
+    pub creator: u64,
+    pub rounding_dust: u64,
+    /// Carry-over left in Progress after the last day
+    pub carry_over: u64,
+    /// Owed to investors after the last day
+    pub owed: u64,
+}
+
+/// Run every day in `input.fees`
+pub fn simulate(input: &SimulationInput) -> Result<SimulationReport, SimulationError> {
+    if input.investors_per_page == 0 {
+        return Err(SimulationError::Input(
+            "investors_per_page must be at least 1".to_string(),
+        ));
+    }
+
+    let mut policy = input.policy();
+    Policy::validate_fee_share_schedule(&policy.fee_share_schedule)
+        .map_err(|e| SimulationError::Input(e.to_string()))?;
+    let streams = input
+        .streams
+        .iter()
+        .map(|s| s.stream())
+        .collect::<Result<Vec<_>, _>>()?;
+    // Every stream has a ledger from the start and deposits never change
+    // here, so the registered total is the same on every page
+    if policy.y0_mode == Y0Mode::StreamDeposits {
+        let registered_deposits = streams
+            .iter()
+            .try_fold(0u64, |sum, s| sum.checked_add(s.deposited()))
+            .ok_or_else(|| SimulationError::Input("stream deposits overflow u64".to_string()))?;
+        policy.y0 = policy.effective_y0(registered_deposits);
+    }
+    let stream_keys = input
+        .streams
+        .iter()
+        .map(|s| s.address())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut report = SimulationReport {
+        investors: input
+            .streams
+            .iter()
+            .map(|s| InvestorTotal {
+                stream: s.name.clone(),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let mut buckets = Buckets {
+        cap_carry: input.carry_over_lamports,
+        ..Default::default()
+    };
+
+    let mut fully_vested_at = 0;
+    // `Progress.registered_locked`: each ledger records its stream's locked
+    // amount when registered (just before the first day) and on every read
+    let mut registered_locked = None;
+    for (offset, claimed) in input.fees.iter().copied().enumerate() {
+        let day = offset as u64 + 1;
+        let timestamp = input.start_ts + offset as i64 * DISTRIBUTION_WINDOW_SECONDS;
+        let page_error = |page: usize, source: anchor_lang::error::Error| SimulationError::Page {
+            day,
+            page: page as u16,
+            reason: source.to_string(),
+        };
+
+        // The whole day applies the fee share in effect as it opens. After
+        // sunset no stream is read, the creator takes the whole pool and
+        // owed balances are paid out whatever their size or the caps
+        let sunset = policy.sunset(fully_vested_at, timestamp);
+        let policy = Policy {
+            investor_fee_share_bps: policy.day_fee_share_bps(fully_vested_at, timestamp),
+            min_payout_lamports: if sunset {
+                0
+            } else {
+                policy.min_payout_lamports
+            },
+            max_investor_payout_bps: if sunset {
+                0
+            } else {
+                policy.max_investor_payout_bps
+            },
+            max_investor_payout_lamports: if sunset {
+                0
+            } else {
+                policy.max_investor_payout_lamports
+            },
+            ..policy.clone()
+        };
+        let locked = streams
+            .iter()
+            .map(|s| match policy.locked_mode {
+                _ if sunset => Ok(0),
+                LockedMode::Instant => s.locked_at(timestamp as u64),
+                LockedMode::WindowAverage => DistributionMath::window_average_locked(
+                    |t| s.locked_at(t),
+                    DistributionMath::window_start(timestamp) as u64,
+                ),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| page_error(0, e))?;
+
+        let mut summary = DayReport {
+            day,
+            timestamp,
+            claimed_quote: claimed,
+            sunset,
+            ..Default::default()
+        };
+        let day_pool = buckets
+            .open_day(claimed, policy.investor_fee_share_bps)
+            .map_err(|e| page_error(0, e))?;
+        summary.carry_over_in = day_pool - claimed;
+        let read_locked = locked
+            .iter()
+            .try_fold(0u64, |sum, l| sum.checked_add(*l))
+            .ok_or_else(|| page_error(0, FeeRoutingError::ArithmeticOverflow.into()))?;
+        let locked_basis = if sunset {
+            0
+        } else {
+            *registered_locked.get_or_insert(read_locked)
+        };
+        let day_terms = DistributionMath::day_terms(&policy, day_pool, locked_basis)
+            .map_err(|e| page_error(0, e))?;
+        summary.eligible_share_bps = day_terms.eligible_share_bps;
+
+        // A day with no investors still runs one (empty) final page
+        let mut pages: Vec<(usize, &[u64])> = locked
+            .chunks(input.investors_per_page)
+            .enumerate()
+            .map(|(i, chunk)| (i * input.investors_per_page, chunk))
+            .collect();
+        if pages.is_empty() {
+            pages.push((0, &[]));
+        }
+        let page_count = pages.len();
+
+        for (page_index, (first, page_locked)) in pages.into_iter().enumerate() {
+            let page_investors = first..first + page_locked.len();
+            // Each stream is on one page a day, with its owed balance from earlier days
+            let balances: Vec<LedgerBalance> = report.investors[page_investors.clone()]
+                .iter()
+                .map(|i| LedgerBalance { owed: i.owed })
+                .collect();
+            let page = DistributionMath::distribute_page(
+                &policy,
+                &day_terms,
+                buckets.investor_budget,
+                page_locked,
+                &stream_keys[page_investors],
+                &balances,
+                summary.investor_total - summary.owed_released,
+            )
+            .map_err(|e| page_error(page_index, e))?;
+
+            for (i, share) in page.shares.iter().enumerate() {
+                let investor = &mut report.investors[first + i];
+                investor.paid += share.transfer_amount();
+                investor.owed = share.owed;
+                report.payouts.push(PayoutRow {
+                    day,
+                    page: page_index as u16,
+                    stream: investor.stream.clone(),
+                    locked: share.locked,
+                    payout: share.payout,
+                    paid: share.paid,
+                    owed_released: share.owed_released,
+                    owed: share.owed,
+                });
+            }
+
+            summary.pages += 1;
+            summary.investor_total += page.page_total_distributed;
+            summary.total_locked += page.total_locked;
+            summary.below_minimum_dust += page.below_minimum_dust;
+            summary.owed_released += page.owed_released;
+            summary.payout_cap_excess += page.payout_cap_excess;
+            summary.rounding_dust += page.rounding_dust;
+            buckets
+                .apply_page(&page)
+                .map_err(|e| page_error(page_index, e))?;
+
+            if page_index + 1 == page_count {
+                summary.creator_total =
+                    buckets.close_day().map_err(|e| page_error(page_index, e))?;
+            }
+        }
+
+        if !sunset {
+            registered_locked = Some(read_locked);
+        }
+        summary.carry_over_out = buckets
+            .carry_over()
+            .map_err(|e| page_error(page_count - 1, e))?;
+
+        // Every stream was read and none has anything locked left
+        if !sunset && !streams.is_empty() && summary.total_locked == 0 {
+            fully_vested_at = timestamp;
+        }
+
+        report.totals.claimed_quote += claimed;
+        report.totals.investors += summary.investor_total;
+        report.totals.creator += summary.creator_total;
+        report.totals.rounding_dust += summary.rounding_dust;
+        report.days.push(summary);
+    }
+    report.totals.carry_over = report
+        .days
+        .last()
+        .map_or(input.carry_over_lamports, |d| d.carry_over_out);
+    report.totals.owed = report.investors.iter().map(|i| i.owed).sum();
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{
+        CapExcessModeInput, FeeShareStepInput, LinearSchedule, LockedModeInput, PolicyInput,
+        RoundingModeInput, StreamInput, StreamSource, Y0ModeInput,
+    };
+    use anchor_lang::prelude::Pubkey;
+
+    fn locked_forever(name: &str, amount: u64) -> StreamInput {
+        StreamInput {
+            name: name.to_string(),
+            address: None,
+            source: StreamSource::Schedule(LinearSchedule {
+                net_amount_deposited: amount,
+                start_time: u64::MAX,
+                period: 1,
+                amount_per_period: 1,
+                cliff: u64::MAX,
+                cliff_amount: 0,
+            }),
+        }
+    }
+
+    fn input(fees: Vec<u64>, streams: Vec<StreamInput>) -> SimulationInput {
+        SimulationInput {
+            start_ts: 1_000,
+            fees,
+            carry_over_lamports: 0,
+            investors_per_page: 2,
+            policy: PolicyInput {
+                y0: 1_000,
+                investor_fee_share_bps: 5_000,
+                daily_cap_lamports: 0,
+                min_payout_lamports: 0,
+                allocation_mode: Default::default(),
+                rounding_mode: Default::default(),
+                max_investor_payout_bps: 0,
+                max_investor_payout_lamports: 0,
+                cap_excess_mode: Default::default(),
+                locked_mode: Default::default(),
+                y0_mode: Default::default(),
+                fee_share_schedule: Vec::new(),
+                end_ts: 0,
+            },
+            streams,
+        }
+    }
+
+    #[test]
+    fn test_single_page_day() {
+        let report = simulate(&input(
+            vec![10_000],
+            vec![locked_forever("a", 600), locked_forever("b", 300)],
+        ))
+        .unwrap();
+
+        // 90% locked, capped at 50%
+        let day = &report.days[0];
+        assert_eq!(day.pages, 1);
+        assert_eq!(day.eligible_share_bps, 5_000);
+        assert_eq!(day.investor_total, 4_999);
+        assert_eq!(day.creator_total, 5_000);
+        assert_eq!(report.investors[0].paid, 3_333);
+        assert_eq!(report.investors[1].paid, 1_666);
+        // The rounding dust rolls into the next day's pool
+        assert_eq!(day.rounding_dust, 1);
+        assert_eq!(report.totals.carry_over, 1);
+    }
+
+    #[test]
+    fn test_investor_cap_excess() {
+        let mut sim = input(
+            vec![10_000],
+            vec![locked_forever("a", 600), locked_forever("b", 300)],
+        );
+        sim.policy.max_investor_payout_lamports = 2_000;
+
+        // "a"'s 3_333 is cut to 2_000 and "b" would get the rest, 3_000,
+        // which is over the cap too, so 1_000 is left for the creator
+        let report = simulate(&sim).unwrap();
+        assert_eq!(report.investors[0].paid, 2_000);
+        assert_eq!(report.investors[1].paid, 2_000);
+        assert_eq!(report.days[0].payout_cap_excess, 1_000);
+        assert_eq!(report.days[0].creator_total, 6_000);
+        assert_eq!(report.days[0].rounding_dust, 0);
+
+        sim.policy.cap_excess_mode = CapExcessModeInput::Creator;
+        let report = simulate(&sim).unwrap();
+        assert_eq!(report.investors[1].paid, 1_666);
+        assert_eq!(report.days[0].payout_cap_excess, 1_333);
+        assert_eq!(report.days[0].creator_total, 6_333);
+    }
+
+    #[test]
+    fn test_pages_share_the_day_pool() {
+        let mut sim = input(
+            vec![10_000, 10_000],
+            vec![
+                locked_forever("a", 200),
+                locked_forever("b", 200),
+                locked_forever("c", 400),
+            ],
+        );
+        sim.policy.daily_cap_lamports = 1_000;
+        let report = simulate(&sim).unwrap();
+
+        // Pool 10_000: investor budget 5_000, creator budget 5_000.
+        // Page 0: 40% of the pool = 4_000, capped to 1_000, 3_000 carried.
+        // Page 1 ("c" alone, 40%): 4_000 clipped to the 1_000 budget left,
+        // all carried because the cap is spent.
+        let day = &report.days[0];
+        assert_eq!(day.pages, 2);
+        assert_eq!(day.investor_total, 1_000);
+        assert_eq!(day.creator_total, 5_000);
+        assert_eq!(day.carry_over_out, 4_000);
+        assert_eq!(report.investors[2].paid, 0);
+        assert_eq!(report.days[1].carry_over_in, 4_000);
+        assert_eq!(report.days[1].creator_total, 7_000);
+        assert_eq!(report.totals.carry_over, 6_000);
+    }
+
+    #[test]
+    fn test_largest_remainder_ties_go_to_lower_address() {
+        let mut sim = input(
+            vec![15, 10_000],
+            vec![locked_forever("a", 100), locked_forever("b", 100)],
+        );
+        sim.policy.y0 = 200;
+        sim.policy.investor_fee_share_bps = 10_000;
+        sim.policy.rounding_mode = RoundingModeInput::LargestRemainder;
+        sim.streams[0].address = Some(Pubkey::new_from_array([2; 32]).to_string());
+        sim.streams[1].address = Some(Pubkey::new_from_array([1; 32]).to_string());
+        let report = simulate(&sim).unwrap();
+
+        assert_eq!(report.payouts[0].payout, 7);
+        assert_eq!(report.payouts[1].payout, 8);
+        assert_eq!(report.totals.rounding_dust, 0);
+        assert_eq!(report.totals.investors, 10_015);
+
+        sim.streams[0].address = Some("not a key".to_string());
+        assert!(matches!(
+            simulate(&sim).unwrap_err(),
+            SimulationError::InvalidStream { .. }
+        ));
+    }
+
+    #[test]
+    fn test_window_average_locked_mode() {
+        // "b" unlocks in full at noon of the first day
+        let cliff_at_noon = StreamInput {
+            name: "b".to_string(),
+            address: None,
+            source: StreamSource::Schedule(LinearSchedule {
+                net_amount_deposited: 300,
+                start_time: u64::MAX,
+                period: 1,
+                amount_per_period: 1,
+                cliff: 43_200,
+                cliff_amount: 300,
+            }),
+        };
+        let mut sim = input(vec![10_000], vec![locked_forever("a", 600), cliff_at_noon]);
+        sim.policy.investor_fee_share_bps = 10_000;
+
+        // Cranked at 00:16 "b" still has everything locked
+        let report = simulate(&sim).unwrap();
+        assert_eq!(report.days[0].total_locked, 900);
+        assert_eq!(report.investors[1].paid, 3_000);
+
+        // Averaged over the day it was locked for half of it
+        sim.policy.locked_mode = LockedModeInput::WindowAverage;
+        let report = simulate(&sim).unwrap();
+        assert_eq!(report.days[0].total_locked, 750);
+        assert_eq!(report.investors[0].paid, 6_000);
+        assert_eq!(report.investors[1].paid, 1_500);
+    }
+
+    #[test]
+    fn test_fee_share_schedule_steps_down() {
+        let mut sim = input(
+            vec![10_000, 10_000, 10_000],
+            vec![locked_forever("a", 600), locked_forever("b", 400)],
+        );
+        sim.policy.investor_fee_share_bps = 6_000;
+        // Day 2 opens at 87_400, exactly when the 40% step starts
+        sim.policy.fee_share_schedule = vec![
+            FeeShareStepInput {
+                start_ts: 87_400,
+                share_bps: 4_000,
+            },
+            FeeShareStepInput {
+                start_ts: 100_000,
+                share_bps: 2_000,
+            },
+        ];
+        let report = simulate(&sim).unwrap();
+        let shares: Vec<u64> = report.days.iter().map(|d| d.eligible_share_bps).collect();
+        assert_eq!(shares, [6_000, 4_000, 2_000]);
+        assert_eq!(report.days[1].investor_total, 4_000);
+        assert_eq!(report.days[1].creator_total, 6_000);
+
+        sim.policy.fee_share_schedule.swap(0, 1);
+        assert!(matches!(
+            simulate(&sim).unwrap_err(),
+            SimulationError::Input(_)
+        ));
+    }
+
+    #[test]
+    fn test_stream_deposits_y0_mode() {
+        let mut sim = input(
+            vec![10_000],
+            vec![locked_forever("a", 600), locked_forever("b", 300)],
+        );
+        // A later round took deposits past the TGE allocation, which counts
+        // as fully locked against the fixed Y0
+        sim.policy.y0 = 500;
+        let fixed = simulate(&sim).unwrap();
+        assert_eq!(fixed.days[0].eligible_share_bps, 5_000);
+
+        // Measured against the 900 deposited everything is locked too
+        sim.policy.y0_mode = Y0ModeInput::StreamDeposits;
+        let report = simulate(&sim).unwrap();
+        assert_eq!(report.days[0].eligible_share_bps, 5_000);
+        assert_eq!(report.investors[0].paid, 3_333);
+        assert_eq!(report.investors[1].paid, 1_666);
+        assert_eq!(report.investors, fixed.investors);
+    }
+
+    #[test]
+    fn test_below_minimum_payouts_are_owed_until_paid() {
+        let mut sim = input(
+            vec![100, 100, 100, 100, 100],
+            vec![locked_forever("a", 990), locked_forever("b", 10)],
+        );
+        sim.policy.investor_fee_share_bps = 10_000;
+        sim.policy.min_payout_lamports = 5;
+        let report = simulate(&sim).unwrap();
+
+        // "b" earns 1 a day and is paid all 5 once the balance reaches 5
+        let b: Vec<_> = report.payouts.iter().filter(|p| p.stream == "b").collect();
+        assert!(b[..4].iter().all(|p| !p.paid));
+        assert_eq!(b[3].owed, 4);
+        assert!(b[4].paid);
+        assert_eq!(b[4].owed_released, 4);
+        assert_eq!(report.investors[1].paid, 5);
+        assert_eq!(report.investors[1].owed, 0);
+        // Owed amounts never join the pool, so "a" keeps exactly its share
+        assert_eq!(report.investors[0].paid, 5 * 99);
+        assert_eq!(report.days[0].below_minimum_dust, 1);
+        assert_eq!(report.days[0].carry_over_out, 0);
+        assert_eq!(report.days[4].owed_released, 4);
+    }
+
+    #[test]
+    fn test_sunset_after_full_vesting() {
+        // Fully unlocked at the cliff, between days 1 and 2
+        let cliff = StreamInput {
+            name: "a".to_string(),
+            address: None,
+            source: StreamSource::Schedule(LinearSchedule {
+                net_amount_deposited: 100,
+                start_time: 50_000,
+                period: 1,
+                amount_per_period: 0,
+                cliff: 50_000,
+                cliff_amount: 100,
+            }),
+        };
+        let mut sim = input(vec![10_000, 10_000, 10_000], vec![cliff]);
+        sim.policy.min_payout_lamports = 2_000;
+        let report = simulate(&sim).unwrap();
+
+        // 10% locked on day 1, below the minimum
+        assert!(!report.days[0].sunset);
+        assert_eq!(report.days[0].investor_total, 0);
+        assert_eq!(report.payouts[0].owed, 1_000);
+        // Nothing locked on day 2 marks the streams fully vested
+        assert!(!report.days[1].sunset);
+        assert_eq!(report.days[1].creator_total, 10_000);
+        // Day 3 pays the creator everything and flushes the owed balance
+        let day = &report.days[2];
+        assert!(day.sunset);
+        assert_eq!(day.eligible_share_bps, 0);
+        assert_eq!(day.creator_total, 10_000);
+        assert_eq!(day.owed_released, 1_000);
+        assert_eq!(report.investors[0].paid, 1_000);
+        assert_eq!(report.investors[0].owed, 0);
+
+        // An end time sunsets from that day on, vested or not
+        let mut sim = input(vec![10_000, 10_000], vec![locked_forever("a", 600)]);
+        sim.policy.end_ts = sim.start_ts + DISTRIBUTION_WINDOW_SECONDS;
+        let report = simulate(&sim).unwrap();
+        assert!(!report.days[0].sunset);
+        assert_eq!(report.days[0].investor_total, 5_000);
+        assert!(report.days[1].sunset);
+        assert_eq!(report.days[1].creator_total, 10_000);
+    }
+
+    #[test]
+    fn test_locked_above_y0_counts_as_fully_locked() {
+        let report = simulate(&input(vec![10_000], vec![locked_forever("a", 5_000)])).unwrap();
+        assert_eq!(report.days[0].eligible_share_bps, 5_000);
+        assert_eq!(report.days[0].investor_total, 5_000);
+        assert_eq!(report.days[0].creator_total, 5_000);
+    }
+}
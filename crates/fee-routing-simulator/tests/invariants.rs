@@ -0,0 +1,312 @@
+//! Property tests for `DistributionMath` and the simulated crank, checked
+//! through the `invariants` module.
+
+use anchor_lang::prelude::Pubkey;
+use fee_routing::{
+    constants::BPS_DENOMINATOR,
+    math::{DayTerms, DistributionMath, LedgerBalance},
+    state::{AllocationMode, CapExcessMode, Policy, RoundingMode},
+};
+use fee_routing_simulator::{
+    input::{
+        AllocationModeInput, CapExcessModeInput, FeeShareStepInput, LinearSchedule,
+        LockedModeInput, PolicyInput, RoundingModeInput, StreamInput, StreamSource, Y0ModeInput,
+    },
+    invariants::{check_investors, check_locked_monotonicity, check_page, check_report, PageInput},
+    simulate, SimulationInput,
+};
+use proptest::prelude::*;
+
+const MAX_PAGE: usize = 12;
+
+/// Zero, small and full-range values, so thresholds and caps actually bind
+fn amount() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        Just(0),
+        1..=10_000u64,
+        1..=1_000_000_000_000u64,
+        any::<u64>()
+    ]
+}
+
+fn allocation_mode() -> impl Strategy<Value = AllocationModeInput> {
+    prop_oneof![
+        Just(AllocationModeInput::Legacy),
+        Just(AllocationModeInput::FullPrecision)
+    ]
+}
+
+fn rounding_mode() -> impl Strategy<Value = RoundingModeInput> {
+    prop_oneof![
+        Just(RoundingModeInput::Floor),
+        Just(RoundingModeInput::LargestRemainder)
+    ]
+}
+
+/// Per-investor caps in bps and lamports (either may be off) and where
+/// the excess goes
+fn investor_caps() -> impl Strategy<Value = (u16, u64, CapExcessModeInput)> {
+    (
+        prop_oneof![Just(0), 0..=10_000u16],
+        amount(),
+        prop_oneof![
+            Just(CapExcessModeInput::Redistribute),
+            Just(CapExcessModeInput::Creator)
+        ],
+    )
+}
+
+#[derive(Debug)]
+struct Page {
+    policy: Policy,
+    /// Fixed against a basis anywhere up to `y0`, above or below what the
+    /// page itself has locked
+    day: DayTerms,
+    /// At most the investor share of the day's pool, as `Buckets::open_day` sets it
+    investor_budget: u64,
+    locked: Vec<u64>,
+    /// Drawn from a handful of values, so remainder ties hit equal keys too
+    stream_keys: Vec<Pubkey>,
+    /// Owed balances below the minimum, as earlier days would leave them
+    balances: Vec<LedgerBalance>,
+    already: u64,
+}
+
+impl Page {
+    fn input(&self) -> PageInput<'_> {
+        PageInput {
+            day: self.day,
+            investor_budget: self.investor_budget,
+            locked_amounts: &self.locked,
+            stream_keys: &self.stream_keys,
+            balances: &self.balances,
+            already_distributed_today: self.already,
+        }
+    }
+}
+
+/// A policy and a page whose locked amounts stay within `y0`, after earlier
+/// pages that paid at most the daily cap and drew down the investor budget
+fn page() -> impl Strategy<Value = Page> {
+    (
+        prop::collection::vec(
+            (
+                prop_oneof![0..=1_000u64, 0..=u64::MAX / 16],
+                0..4u8,
+                any::<u64>(),
+            ),
+            0..=MAX_PAGE,
+        ),
+        amount(),
+        0..=10_000u16,
+        amount(),
+        amount(),
+        amount(),
+        amount(),
+        amount(),
+        allocation_mode(),
+        rounding_mode(),
+        investor_caps(),
+        amount(),
+    )
+        .prop_map(
+            |(
+                investors,
+                headroom,
+                share,
+                cap,
+                min_payout,
+                day_pool,
+                budget,
+                already,
+                allocation,
+                rounding,
+                (cap_bps, cap_lamports, excess_mode),
+                basis,
+            )| {
+                let locked: Vec<u64> = investors.iter().map(|i| i.0).collect();
+                let keys = investors.iter().map(|i| i.1);
+                let balances = investors.iter().map(|i| i.2);
+                let total_locked: u64 = locked.iter().sum();
+                let policy = Policy {
+                    y0: total_locked.saturating_add(headroom).max(1),
+                    investor_fee_share_bps: share,
+                    daily_cap_lamports: cap,
+                    min_payout_lamports: min_payout,
+                    quote_mint: Pubkey::default(),
+                    creator_wallet: Pubkey::default(),
+                    authority: Pubkey::default(),
+                    allocation_mode: AllocationMode::from(allocation),
+                    rounding_mode: RoundingMode::from(rounding),
+                    max_investor_payout_bps: cap_bps,
+                    max_investor_payout_lamports: cap_lamports,
+                    cap_excess_mode: CapExcessMode::from(excess_mode),
+                    locked_mode: Default::default(),
+                    y0_mode: Default::default(),
+                    fee_share_schedule: Vec::new(),
+                    policy_change_delay_seconds: 0,
+                    pending_fields: 0,
+                    next_policy_change_id: 0,
+                    end_ts: 0,
+                    bump: 0,
+                };
+                let share_of_pool =
+                    (u128::from(day_pool) * u128::from(share) / u128::from(BPS_DENOMINATOR)) as u64;
+                let day = DistributionMath::day_terms(&policy, day_pool, basis.min(policy.y0))
+                    .expect("basis within y0");
+                Page {
+                    policy,
+                    day,
+                    investor_budget: budget.min(share_of_pool),
+                    locked,
+                    stream_keys: keys.map(|k| Pubkey::new_from_array([k; 32])).collect(),
+                    // Owed balances sum with the pool within a u64
+                    balances: balances
+                        .map(|o| LedgerBalance {
+                            owed: o
+                                % min_payout.max(1)
+                                % ((u64::MAX - day_pool) / MAX_PAGE as u64).max(1),
+                        })
+                        .collect(),
+                    already: if cap > 0 { already.min(cap) } else { already },
+                }
+            },
+        )
+}
+
+fn stream() -> impl Strategy<Value = LinearSchedule> {
+    (
+        0..=1_000_000_000u64,
+        0..=30 * 86_400u64,
+        1..=30 * 86_400u64,
+        0..=1_000_000_000u64,
+    )
+        .prop_map(|(net, start_offset, period, per_period)| LinearSchedule {
+            net_amount_deposited: net,
+            start_time: 1_700_000_000 + start_offset,
+            period,
+            amount_per_period: per_period.min(net).max(1),
+            cliff: 0,
+            cliff_amount: 0,
+        })
+}
+
+fn crank() -> impl Strategy<Value = SimulationInput> {
+    (
+        prop::collection::vec(stream(), 0..=10),
+        prop::collection::vec(0..=1_000_000_000_000u64, 1..=5),
+        0..=1_000_000_000u64,
+        1..=5usize,
+        0..=1_000_000_000u64,
+        0..=10_000u16,
+        prop_oneof![Just(0), 1..=1_000_000_000u64],
+        prop_oneof![Just(0), 1..=10_000_000u64],
+        allocation_mode(),
+        rounding_mode(),
+        investor_caps(),
+        (
+            prop_oneof![
+                Just(LockedModeInput::Instant),
+                Just(LockedModeInput::WindowAverage)
+            ],
+            prop_oneof![Just(Y0ModeInput::Fixed), Just(Y0ModeInput::StreamDeposits)],
+            prop::collection::vec((1..=2 * 86_400i64, 0..=10_000u16), 0..=3),
+        ),
+    )
+        .prop_map(
+            |(
+                schedules,
+                fees,
+                carry,
+                per_page,
+                headroom,
+                share,
+                cap,
+                min_payout,
+                allocation,
+                rounding,
+                (cap_bps, cap_lamports, excess_mode),
+                (locked_mode, y0_mode, steps),
+            )| {
+                // Steps land anywhere from a day before the first crank on
+                let mut start_ts = 1_700_000_000 - 86_400;
+                let fee_share_schedule = steps
+                    .into_iter()
+                    .map(|(gap, share_bps)| {
+                        start_ts += gap;
+                        FeeShareStepInput {
+                            start_ts,
+                            share_bps,
+                        }
+                    })
+                    .collect();
+                let total: u64 = schedules.iter().map(|s| s.net_amount_deposited).sum();
+                SimulationInput {
+                    start_ts: 1_700_000_000,
+                    fees,
+                    carry_over_lamports: carry,
+                    investors_per_page: per_page,
+                    policy: PolicyInput {
+                        y0: (total + headroom).max(1),
+                        investor_fee_share_bps: share,
+                        daily_cap_lamports: cap,
+                        min_payout_lamports: min_payout,
+                        allocation_mode: allocation,
+                        rounding_mode: rounding,
+                        max_investor_payout_bps: cap_bps,
+                        max_investor_payout_lamports: cap_lamports,
+                        cap_excess_mode: excess_mode,
+                        locked_mode,
+                        y0_mode,
+                        fee_share_schedule,
+                        end_ts: 0,
+                    },
+                    streams: schedules
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, schedule)| StreamInput {
+                            name: format!("s{i}"),
+                            address: None,
+                            source: StreamSource::Schedule(schedule),
+                        })
+                        .collect(),
+                }
+            },
+        )
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    /// Within the stated bounds no page overflows, payouts stay within the
+    /// distributable amount and the budget, every allocated lamport is paid
+    /// or carried, and the cap holds
+    #[test]
+    fn prop_page_invariants(p in page()) {
+        let page = p
+            .input()
+            .distribute(&p.policy, &p.locked)
+            .expect("no overflow within bounds");
+        prop_assert_eq!(check_page(&p.policy, &page, p.already), vec![]);
+    }
+
+    #[test]
+    fn prop_payout_monotonic_in_locked(
+        p in page(),
+        step in prop_oneof![Just(1u64), any::<u64>()],
+    ) {
+        let violations = check_locked_monotonicity(&p.policy, &p.input(), step).unwrap();
+        prop_assert_eq!(violations, vec![]);
+    }
+
+    /// The daily cap holds across all of a day's pages, every day accounts
+    /// for exactly what it claimed plus what it carried in, and every
+    /// investor is eventually paid or still owed each payout it was allotted
+    #[test]
+    fn prop_crank_invariants(input in crank()) {
+        let policy = input.policy();
+        let report = simulate(&input).expect("no page fails within bounds");
+        prop_assert_eq!(check_report(&policy, &report), vec![]);
+        prop_assert_eq!(check_investors(&report), vec![]);
+    }
+}
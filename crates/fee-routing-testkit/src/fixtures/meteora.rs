@@ -0,0 +1,220 @@
+//! CP-AMM `Pool` and `Position` accounts.
+//!
+//! Only the fields the fee-routing program and the mock read are written;
+//! everything else stays zeroed at its real offset.
+
+use anchor_lang::prelude::*;
+use fee_routing::meteora;
+use solana_account::Account;
+
+use super::{account_discriminator, owned_by, write_pubkey, write_u64};
+
+/// Size of a CP-AMM `Pool` account, discriminator included
+pub const POOL_LEN: usize = 1112;
+/// `Pool::token_a_mint`
+pub const POOL_TOKEN_A_MINT_OFFSET: usize = 168;
+/// `Pool::token_b_mint`
+pub const POOL_TOKEN_B_MINT_OFFSET: usize = 200;
+/// `Pool::token_a_vault`
+pub const POOL_TOKEN_A_VAULT_OFFSET: usize = 232;
+/// `Pool::token_b_vault`
+pub const POOL_TOKEN_B_VAULT_OFFSET: usize = 264;
+/// `Pool::liquidity`
+pub const POOL_LIQUIDITY_OFFSET: usize = 360;
+/// `Pool::collect_fee_mode`
+pub const POOL_COLLECT_FEE_MODE_OFFSET: usize = 484;
+
+/// `CollectFeeMode::BothToken`: fees accrue in both tokens
+pub const COLLECT_FEE_MODE_BOTH_TOKEN: u8 = 0;
+/// `CollectFeeMode::OnlyB`: fees accrue in token B (quote) only
+pub const COLLECT_FEE_MODE_ONLY_B: u8 = 1;
+
+/// Size of a CP-AMM `Position` account, discriminator included
+pub const POSITION_LEN: usize = 408;
+/// `Position::pool`
+pub const POSITION_POOL_OFFSET: usize = 8;
+/// `Position::nft_mint`
+pub const POSITION_NFT_MINT_OFFSET: usize = 40;
+/// `Position::fee_a_pending`
+pub const POSITION_FEE_A_PENDING_OFFSET: usize = 136;
+/// `Position::fee_b_pending`
+pub const POSITION_FEE_B_PENDING_OFFSET: usize = 144;
+/// `Position::unlocked_liquidity`
+pub const POSITION_UNLOCKED_LIQUIDITY_OFFSET: usize = 152;
+
+/// Builds a CP-AMM `Pool` account, quote-only (`OnlyB`) by default
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolBuilder {
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub liquidity: u128,
+    pub collect_fee_mode: u8,
+}
+
+impl PoolBuilder {
+    pub fn new(token_a_mint: Pubkey, token_b_mint: Pubkey) -> Self {
+        Self {
+            token_a_mint,
+            token_b_mint,
+            token_a_vault: Pubkey::default(),
+            token_b_vault: Pubkey::default(),
+            liquidity: 0,
+            collect_fee_mode: COLLECT_FEE_MODE_ONLY_B,
+        }
+    }
+
+    pub fn vaults(mut self, token_a_vault: Pubkey, token_b_vault: Pubkey) -> Self {
+        self.token_a_vault = token_a_vault;
+        self.token_b_vault = token_b_vault;
+        self
+    }
+
+    pub fn liquidity(mut self, liquidity: u128) -> Self {
+        self.liquidity = liquidity;
+        self
+    }
+
+    pub fn collect_fee_mode(mut self, collect_fee_mode: u8) -> Self {
+        self.collect_fee_mode = collect_fee_mode;
+        self
+    }
+
+    pub fn data(&self) -> Vec<u8> {
+        let mut data = vec![0; POOL_LEN];
+        data[..8].copy_from_slice(&account_discriminator("Pool"));
+        write_pubkey(&mut data, POOL_TOKEN_A_MINT_OFFSET, &self.token_a_mint);
+        write_pubkey(&mut data, POOL_TOKEN_B_MINT_OFFSET, &self.token_b_mint);
+        write_pubkey(&mut data, POOL_TOKEN_A_VAULT_OFFSET, &self.token_a_vault);
+        write_pubkey(&mut data, POOL_TOKEN_B_VAULT_OFFSET, &self.token_b_vault);
+        data[POOL_LIQUIDITY_OFFSET..POOL_LIQUIDITY_OFFSET + 16]
+            .copy_from_slice(&self.liquidity.to_le_bytes());
+        data[POOL_COLLECT_FEE_MODE_OFFSET] = self.collect_fee_mode;
+        data
+    }
+
+    /// The pool as a CP-AMM-owned account
+    pub fn account(&self) -> Account {
+        owned_by(self.data(), meteora::cp_amm_program_id())
+    }
+}
+
+/// Builds a CP-AMM `Position` account with fees waiting to be claimed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PositionBuilder {
+    pub pool: Pubkey,
+    pub nft_mint: Pubkey,
+    pub fee_a_pending: u64,
+    pub fee_b_pending: u64,
+    pub unlocked_liquidity: u128,
+}
+
+impl PositionBuilder {
+    pub fn new(pool: Pubkey, nft_mint: Pubkey) -> Self {
+        Self {
+            pool,
+            nft_mint,
+            fee_a_pending: 0,
+            fee_b_pending: 0,
+            unlocked_liquidity: 0,
+        }
+    }
+
+    pub fn pending_fees(mut self, fee_a: u64, fee_b: u64) -> Self {
+        self.fee_a_pending = fee_a;
+        self.fee_b_pending = fee_b;
+        self
+    }
+
+    pub fn unlocked_liquidity(mut self, liquidity: u128) -> Self {
+        self.unlocked_liquidity = liquidity;
+        self
+    }
+
+    pub fn data(&self) -> Vec<u8> {
+        let mut data = vec![0; POSITION_LEN];
+        data[..8].copy_from_slice(&account_discriminator("Position"));
+        write_pubkey(&mut data, POSITION_POOL_OFFSET, &self.pool);
+        write_pubkey(&mut data, POSITION_NFT_MINT_OFFSET, &self.nft_mint);
+        set_pending_fees(&mut data, self.fee_a_pending, self.fee_b_pending);
+        data[POSITION_UNLOCKED_LIQUIDITY_OFFSET..POSITION_UNLOCKED_LIQUIDITY_OFFSET + 16]
+            .copy_from_slice(&self.unlocked_liquidity.to_le_bytes());
+        data
+    }
+
+    /// The position as a CP-AMM-owned account
+    pub fn account(&self) -> Account {
+        owned_by(self.data(), meteora::cp_amm_program_id())
+    }
+}
+
+/// Overwrite the pending fees in a position account's data
+pub fn set_pending_fees(data: &mut [u8], fee_a: u64, fee_b: u64) {
+    write_u64(data, POSITION_FEE_A_PENDING_OFFSET, fee_a);
+    write_u64(data, POSITION_FEE_B_PENDING_OFFSET, fee_b);
+}
+
+/// Pending `(fee_a, fee_b)` in a position account's data
+pub fn pending_fees(data: &[u8]) -> (u64, u64) {
+    let read = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    (
+        read(POSITION_FEE_A_PENDING_OFFSET),
+        read(POSITION_FEE_B_PENDING_OFFSET),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_and_position_layouts() {
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let pool = PoolBuilder::new(mint_a, mint_b).data();
+        assert_eq!(pool.len(), POOL_LEN);
+        assert_eq!(pool[..8], account_discriminator("Pool"));
+        assert_eq!(
+            pool[POOL_TOKEN_B_MINT_OFFSET..POOL_TOKEN_B_MINT_OFFSET + 32],
+            mint_b.to_bytes()
+        );
+        assert_eq!(pool[POOL_COLLECT_FEE_MODE_OFFSET], COLLECT_FEE_MODE_ONLY_B);
+
+        let mut position = PositionBuilder::new(Pubkey::new_unique(), Pubkey::new_unique())
+            .pending_fees(3, 5)
+            .data();
+        assert_eq!(position.len(), POSITION_LEN);
+        assert_eq!(pending_fees(&position), (3, 5));
+        set_pending_fees(&mut position, 0, 7);
+        assert_eq!(pending_fees(&position), (0, 7));
+    }
+    #[test]
+    fn test_builders_match_program_layouts() {
+        use fee_routing::meteora::{CpAmmAccount, Pool, Position};
+
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (vault_a, vault_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let pool_data = PoolBuilder::new(mint_a, mint_b)
+            .vaults(vault_a, vault_b)
+            .liquidity(1 << 70)
+            .data();
+        let pool = Pool::from_bytes(&pool_data).unwrap();
+        assert_eq!((pool.token_a_mint, pool.token_b_mint), (mint_a, mint_b));
+        assert_eq!((pool.token_a_vault, pool.token_b_vault), (vault_a, vault_b));
+        assert_eq!({ pool.liquidity }, 1 << 70);
+        assert!(pool.is_quote_only());
+
+        let (pool_key, nft_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let position_data = PositionBuilder::new(pool_key, nft_mint)
+            .pending_fees(3, 5)
+            .unlocked_liquidity(9)
+            .data();
+        let position = Position::from_bytes(&position_data).unwrap();
+        assert_eq!((position.pool, position.nft_mint), (pool_key, nft_mint));
+        assert_eq!(
+            ({ position.fee_a_pending }, { position.fee_b_pending }),
+            (3, 5)
+        );
+        assert_eq!({ position.unlocked_liquidity }, 9);
+    }
+}
@@ -0,0 +1,40 @@
+//! Account fixtures: SPL token accounts, Meteora CP-AMM pools and positions,
+//! and Streamflow contracts.
+//!
+//! Builders produce the same bytes the real programs would store, so the
+//! fee-routing handler and the mock CP-AMM read them through their normal
+//! deserialization paths.
+
+mod meteora;
+mod streamflow;
+mod token;
+
+pub use self::{meteora::*, streamflow::*, token::*};
+
+use anchor_lang::prelude::*;
+use solana_account::Account;
+
+/// First 8 bytes of `sha256("account:<name>")`
+pub fn account_discriminator(name: &str) -> [u8; 8] {
+    let hash = anchor_lang::solana_program::hash::hash(format!("account:{name}").as_bytes());
+    hash.to_bytes()[..8].try_into().unwrap()
+}
+
+/// A rent-exempt account holding `data`
+pub fn owned_by(data: Vec<u8>, owner: Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn write_pubkey(data: &mut [u8], offset: usize, key: &Pubkey) {
+    data[offset..offset + 32].copy_from_slice(key.as_ref());
+}
+
+fn write_u64(data: &mut [u8], offset: usize, value: u64) {
+    data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
@@ -0,0 +1,145 @@
+//! Streamflow `Contract` accounts.
+//!
+//! Contracts are borsh-encoded with no discriminator. The fee-routing handler
+//! deserializes the whole account with `try_from_slice` and rejects anything
+//! under 400 bytes, so accounts are padded to [`CONTRACT_LEN`] through
+//! `ix_padding` and the encoded contract fills them exactly.
+
+use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
+use solana_account::Account;
+use streamflow_sdk::state::Contract;
+
+use super::owned_by;
+
+/// Size of a Streamflow contract (metadata) account
+pub const CONTRACT_LEN: usize = 1104;
+
+/// Builds a Streamflow contract with a linear schedule
+///
+/// Field names follow Streamflow's `CreateParams`. The cliff defaults to
+/// `start_time` with nothing released at it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamBuilder {
+    pub net_amount_deposited: u64,
+    pub start_time: u64,
+    pub period: u64,
+    pub amount_per_period: u64,
+    pub cliff: u64,
+    pub cliff_amount: u64,
+    pub amount_withdrawn: u64,
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+}
+
+impl StreamBuilder {
+    /// `net_amount_deposited` released `amount_per_period` every `period`
+    /// seconds from `start_time`
+    pub fn new(
+        net_amount_deposited: u64,
+        start_time: u64,
+        period: u64,
+        amount_per_period: u64,
+    ) -> Self {
+        Self {
+            net_amount_deposited,
+            start_time,
+            period,
+            amount_per_period,
+            cliff: start_time,
+            cliff_amount: 0,
+            amount_withdrawn: 0,
+            recipient: Pubkey::default(),
+            mint: Pubkey::default(),
+        }
+    }
+
+    /// Release `cliff_amount` at `cliff` on top of the periodic schedule
+    pub fn cliff(mut self, cliff: u64, cliff_amount: u64) -> Self {
+        self.cliff = cliff;
+        self.cliff_amount = cliff_amount;
+        self
+    }
+
+    /// Amount the recipient has already withdrawn
+    pub fn withdrawn(mut self, amount_withdrawn: u64) -> Self {
+        self.amount_withdrawn = amount_withdrawn;
+        self
+    }
+
+    pub fn recipient(mut self, recipient: Pubkey) -> Self {
+        self.recipient = recipient;
+        self
+    }
+
+    pub fn mint(mut self, mint: Pubkey) -> Self {
+        self.mint = mint;
+        self
+    }
+
+    /// When the last period unlocks, as Streamflow computes it at creation;
+    /// never for a schedule that releases nothing per period
+    pub fn end_time(&self) -> u64 {
+        if self.amount_per_period == 0 {
+            return u64::MAX;
+        }
+        let periods = self
+            .net_amount_deposited
+            .saturating_sub(self.cliff_amount)
+            .div_ceil(self.amount_per_period);
+        self.start_time
+            .saturating_add(periods.saturating_mul(self.period))
+    }
+
+    pub fn build(&self) -> Contract {
+        // Zeroed bytes decode to an empty contract; start there so only the
+        // schedule and the fields tests care about need setting
+        let mut contract = Contract::deserialize(&mut &[0u8; CONTRACT_LEN][..]).unwrap();
+        contract.created_at = self.start_time;
+        contract.amount_withdrawn = self.amount_withdrawn;
+        contract.end_time = self.end_time();
+        contract.recipient = self.recipient;
+        contract.mint = self.mint;
+        contract.ix.net_amount_deposited = self.net_amount_deposited;
+        contract.ix.start_time = self.start_time;
+        contract.ix.period = self.period;
+        contract.ix.amount_per_period = self.amount_per_period;
+        contract.ix.cliff = self.cliff;
+        contract.ix.cliff_amount = self.cliff_amount;
+        contract
+    }
+
+    pub fn data(&self) -> Vec<u8> {
+        contract_data(&self.build())
+    }
+
+    /// The contract as a Streamflow-owned account
+    pub fn account(&self) -> Account {
+        stream_account(&self.build())
+    }
+}
+
+/// Shorthand for [`StreamBuilder::new`] without a cliff or withdrawals
+pub fn linear_stream(
+    net_amount_deposited: u64,
+    start_time: u64,
+    period: u64,
+    amount_per_period: u64,
+) -> Contract {
+    StreamBuilder::new(net_amount_deposited, start_time, period, amount_per_period).build()
+}
+
+/// `contract` encoded and padded to [`CONTRACT_LEN`]
+pub fn contract_data(contract: &Contract) -> Vec<u8> {
+    let mut contract = contract.clone();
+    contract.ix_padding.clear();
+    let unpadded = contract.try_to_vec().unwrap().len();
+    contract
+        .ix_padding
+        .resize(CONTRACT_LEN.saturating_sub(unpadded), 0);
+    contract.try_to_vec().unwrap()
+}
+
+/// `contract` as a Streamflow-owned account
+pub fn stream_account(contract: &Contract) -> Account {
+    owned_by(contract_data(contract), streamflow_sdk::id())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program_option::COption, program_pack::Pack},
+};
+use anchor_spl::token::{self, spl_token};
+use solana_account::Account;
+
+use super::owned_by;
+
+/// An SPL mint with no mint authority
+pub fn mint_account(decimals: u8) -> Account {
+    let mint = spl_token::state::Mint {
+        mint_authority: COption::None,
+        supply: u64::MAX,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    packed(&mint)
+}
+
+/// An initialized SPL token account holding `amount`
+pub fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    let account = spl_token::state::Account {
+        mint,
+        owner,
+        amount,
+        delegate: COption::None,
+        state: spl_token::state::AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+    packed(&account)
+}
+
+fn packed<T: Pack>(state: &T) -> Account {
+    let mut data = vec![0; T::LEN];
+    state.pack_into_slice(&mut data);
+    owned_by(data, token::ID)
+}
@@ -0,0 +1,424 @@
+//! A `distribute_fees` environment running in-process on `solana-program-test`.
+
+use std::ops::Range;
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        entrypoint::ProgramResult,
+        instruction::{Instruction, InstructionError},
+    },
+};
+use anchor_spl::{associated_token::get_associated_token_address, token};
+use fee_routing::{
+    meteora,
+    state::{DistributionHistory, Policy, Progress},
+};
+use fee_routing_client::{
+    accounts::decode,
+    instructions::{self, DistributeFeesAccounts, InvestorAccounts, PolicyParams},
+    pda,
+};
+use solana_keypair::Keypair;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+use streamflow_sdk::state::Contract;
+
+use crate::{fixtures, mock_cp_amm};
+
+/// 2025-01-01T00:00:00Z
+pub const DEFAULT_START_TS: i64 = 1_735_689_600;
+
+/// Quote and base liquidity each pool vault starts with
+pub const POOL_VAULT_BALANCE: u64 = u64::MAX / 4;
+
+/// The `Policy` parameters a harness is initialized with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PolicyTerms {
+    pub y0: u64,
+    pub investor_fee_share_bps: u16,
+    pub daily_cap_lamports: u64,
+    pub min_payout_lamports: u64,
+}
+
+/// Collects investors before the test validator starts
+pub struct HarnessBuilder {
+    terms: PolicyTerms,
+    start_ts: i64,
+    streams: Vec<Contract>,
+}
+
+impl HarnessBuilder {
+    pub fn new(terms: PolicyTerms) -> Self {
+        Self {
+            terms,
+            start_ts: DEFAULT_START_TS,
+            streams: Vec::new(),
+        }
+    }
+
+    /// Cluster time when the harness starts
+    pub fn start_ts(mut self, start_ts: i64) -> Self {
+        self.start_ts = start_ts;
+        self
+    }
+
+    /// Add an investor vesting through `stream`; an unset recipient gets a
+    /// fresh wallet and an unset mint becomes the quote mint
+    pub fn investor(mut self, stream: Contract) -> Self {
+        self.streams.push(stream);
+        self
+    }
+
+    pub fn investors(mut self, streams: impl IntoIterator<Item = Contract>) -> Self {
+        self.streams.extend(streams);
+        self
+    }
+
+    /// Start the bank and initialize policy, progress and history
+    pub async fn start(self) -> std::result::Result<Harness, BanksClientError> {
+        let mut program_test = ProgramTest::default();
+        // Native processors, so no BPF build is needed
+        program_test.prefer_bpf(false);
+        program_test.add_program(
+            "fee_routing",
+            fee_routing::ID,
+            processor!(fee_routing_entry),
+        );
+        program_test.add_program(
+            "mock_cp_amm",
+            meteora::cp_amm_program_id(),
+            processor!(mock_cp_amm::process_instruction),
+        );
+
+        let quote_mint = Pubkey::new_unique();
+        let base_mint = Pubkey::new_unique();
+        program_test.add_account(quote_mint, fixtures::mint_account(6));
+        program_test.add_account(base_mint, fixtures::mint_account(9));
+
+        // Quote-only pool and the honorary position in it, held through its
+        // NFT by the position owner PDA
+        let pool = Pubkey::new_unique();
+        let pool_token_a_vault = Pubkey::new_unique();
+        let pool_token_b_vault = Pubkey::new_unique();
+        program_test.add_account(
+            pool,
+            fixtures::PoolBuilder::new(base_mint, quote_mint)
+                .vaults(pool_token_a_vault, pool_token_b_vault)
+                .account(),
+        );
+        for (address, mint) in [
+            (pool_token_a_vault, base_mint),
+            (pool_token_b_vault, quote_mint),
+        ] {
+            program_test.add_account(
+                address,
+                fixtures::token_account(mint, meteora::pool_authority(), POOL_VAULT_BALANCE),
+            );
+        }
+
+        let vault = Pubkey::new_unique();
+        let nft_mint = Pubkey::new_unique();
+        let position = pda::cp_amm::position(&nft_mint).0;
+        let position_nft_account = pda::cp_amm::position_nft_account(&nft_mint).0;
+        program_test.add_account(
+            position,
+            fixtures::PositionBuilder::new(pool, nft_mint).account(),
+        );
+        program_test.add_account(
+            position_nft_account,
+            fixtures::token_account(nft_mint, pda::position_owner(&vault).0, 1),
+        );
+
+        let treasury_token_a = instructions::treasury_token_account(&base_mint, &token::ID);
+        let treasury_token_b = instructions::treasury_token_account(&quote_mint, &token::ID);
+        for (address, mint) in [
+            (treasury_token_a, base_mint),
+            (treasury_token_b, quote_mint),
+        ] {
+            program_test.add_account(
+                address,
+                fixtures::token_account(mint, pda::treasury_authority().0, 0),
+            );
+        }
+
+        let creator_wallet = Pubkey::new_unique();
+        let creator_ata = get_associated_token_address(&creator_wallet, &quote_mint);
+        program_test.add_account(
+            creator_ata,
+            fixtures::token_account(quote_mint, creator_wallet, 0),
+        );
+
+        let mut investors = Vec::with_capacity(self.streams.len());
+        for mut contract in self.streams {
+            if contract.recipient == Pubkey::default() {
+                contract.recipient = Pubkey::new_unique();
+            }
+            if contract.mint == Pubkey::default() {
+                contract.mint = quote_mint;
+            }
+            let wallet = contract.recipient;
+
+            let accounts = InvestorAccounts {
+                stream: Pubkey::new_unique(),
+                ata: get_associated_token_address(&wallet, &quote_mint),
+            };
+            program_test.add_account(accounts.stream, fixtures::stream_account(&contract));
+            program_test.add_account(accounts.ata, fixtures::token_account(quote_mint, wallet, 0));
+            investors.push(accounts);
+        }
+
+        let context = program_test.start_with_context().await;
+        let authority = context.payer.pubkey();
+        let mut harness = Harness {
+            accounts: DistributeFeesAccounts {
+                caller: authority,
+                vault,
+                pool,
+                position,
+                position_nft_account,
+                treasury_token_a,
+                treasury_token_b,
+                pool_token_a_vault,
+                pool_token_b_vault,
+                token_a_mint: base_mint,
+                token_b_mint: quote_mint,
+                token_a_program: token::ID,
+                token_b_program: token::ID,
+                creator_ata,
+            },
+            investors,
+            quote_mint,
+            creator_wallet,
+            context,
+        };
+
+        harness.set_time(self.start_ts).await?;
+        let params = PolicyParams {
+            y0: self.terms.y0,
+            investor_fee_share_bps: self.terms.investor_fee_share_bps,
+            daily_cap_lamports: self.terms.daily_cap_lamports,
+            min_payout_lamports: self.terms.min_payout_lamports,
+            quote_mint,
+            creator_wallet,
+        };
+        harness
+            .process(&[
+                instructions::initialize_policy(authority, &params),
+                instructions::initialize_progress(authority),
+                instructions::initialize_history(authority),
+            ])
+            .await?;
+        let streams: Vec<Pubkey> = harness.investors.iter().map(|inv| inv.stream).collect();
+        for stream in streams {
+            harness
+                .process(&[instructions::initialize_investor_ledger(
+                    authority, authority, stream,
+                )])
+                .await?;
+        }
+        Ok(harness)
+    }
+}
+
+/// A started bank with the fee-routing program, the mock CP-AMM and one
+/// Streamflow contract per investor
+///
+/// The test payer is the policy authority and the crank caller.
+pub struct Harness {
+    pub context: ProgramTestContext,
+    /// Fixed `distribute_fees` accounts
+    pub accounts: DistributeFeesAccounts,
+    /// Investors in the order they were added
+    pub investors: Vec<InvestorAccounts>,
+    pub quote_mint: Pubkey,
+    pub creator_wallet: Pubkey,
+}
+
+impl Harness {
+    pub fn builder(terms: PolicyTerms) -> HarnessBuilder {
+        HarnessBuilder::new(terms)
+    }
+
+    /// Sign `instructions` with the payer and process them in one transaction
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+    ) -> std::result::Result<(), BanksClientError> {
+        self.process_signed(instructions, &[]).await
+    }
+
+    /// Like [`Harness::process`], with `signers` signing alongside the payer
+    pub async fn process_signed(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<(), BanksClientError> {
+        // A fresh blockhash keeps identical pages on different days distinct
+        let blockhash = self.context.get_new_latest_blockhash().await?;
+        let payer = &self.context.payer;
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context.banks_client.process_transaction(tx).await
+    }
+
+    /// Add `fee_a` and `fee_b` to what the honorary position can claim
+    pub async fn accrue_fees(
+        &mut self,
+        fee_a: u64,
+        fee_b: u64,
+    ) -> std::result::Result<(), BanksClientError> {
+        let position = self.accounts.position;
+        let mut account = self
+            .context
+            .banks_client
+            .get_account(position)
+            .await?
+            .expect("position account exists");
+
+        let (pending_a, pending_b) = fixtures::pending_fees(&account.data);
+        fixtures::set_pending_fees(&mut account.data, pending_a + fee_a, pending_b + fee_b);
+
+        self.context.set_account(&position, &account.into());
+        Ok(())
+    }
+
+    /// Crank one page covering `investors` (indexes into [`Harness::investors`])
+    pub async fn distribute_page(
+        &mut self,
+        page_index: u16,
+        investors: Range<usize>,
+        is_final_page: bool,
+    ) -> std::result::Result<(), BanksClientError> {
+        let ix = instructions::distribute_fees(
+            &self.accounts,
+            &self.investors[investors],
+            page_index,
+            is_final_page,
+        );
+        self.process(&[ix]).await
+    }
+
+    /// Crank a whole day, `investors_per_page` investors at a time
+    pub async fn run_day(
+        &mut self,
+        investors_per_page: usize,
+    ) -> std::result::Result<(), BanksClientError> {
+        let count = self.investors.len();
+        let pages = count.div_ceil(investors_per_page.max(1)).max(1);
+        for page in 0..pages {
+            let start = page * investors_per_page;
+            let end = (start + investors_per_page).min(count);
+            self.distribute_page(page as u16, start..end, page + 1 == pages)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn now(&mut self) -> std::result::Result<i64, BanksClientError> {
+        let clock: Clock = self.context.banks_client.get_sysvar().await?;
+        Ok(clock.unix_timestamp)
+    }
+
+    /// Move the cluster clock to `unix_timestamp`
+    pub async fn set_time(
+        &mut self,
+        unix_timestamp: i64,
+    ) -> std::result::Result<(), BanksClientError> {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await?;
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+        Ok(())
+    }
+
+    /// Move the cluster clock forward by `seconds`
+    pub async fn advance(&mut self, seconds: i64) -> std::result::Result<(), BanksClientError> {
+        let now = self.now().await?;
+        self.set_time(now + seconds).await
+    }
+
+    /// Balance of an SPL token account, zero if it doesn't exist
+    pub async fn token_balance(
+        &mut self,
+        address: Pubkey,
+    ) -> std::result::Result<u64, BanksClientError> {
+        let Some(account) = self.context.banks_client.get_account(address).await? else {
+            return Ok(0);
+        };
+        let token_account = token::TokenAccount::try_deserialize(&mut &account.data[..])
+            .expect("not a token account");
+        Ok(token_account.amount)
+    }
+
+    pub async fn investor_balance(
+        &mut self,
+        investor: usize,
+    ) -> std::result::Result<u64, BanksClientError> {
+        let ata = self.investors[investor].ata;
+        self.token_balance(ata).await
+    }
+
+    pub async fn creator_balance(&mut self) -> std::result::Result<u64, BanksClientError> {
+        self.token_balance(self.accounts.creator_ata).await
+    }
+
+    pub async fn treasury_balance(&mut self) -> std::result::Result<u64, BanksClientError> {
+        self.token_balance(self.accounts.treasury_token_b).await
+    }
+
+    pub async fn policy(&mut self) -> std::result::Result<Policy, BanksClientError> {
+        self.program_account(pda::policy().0).await
+    }
+
+    pub async fn progress(&mut self) -> std::result::Result<Progress, BanksClientError> {
+        self.program_account(pda::progress().0).await
+    }
+
+    pub async fn history(&mut self) -> std::result::Result<DistributionHistory, BanksClientError> {
+        self.program_account(pda::history().0).await
+    }
+
+    async fn program_account<T: AccountDeserialize>(
+        &mut self,
+        address: Pubkey,
+    ) -> std::result::Result<T, BanksClientError> {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await?
+            .expect("program account exists");
+        Ok(decode(&account.data).expect("program account decodes"))
+    }
+}
+
+/// The custom error code a failed transaction returned, if any
+///
+/// Compare against `u32::from(FeeRoutingError::…)`.
+pub fn error_code(err: &BanksClientError) -> Option<u32> {
+    let tx_err = match err {
+        BanksClientError::TransactionError(e) => e,
+        BanksClientError::SimulationError { err, .. } => err,
+        _ => return None,
+    };
+    match tx_err {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(*code),
+        _ => None,
+    }
+}
+
+/// Anchor's `entry` wants `&'info [AccountInfo<'info>]`, but program-test
+/// lends the accounts for a shorter lifetime; leaking a copy is fine for the
+/// life of a test process
+fn fee_routing_entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    fee_routing::entry(program_id, accounts, data)
+}
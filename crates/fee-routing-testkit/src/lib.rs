@@ -0,0 +1,29 @@
+//! In-process test harness for the fee-routing program.
+//!
+//! [`Harness`] starts a `solana-program-test` bank with the fee-routing
+//! program and a native mock of the Meteora CP-AMM program, both running as
+//! native processors, so `distribute_fees` runs end to end without a
+//! validator, a BPF build or network access:
+//!
+//! - [`mock_cp_amm`]: `claim_position_fee` paying the position's pending
+//!   A/B fees from the pool vaults into the treasury
+//! - [`fixtures`]: builders for SPL token accounts, CP-AMM pools and
+//!   positions, and Streamflow contracts. Streamflow is never invoked, so its
+//!   contracts are just accounts owned by the Streamflow program id
+//! - [`harness`]: builder, crank helpers, clock control and balance and
+//!   state readers
+//!
+//! ```ignore
+//! let mut harness = Harness::builder(terms)
+//!     .investor(fixtures::linear_stream(1_000_000, start, 86_400, 10_000))
+//!     .start()
+//!     .await?;
+//! harness.accrue_fees(0, 50_000).await?;
+//! harness.run_day(4).await?;
+//! ```
+
+pub mod fixtures;
+pub mod harness;
+pub mod mock_cp_amm;
+
+pub use harness::{error_code, Harness, HarnessBuilder, PolicyTerms};
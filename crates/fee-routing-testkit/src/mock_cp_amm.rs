@@ -0,0 +1,118 @@
+//! Native stand-in for the Meteora CP-AMM program.
+//!
+//! Only `claim_position_fee` is implemented: it pays the position's pending
+//! A and B fees out of the pool vaults into the owner's token accounts and
+//! zeroes them, the same observable effect the real program has on the
+//! treasury. Pools and positions use the real account layouts, so tests set
+//! the pending amounts with [`crate::fixtures::PositionBuilder`] or
+//! [`crate::Harness::accrue_fees`].
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{entrypoint::ProgramResult, program::invoke_signed, program_pack::Pack},
+};
+use anchor_spl::token::spl_token;
+use fee_routing::meteora;
+
+use crate::fixtures::{
+    pending_fees, set_pending_fees, POOL_LEN, POOL_TOKEN_A_VAULT_OFFSET, POOL_TOKEN_B_VAULT_OFFSET,
+    POSITION_LEN, POSITION_NFT_MINT_OFFSET, POSITION_POOL_OFFSET,
+};
+
+/// Seed of the pool authority PDA that owns every pool vault
+pub const POOL_AUTHORITY_SEED: &[u8] = b"pool_authority";
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    match data.get(..8) {
+        Some(discriminator) if discriminator == meteora::CLAIM_POSITION_FEE_IX => {
+            claim_position_fee(program_id, accounts)
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Accounts in `meteora::claim_position_fee_cpi` order
+fn claim_position_fee(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let [pool_authority, pool, position, token_a_account, token_b_account, token_a_vault, token_b_vault, _token_a_mint, _token_b_mint, position_nft_account, owner, token_a_program, token_b_program, _event_authority, _program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (authority, bump) = Pubkey::find_program_address(&[POOL_AUTHORITY_SEED], program_id);
+    if pool_authority.key != &authority {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if position.owner != program_id || pool.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    {
+        let pool_data = pool.try_borrow_data()?;
+        if pool_data.len() < POOL_LEN
+            || read_pubkey(&pool_data, POOL_TOKEN_A_VAULT_OFFSET) != *token_a_vault.key
+            || read_pubkey(&pool_data, POOL_TOKEN_B_VAULT_OFFSET) != *token_b_vault.key
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    let (fee_a, fee_b) = {
+        let data = position.try_borrow_data()?;
+        if data.len() < POSITION_LEN || read_pubkey(&data, POSITION_POOL_OFFSET) != *pool.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // The position belongs to whoever holds its NFT
+        let nft = spl_token::state::Account::unpack(&position_nft_account.try_borrow_data()?)?;
+        if nft.owner != *owner.key
+            || nft.mint != read_pubkey(&data, POSITION_NFT_MINT_OFFSET)
+            || nft.amount != 1
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        pending_fees(&data)
+    };
+
+    let signer_seeds: &[&[&[u8]]] = &[&[POOL_AUTHORITY_SEED, &[bump]]];
+    for (amount, vault, destination, token_program) in [
+        (fee_a, token_a_vault, token_a_account, token_a_program),
+        (fee_b, token_b_vault, token_b_account, token_b_program),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        let ix = spl_token::instruction::transfer(
+            token_program.key,
+            vault.key,
+            destination.key,
+            pool_authority.key,
+            &[],
+            amount,
+        )?;
+        invoke_signed(
+            &ix,
+            &[
+                vault.clone(),
+                destination.clone(),
+                pool_authority.clone(),
+                token_program.clone(),
+            ],
+            signer_seeds,
+        )?;
+    }
+
+    set_pending_fees(&mut position.try_borrow_mut_data()?, 0, 0);
+    Ok(())
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::try_from(&data[offset..offset + 32]).unwrap()
+}
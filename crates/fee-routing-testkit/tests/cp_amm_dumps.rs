@@ -0,0 +1,98 @@
+//! The zero-copy CP-AMM `Pool` and `Position` readers against real cluster
+//! accounts, dumped with `scripts/dump-cp-amm-fixtures.ts` together with the
+//! fields Meteora's own SDK decodes from them.
+
+use std::{collections::HashMap, fs, path::Path, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use fee_routing::meteora::{cp_amm_program_id, CpAmmAccount, Pool, Position};
+
+const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/cp-amm");
+
+/// One `<name>.expected` file with its two account dumps
+struct Dump {
+    name: String,
+    expected: HashMap<String, String>,
+    pool: Vec<u8>,
+    position: Vec<u8>,
+}
+
+impl Dump {
+    fn field(&self, key: &str) -> &str {
+        self.expected
+            .get(key)
+            .unwrap_or_else(|| panic!("{}: no `{}` in the expected file", self.name, key))
+    }
+
+    fn pubkey(&self, key: &str) -> Pubkey {
+        Pubkey::from_str(self.field(key)).unwrap()
+    }
+
+    fn number<T: FromStr>(&self, key: &str) -> T
+    where
+        T::Err: std::fmt::Debug,
+    {
+        self.field(key).parse().unwrap()
+    }
+}
+
+fn dumps() -> Vec<Dump> {
+    let mut dumps: Vec<Dump> = fs::read_dir(FIXTURES_DIR)
+        .unwrap()
+        .filter_map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path
+                .file_name()?
+                .to_str()?
+                .strip_suffix(".expected")?
+                .to_string();
+            let dir = Path::new(FIXTURES_DIR);
+            let expected = fs::read_to_string(&path)
+                .unwrap()
+                .lines()
+                .filter_map(|line| line.split_once(" = "))
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect();
+            Some(Dump {
+                pool: fs::read(dir.join(format!("{name}.pool.bin"))).unwrap(),
+                position: fs::read(dir.join(format!("{name}.position.bin"))).unwrap(),
+                name,
+                expected,
+            })
+        })
+        .collect();
+    dumps.sort_by(|a, b| a.name.cmp(&b.name));
+    dumps
+}
+
+#[test]
+#[ignore = "needs cluster dumps: run scripts/dump-cp-amm-fixtures.ts and commit its output"]
+fn test_readers_decode_cluster_dumps() {
+    let dumps = dumps();
+    assert!(!dumps.is_empty(), "no dumps in {FIXTURES_DIR}");
+
+    for dump in &dumps {
+        let name = &dump.name;
+        let pool = Pool::from_account_data(&cp_amm_program_id(), &dump.pool).unwrap();
+        assert_eq!(pool.token_a_mint, dump.pubkey("token_a_mint"), "{name}");
+        assert_eq!(pool.token_b_mint, dump.pubkey("token_b_mint"), "{name}");
+        let collect_fee_mode: u8 = dump.number("collect_fee_mode");
+        assert_eq!(pool.collect_fee_mode, collect_fee_mode, "{name}");
+        let liquidity: u128 = dump.number("liquidity");
+        assert_eq!({ pool.liquidity }, liquidity, "{name}");
+
+        let position = Position::from_account_data(&cp_amm_program_id(), &dump.position).unwrap();
+        assert_eq!(position.pool, dump.pubkey("pool"), "{name}");
+        assert_eq!(position.nft_mint, dump.pubkey("nft_mint"), "{name}");
+        let unlocked_liquidity: u128 = dump.number("unlocked_liquidity");
+        assert_eq!(
+            { position.unlocked_liquidity },
+            unlocked_liquidity,
+            "{name}"
+        );
+
+        // Neither account passes for the other
+        assert!(Position::from_bytes(&dump.pool).is_err(), "{name}");
+        assert!(Pool::from_bytes(&dump.position).is_err(), "{name}");
+    }
+}
@@ -0,0 +1,387 @@
+//! `distribute_fees` end to end against the mock CP-AMM and fixture streams.
+
+use anchor_lang::prelude::Pubkey;
+use fee_routing::{errors::FeeRoutingError, math::DistributionMath};
+use fee_routing_client::instructions::{self, InvestorAccounts};
+use fee_routing_testkit::{
+    error_code,
+    fixtures::{linear_stream, stream_account, token_account},
+    harness::DEFAULT_START_TS,
+    Harness, PolicyTerms,
+};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const DAY: i64 = 86_400;
+
+fn terms() -> PolicyTerms {
+    PolicyTerms {
+        y0: 10_000_000,
+        investor_fee_share_bps: 6_000,
+        daily_cap_lamports: 0,
+        min_payout_lamports: 1_000,
+    }
+}
+
+/// Nothing has vested at the start, so these lock 4M, 3M and 1M
+fn streams() -> Vec<streamflow_sdk::state::Contract> {
+    let start = DEFAULT_START_TS as u64;
+    vec![
+        linear_stream(4_000_000, start, DAY as u64, 40_000),
+        linear_stream(3_000_000, start, DAY as u64, 30_000),
+        linear_stream(1_000_000, start, DAY as u64, 10_000),
+    ]
+}
+
+#[tokio::test]
+async fn test_single_page_day_pays_investors_and_creator() {
+    let mut harness = Harness::builder(terms())
+        .investors(streams())
+        .start()
+        .await
+        .unwrap();
+    harness.accrue_fees(0, 1_000_000).await.unwrap();
+
+    harness.run_day(4).await.unwrap();
+
+    let policy = harness.policy().await.unwrap();
+    let budget = 1_000_000 * u64::from(policy.investor_fee_share_bps) / 10_000;
+    let day = DistributionMath::day_terms(&policy, 1_000_000, 8_000_000).unwrap();
+    let expected = DistributionMath::distribute_page(
+        &policy,
+        &day,
+        budget,
+        &[4_000_000, 3_000_000, 1_000_000],
+        &[],
+        &[],
+        0,
+    )
+    .unwrap();
+    for (i, share) in expected.shares.iter().enumerate() {
+        assert!(share.paid);
+        assert_eq!(harness.investor_balance(i).await.unwrap(), share.payout);
+    }
+    assert_eq!(
+        harness.creator_balance().await.unwrap(),
+        1_000_000 - expected.investor_allocation
+    );
+    assert_eq!(
+        harness.treasury_balance().await.unwrap(),
+        expected.carry_over().unwrap()
+    );
+
+    let progress = harness.progress().await.unwrap();
+    assert_eq!(progress.current_day, 1);
+    assert_eq!(progress.day_claimed_quote, 1_000_000);
+    assert!(progress.creator_payout_sent);
+    assert_eq!(
+        progress.buckets.carry_over().unwrap(),
+        expected.carry_over().unwrap()
+    );
+    // Each ledger registered its stream's deposit and locked amount
+    assert_eq!(progress.registered_deposits, 8_000_000);
+    assert_eq!(progress.registered_locked, 8_000_000);
+    assert_eq!(progress.day_locked_basis, 8_000_000);
+
+    let history = harness.history().await.unwrap();
+    assert_eq!(history.total_days_recorded, 1);
+}
+
+#[tokio::test]
+async fn test_window_gates_next_day() {
+    let mut harness = Harness::builder(terms())
+        .investors(streams())
+        .start()
+        .await
+        .unwrap();
+    harness.accrue_fees(0, 500_000).await.unwrap();
+    harness.run_day(4).await.unwrap();
+
+    harness.accrue_fees(0, 500_000).await.unwrap();
+    harness.advance(DAY - 1).await.unwrap();
+    let err = harness.run_day(4).await.unwrap_err();
+    assert_eq!(
+        error_code(&err),
+        Some(u32::from(FeeRoutingError::DistributionWindowNotElapsed))
+    );
+
+    harness.advance(1).await.unwrap();
+    harness.run_day(4).await.unwrap();
+    assert_eq!(harness.progress().await.unwrap().current_day, 2);
+}
+
+#[tokio::test]
+async fn test_multi_page_day_keeps_page_order() {
+    let mut harness = Harness::builder(terms())
+        .investors(streams())
+        .start()
+        .await
+        .unwrap();
+    harness.accrue_fees(0, 1_000_000).await.unwrap();
+
+    // Page 1 can't run before page 0
+    let err = harness.distribute_page(1, 2..3, true).await.unwrap_err();
+    assert_eq!(
+        error_code(&err),
+        Some(u32::from(FeeRoutingError::InvalidPageIndex))
+    );
+
+    harness.distribute_page(0, 0..2, false).await.unwrap();
+    let progress = harness.progress().await.unwrap();
+    assert_eq!(progress.current_page, 1);
+    assert!(!progress.creator_payout_sent);
+
+    harness.distribute_page(1, 2..3, true).await.unwrap();
+    let progress = harness.progress().await.unwrap();
+    assert_eq!(progress.pages_processed_today, 2);
+    assert!(progress.creator_payout_sent);
+
+    // The 60% share of 1M is fixed for the day against all 8M locked, so
+    // page 0 can't take the last page's 1/8
+    assert_eq!(progress.day_investor_allocation, 600_000);
+    assert_eq!(harness.investor_balance(0).await.unwrap(), 300_000);
+    assert_eq!(harness.investor_balance(1).await.unwrap(), 225_000);
+    assert_eq!(harness.investor_balance(2).await.unwrap(), 75_000);
+}
+
+#[tokio::test]
+async fn test_base_fees_fail_the_claim() {
+    let mut harness = Harness::builder(terms())
+        .investors(streams())
+        .start()
+        .await
+        .unwrap();
+    harness.accrue_fees(1, 1_000_000).await.unwrap();
+
+    let err = harness.run_day(4).await.unwrap_err();
+    assert_eq!(
+        error_code(&err),
+        Some(u32::from(FeeRoutingError::BaseFeesDetected))
+    );
+    // The failed transaction rolled back the claim too
+    assert_eq!(harness.treasury_balance().await.unwrap(), 0);
+    assert_eq!(harness.creator_balance().await.unwrap(), 0);
+    assert_eq!(harness.progress().await.unwrap().current_day, 0);
+}
+
+#[tokio::test]
+async fn test_duplicate_stream_is_rejected() {
+    let mut harness = Harness::builder(terms())
+        .investors(streams())
+        .start()
+        .await
+        .unwrap();
+    harness.accrue_fees(0, 1_000_000).await.unwrap();
+
+    // The same triple twice on one page would release its owed balance twice
+    let repeated = [
+        harness.investors[0],
+        harness.investors[1],
+        harness.investors[0],
+    ];
+    let ix = instructions::distribute_fees(&harness.accounts, &repeated, 0, true);
+    let err = harness.process(&[ix]).await.unwrap_err();
+    assert_eq!(
+        error_code(&err),
+        Some(u32::from(FeeRoutingError::DuplicateInvestor))
+    );
+    assert_eq!(harness.treasury_balance().await.unwrap(), 0);
+
+    // Nor may a later page of the same day pay it again
+    harness.distribute_page(0, 0..2, false).await.unwrap();
+    let err = harness.distribute_page(1, 1..3, true).await.unwrap_err();
+    assert_eq!(
+        error_code(&err),
+        Some(u32::from(FeeRoutingError::DuplicateInvestor))
+    );
+    harness.distribute_page(1, 2..3, true).await.unwrap();
+    assert!(harness.progress().await.unwrap().creator_payout_sent);
+}
+
+#[tokio::test]
+async fn test_payouts_only_reach_the_stream_recipient() {
+    let mut harness = Harness::builder(terms())
+        .investors(streams())
+        .start()
+        .await
+        .unwrap();
+    harness.accrue_fees(0, 1_000_000).await.unwrap();
+
+    // Someone else's stream and ledger next to the caller's own quote account
+    let thief = Pubkey::new_unique();
+    let thief_ata = Pubkey::new_unique();
+    harness.context.set_account(
+        &thief_ata,
+        &token_account(harness.quote_mint, thief, 0).into(),
+    );
+    let triples = [
+        InvestorAccounts {
+            stream: harness.investors[0].stream,
+            ata: thief_ata,
+        },
+        harness.investors[1],
+        harness.investors[2],
+    ];
+    let ix = instructions::distribute_fees(&harness.accounts, &triples, 0, true);
+    let err = harness.process(&[ix]).await.unwrap_err();
+    assert_eq!(
+        error_code(&err),
+        Some(u32::from(FeeRoutingError::InvalidAccountOwnership))
+    );
+    assert_eq!(harness.token_balance(thief_ata).await.unwrap(), 0);
+
+    harness.run_day(4).await.unwrap();
+    assert_eq!(harness.investor_balance(0).await.unwrap(), 300_000);
+}
+
+#[tokio::test]
+async fn test_sunset_flushes_owed_balances_only_to_the_recipient() {
+    let start = DEFAULT_START_TS as u64;
+    let mut harness = Harness::builder(terms())
+        .investor(linear_stream(1_000_000, start, DAY as u64, 1_000_000))
+        .start()
+        .await
+        .unwrap();
+
+    // 10% locked: the 100 lamport payout is under the minimum and owed
+    harness.accrue_fees(0, 1_000).await.unwrap();
+    harness.run_day(4).await.unwrap();
+    assert_eq!(harness.progress().await.unwrap().buckets.owed, 100);
+
+    // The next day finds the stream fully vested and sunsets distribution
+    harness.advance(2 * DAY).await.unwrap();
+    harness.run_day(4).await.unwrap();
+    assert_ne!(harness.progress().await.unwrap().fully_vested_at, 0);
+
+    // After sunset the stream isn't read, but its ledger still names who
+    // the owed balance belongs to
+    harness.advance(DAY).await.unwrap();
+    let thief = Pubkey::new_unique();
+    let thief_ata = Pubkey::new_unique();
+    harness.context.set_account(
+        &thief_ata,
+        &token_account(harness.quote_mint, thief, 0).into(),
+    );
+    let triple = InvestorAccounts {
+        stream: harness.investors[0].stream,
+        ata: thief_ata,
+    };
+    let ix = instructions::distribute_fees(&harness.accounts, &[triple], 0, true);
+    let err = harness.process(&[ix]).await.unwrap_err();
+    assert_eq!(
+        error_code(&err),
+        Some(u32::from(FeeRoutingError::InvalidAccountOwnership))
+    );
+    assert_eq!(harness.token_balance(thief_ata).await.unwrap(), 0);
+
+    harness.run_day(4).await.unwrap();
+    assert_eq!(harness.investor_balance(0).await.unwrap(), 100);
+    assert_eq!(harness.progress().await.unwrap().buckets.owed, 0);
+}
+
+#[tokio::test]
+async fn test_final_page_waits_for_every_stream() {
+    let mut harness = Harness::builder(terms())
+        .investors(streams())
+        .start()
+        .await
+        .unwrap();
+    harness.accrue_fees(0, 1_000_000).await.unwrap();
+
+    // An empty final page 0 would hand the whole investor budget to the creator
+    let err = harness.distribute_page(0, 0..0, true).await.unwrap_err();
+    assert_eq!(
+        error_code(&err),
+        Some(u32::from(FeeRoutingError::StreamsNotAllRead))
+    );
+    assert_eq!(harness.creator_balance().await.unwrap(), 0);
+    assert_eq!(harness.progress().await.unwrap().current_day, 0);
+
+    // So would a final page that leaves out the last stream
+    harness.distribute_page(0, 0..2, false).await.unwrap();
+    let err = harness.distribute_page(1, 2..2, true).await.unwrap_err();
+    assert_eq!(
+        error_code(&err),
+        Some(u32::from(FeeRoutingError::StreamsNotAllRead))
+    );
+
+    harness.distribute_page(1, 2..3, true).await.unwrap();
+    assert_eq!(harness.investor_balance(2).await.unwrap(), 75_000);
+    assert_eq!(harness.creator_balance().await.unwrap(), 400_000);
+}
+
+#[tokio::test]
+async fn test_only_the_authority_registers_streams() {
+    let mut harness = Harness::builder(terms())
+        .investors(streams())
+        .start()
+        .await
+        .unwrap();
+    let before = harness.progress().await.unwrap();
+
+    // Registering moves the deposits and locked amounts the split rests on
+    let outsider = Keypair::new();
+    let payer = harness.context.payer.pubkey();
+    let contract = linear_stream(5_000_000, DEFAULT_START_TS as u64, DAY as u64, 50_000);
+    let stream = Pubkey::new_unique();
+    harness
+        .context
+        .set_account(&stream, &stream_account(&contract).into());
+    let ix = instructions::initialize_investor_ledger(payer, outsider.pubkey(), stream);
+    let err = harness
+        .process_signed(&[ix], &[&outsider])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error_code(&err),
+        Some(u32::from(FeeRoutingError::Unauthorized))
+    );
+    let after = harness.progress().await.unwrap();
+    assert_eq!(after.registered_deposits, before.registered_deposits);
+    assert_eq!(after.registered_streams, before.registered_streams);
+
+    // The test payer is the authority
+    let ix = instructions::initialize_investor_ledger(payer, payer, stream);
+    harness.process(&[ix]).await.unwrap();
+    let after = harness.progress().await.unwrap();
+    assert_eq!(
+        after.registered_deposits,
+        before.registered_deposits + 5_000_000
+    );
+    assert_eq!(after.registered_streams, before.registered_streams + 1);
+}
+
+#[tokio::test]
+async fn test_no_registration_after_sunset() {
+    let start = DEFAULT_START_TS as u64;
+    let mut harness = Harness::builder(terms())
+        .investor(linear_stream(1_000_000, start, DAY as u64, 1_000_000))
+        .start()
+        .await
+        .unwrap();
+
+    // A day after the only stream fully vested sunsets distribution
+    harness.advance(2 * DAY).await.unwrap();
+    harness.accrue_fees(0, 1_000_000).await.unwrap();
+    harness.run_day(4).await.unwrap();
+    let before = harness.progress().await.unwrap();
+    assert_ne!(before.fully_vested_at, 0);
+
+    // A new stream can't reopen it
+    let payer = harness.context.payer.pubkey();
+    let contract = linear_stream(5_000_000, start, DAY as u64, 50_000);
+    let stream = Pubkey::new_unique();
+    harness
+        .context
+        .set_account(&stream, &stream_account(&contract).into());
+    let ix = instructions::initialize_investor_ledger(payer, payer, stream);
+    let err = harness.process(&[ix]).await.unwrap_err();
+    assert_eq!(
+        error_code(&err),
+        Some(u32::from(FeeRoutingError::DistributionSunset))
+    );
+    assert_eq!(
+        harness.progress().await.unwrap().fully_vested_at,
+        before.fully_vested_at
+    );
+}
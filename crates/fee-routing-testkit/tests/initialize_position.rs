@@ -0,0 +1,93 @@
+//! `initialize_position` pool checks. Each case is rejected before the
+//! `create_position` CPI, so the mock CP-AMM never has to implement it.
+
+use anchor_lang::prelude::Pubkey;
+use fee_routing::errors::FeeRoutingError;
+use fee_routing_client::instructions::{self, InitializePositionAccounts};
+use fee_routing_testkit::{
+    error_code,
+    fixtures::{owned_by, PoolBuilder, COLLECT_FEE_MODE_BOTH_TOKEN},
+    Harness, PolicyTerms,
+};
+use solana_account::Account;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+fn terms() -> PolicyTerms {
+    PolicyTerms {
+        y0: 10_000_000,
+        investor_fee_share_bps: 6_000,
+        daily_cap_lamports: 0,
+        min_payout_lamports: 1_000,
+    }
+}
+
+/// Plant `pool` at a fresh address and return the error code from opening
+/// the honorary position in it
+async fn initialize_in(mut harness: Harness, pool: Account) -> Option<u32> {
+    let pool_address = Pubkey::new_unique();
+    harness.context.set_account(&pool_address, &pool.into());
+
+    let nft_mint = Keypair::new();
+    let ix = instructions::initialize_position(&InitializePositionAccounts {
+        authority: harness.context.payer.pubkey(),
+        vault: harness.accounts.vault,
+        position_nft_mint: nft_mint.pubkey(),
+        pool: pool_address,
+        quote_mint: harness.quote_mint,
+    });
+    let err = harness
+        .process_signed(&[ix], &[&nft_mint])
+        .await
+        .unwrap_err();
+    error_code(&err)
+}
+
+#[tokio::test]
+async fn test_rejects_pool_collecting_fees_in_both_tokens() {
+    let harness = Harness::builder(terms()).start().await.unwrap();
+    let pool = PoolBuilder::new(harness.accounts.token_a_mint, harness.quote_mint)
+        .collect_fee_mode(COLLECT_FEE_MODE_BOTH_TOKEN)
+        .account();
+
+    assert_eq!(
+        initialize_in(harness, pool).await,
+        Some(u32::from(FeeRoutingError::PoolNotQuoteOnly))
+    );
+}
+
+#[tokio::test]
+async fn test_rejects_quote_only_pool_in_another_mint() {
+    let harness = Harness::builder(terms()).start().await.unwrap();
+    // Collects only token B, but token B isn't the policy's quote mint
+    let pool = PoolBuilder::new(harness.accounts.token_a_mint, Pubkey::new_unique()).account();
+
+    assert_eq!(
+        initialize_in(harness, pool).await,
+        Some(u32::from(FeeRoutingError::PoolQuoteMintMismatch))
+    );
+}
+
+#[tokio::test]
+async fn test_rejects_pool_with_quote_mint_as_token_a() {
+    let harness = Harness::builder(terms()).start().await.unwrap();
+    // The quote mint on the wrong side: OnlyB fees would be paid in the base
+    let pool = PoolBuilder::new(harness.quote_mint, harness.accounts.token_a_mint).account();
+
+    assert_eq!(
+        initialize_in(harness, pool).await,
+        Some(u32::from(FeeRoutingError::PoolQuoteMintMismatch))
+    );
+}
+
+#[tokio::test]
+async fn test_rejects_pool_not_owned_by_cp_amm() {
+    let harness = Harness::builder(terms()).start().await.unwrap();
+    let data = PoolBuilder::new(harness.accounts.token_a_mint, harness.quote_mint).data();
+    let pool = owned_by(data, Pubkey::new_unique());
+
+    assert_eq!(
+        initialize_in(harness, pool).await,
+        Some(u32::from(FeeRoutingError::InvalidPoolAccount))
+    );
+}
@@ -0,0 +1,112 @@
+//! Locked-amount edge cases, read from fixture contract bytes exactly as the
+//! `distribute_fees` handler reads them.
+
+use anchor_lang::{prelude::Pubkey, AnchorDeserialize};
+use fee_routing::math::DistributionMath;
+use fee_routing_testkit::fixtures::{StreamBuilder, CONTRACT_LEN};
+use streamflow_sdk::state::Contract;
+
+/// Decode the account like the handler does, then read the locked amount
+fn locked_at(stream: &StreamBuilder, now: u64) -> u64 {
+    let account = stream.account();
+    assert_eq!(account.owner, streamflow_sdk::id());
+    assert!(account.data.len() >= 400);
+    let contract = Contract::try_from_slice(&account.data).unwrap();
+    DistributionMath::locked_amount(&contract, now).unwrap()
+}
+
+#[test]
+fn test_account_round_trips_through_handler_decoding() {
+    let recipient = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let stream = StreamBuilder::new(1_000, 100, 10, 100)
+        .cliff(100, 50)
+        .withdrawn(300)
+        .recipient(recipient)
+        .mint(mint);
+
+    let data = stream.data();
+    assert_eq!(data.len(), CONTRACT_LEN);
+
+    let contract = Contract::try_from_slice(&data).unwrap();
+    assert_eq!(contract.recipient, recipient);
+    assert_eq!(contract.mint, mint);
+    assert_eq!(contract.amount_withdrawn, 300);
+    assert_eq!(contract.ix.net_amount_deposited, 1_000);
+    assert_eq!(contract.ix.cliff_amount, 50);
+    // (1_000 - 50) / 100 rounds up to 10 periods
+    assert_eq!(contract.end_time, 200);
+}
+
+#[test]
+fn test_fully_locked_before_start() {
+    let stream = StreamBuilder::new(1_000, 100, 10, 100);
+    assert_eq!(locked_at(&stream, 0), 1_000);
+    assert_eq!(locked_at(&stream, 99), 1_000);
+}
+
+#[test]
+fn test_unlocks_whole_periods_only() {
+    let stream = StreamBuilder::new(1_000, 100, 10, 100);
+    assert_eq!(locked_at(&stream, 100), 1_000);
+    assert_eq!(locked_at(&stream, 109), 1_000);
+    assert_eq!(locked_at(&stream, 110), 900);
+    assert_eq!(locked_at(&stream, 155), 500);
+}
+
+#[test]
+fn test_last_partial_period_unlocks_at_end_time() {
+    // 1_000 at 300 per period: three full periods, then 100
+    let stream = StreamBuilder::new(1_000, 0, 10, 300);
+    assert_eq!(stream.end_time(), 40);
+    assert_eq!(locked_at(&stream, 30), 100);
+    assert_eq!(locked_at(&stream, 39), 100);
+    assert_eq!(locked_at(&stream, 40), 0);
+    assert_eq!(locked_at(&stream, u64::MAX), 0);
+}
+
+#[test]
+fn test_cliff_amount_unlocks_at_cliff() {
+    let stream = StreamBuilder::new(1_000, 100, 10, 100).cliff(100, 400);
+    assert_eq!(locked_at(&stream, 99), 1_000);
+    assert_eq!(locked_at(&stream, 100), 600);
+    assert_eq!(locked_at(&stream, 120), 400);
+    assert_eq!(stream.end_time(), 160);
+    assert_eq!(locked_at(&stream, 160), 0);
+}
+
+#[test]
+fn test_withdrawals_do_not_change_locked() {
+    let stream = StreamBuilder::new(1_000, 100, 10, 100);
+    let withdrawn = stream.clone().withdrawn(400);
+    for now in [50, 100, 140, 155, 200] {
+        assert_eq!(locked_at(&stream, now), locked_at(&withdrawn, now));
+    }
+}
+
+#[test]
+fn test_zero_rate_stays_locked() {
+    let stream = StreamBuilder::new(1_000, 100, 10, 0);
+    assert_eq!(stream.end_time(), u64::MAX);
+    assert_eq!(locked_at(&stream, 1_000_000), 1_000);
+}
+
+#[test]
+fn test_empty_stream_locks_nothing() {
+    let stream = StreamBuilder::new(0, 100, 10, 100);
+    assert_eq!(locked_at(&stream, 0), 0);
+    assert_eq!(locked_at(&stream, 150), 0);
+}
+
+#[test]
+fn test_locked_never_increases() {
+    let stream = StreamBuilder::new(12_345, 1_000, 7, 89).cliff(1_000, 1_111);
+    let mut previous = u64::MAX;
+    for now in (900..3_000).step_by(3) {
+        let locked = locked_at(&stream, now);
+        assert!(locked <= previous, "locked went up at {now}");
+        assert!(locked <= 12_345);
+        previous = locked;
+    }
+    assert_eq!(previous, 0);
+}
@@ -0,0 +1,138 @@
+//! `migrate_accounts` growing first-release Policy and Progress accounts.
+
+use anchor_lang::{AnchorSerialize, Discriminator};
+use fee_routing::{
+    errors::FeeRoutingError,
+    state::{Buckets, Policy, PolicyV1, Progress, ProgressV1},
+};
+use fee_routing_client::{instructions, pda};
+use fee_routing_testkit::{error_code, fixtures::owned_by, Harness, PolicyTerms};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+fn terms() -> PolicyTerms {
+    PolicyTerms {
+        y0: 10_000_000,
+        investor_fee_share_bps: 6_000,
+        daily_cap_lamports: 0,
+        min_payout_lamports: 1_000,
+    }
+}
+
+fn v1_progress(current_page: u16, creator_payout_sent: bool) -> ProgressV1 {
+    ProgressV1 {
+        last_distribution_ts: 1_735_000_000,
+        current_day: 7,
+        daily_distributed_to_investors: 0,
+        carry_over_lamports: 42,
+        current_page,
+        pages_processed_today: current_page,
+        total_investors: 0,
+        creator_payout_sent,
+        has_base_fees: false,
+        total_rounding_dust: 9,
+        bump: pda::progress().1,
+    }
+}
+
+/// Overwrite the harness's Policy and Progress with first-release accounts
+async fn downgrade(harness: &mut Harness, progress: ProgressV1) {
+    let policy = harness.policy().await.unwrap();
+    let v1 = PolicyV1 {
+        y0: policy.y0,
+        investor_fee_share_bps: policy.investor_fee_share_bps,
+        daily_cap_lamports: policy.daily_cap_lamports,
+        min_payout_lamports: policy.min_payout_lamports,
+        quote_mint: policy.quote_mint,
+        creator_wallet: policy.creator_wallet,
+        authority: policy.authority,
+        bump: policy.bump,
+    };
+    let mut data = Policy::DISCRIMINATOR.to_vec();
+    data.extend(v1.try_to_vec().unwrap());
+    assert_eq!(data.len(), PolicyV1::LEN);
+    harness
+        .context
+        .set_account(&pda::policy().0, &owned_by(data, fee_routing::ID).into());
+
+    let mut data = Progress::DISCRIMINATOR.to_vec();
+    data.extend(progress.try_to_vec().unwrap());
+    assert_eq!(data.len(), ProgressV1::LEN);
+    harness
+        .context
+        .set_account(&pda::progress().0, &owned_by(data, fee_routing::ID).into());
+}
+
+#[tokio::test]
+async fn test_first_release_accounts_grow_to_the_current_layout() {
+    let mut harness = Harness::builder(terms()).start().await.unwrap();
+    downgrade(&mut harness, v1_progress(0, false)).await;
+    let payer = harness.context.payer.pubkey();
+
+    let outsider = Keypair::new();
+    let ix = instructions::migrate_accounts(outsider.pubkey());
+    let err = harness
+        .process_signed(&[ix], &[&outsider])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error_code(&err),
+        Some(u32::from(FeeRoutingError::Unauthorized))
+    );
+
+    harness
+        .process(&[instructions::migrate_accounts(payer)])
+        .await
+        .unwrap();
+    for (key, len) in [
+        (pda::policy().0, Policy::LEN),
+        (pda::progress().0, Progress::LEN),
+    ] {
+        let account = harness
+            .context
+            .banks_client
+            .get_account(key)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(account.data.len(), len);
+    }
+
+    let policy = harness.policy().await.unwrap();
+    assert_eq!(policy.y0, 10_000_000);
+    assert_eq!(policy.authority, payer);
+    assert_eq!(policy.policy_change_delay_seconds, 0);
+    let progress = harness.progress().await.unwrap();
+    assert_eq!(progress.current_day, 7);
+    assert_eq!(progress.total_rounding_dust, 9);
+    assert_eq!(
+        progress.buckets,
+        Buckets {
+            dust: 42,
+            ..Default::default()
+        }
+    );
+
+    // Accounts already in the current layout are left alone
+    harness
+        .process(&[instructions::migrate_accounts(payer)])
+        .await
+        .unwrap();
+    assert_eq!(harness.progress().await.unwrap().current_day, 7);
+}
+
+#[tokio::test]
+async fn test_migration_waits_for_the_day_to_close() {
+    let mut harness = Harness::builder(terms()).start().await.unwrap();
+    downgrade(&mut harness, v1_progress(2, false)).await;
+    let payer = harness.context.payer.pubkey();
+
+    let err = harness
+        .process(&[instructions::migrate_accounts(payer)])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error_code(&err),
+        Some(u32::from(FeeRoutingError::DistributionDayInProgress))
+    );
+}
@@ -13,12 +13,36 @@ pub const PROGRESS_SEED: &[u8] = b"progress";
 /// Seed for program quote treasury
 pub const TREASURY_SEED: &[u8] = b"treasury";
 
+/// Seed for distribution history ring buffer PDA
+pub const HISTORY_SEED: &[u8] = b"history";
+
+/// Seed for per-stream investor ledger PDAs
+pub const INVESTOR_LEDGER_SEED: &[u8] = b"investor_ledger";
+
+/// Seed for pending policy change PDAs
+pub const POLICY_CHANGE_SEED: &[u8] = b"policy_change";
+
 /// Seconds in 24 hours
 pub const DISTRIBUTION_WINDOW_SECONDS: i64 = 86_400;
 
 /// Basis points denominator (10000 = 100%)
 pub const BPS_DENOMINATOR: u64 = 10_000;
 
+/// Number of closed distribution days retained on-chain in `DistributionHistory`
+///
+/// Once full, the oldest day is overwritten. 30 days keeps the account around
+/// 2.4KB, which fits comfortably in a single `init` without realloc.
+pub const DISTRIBUTION_HISTORY_DAYS: usize = 30;
+
+/// Most steps a `Policy.fee_share_schedule` may hold; the policy account
+/// reserves room for all of them
+pub const MAX_FEE_SHARE_STEPS: usize = 8;
+
+/// Shortest non-zero `Policy.policy_change_delay_seconds`: one distribution
+/// window, so at least one day runs under the old policy after a change is
+/// queued
+pub const MIN_POLICY_CHANGE_DELAY_SECONDS: i64 = 86_400;
+
 /// Maximum investors per page during distribution
 ///
 /// This limit ensures transactions stay within Solana's constraints:
@@ -49,4 +49,67 @@ pub enum FeeRoutingError {
 
     #[msg("Too many investors in single page - exceeds maximum")]
     TooManyInvestors,
+
+    #[msg("Signer is not the policy authority")]
+    Unauthorized,
+
+    #[msg("Invalid policy parameter")]
+    InvalidPolicyParameter,
+
+    #[msg("A distribution day is in progress - finish its pages first")]
+    DistributionDayInProgress,
+
+    #[msg("Quote treasury holds less than the distribution ledger accounts for")]
+    TreasuryBelowLedger,
+
+    #[msg("Investor ledger is not the PDA for this stream")]
+    InvalidInvestorLedger,
+
+    #[msg("Fee share schedule must have strictly increasing start times and shares of at most 10000 bps")]
+    InvalidFeeShareSchedule,
+
+    #[msg("Policy changes are timelocked - queue them with queue_policy_change")]
+    PolicyChangeTimelocked,
+
+    #[msg("A queued policy change already covers one of these fields")]
+    PolicyChangeFieldPending,
+
+    #[msg("Policy change is still in its timelock delay")]
+    PolicyChangeNotMatured,
+
+    #[msg("Policy change has matured and can no longer be cancelled")]
+    PolicyChangeMatured,
+
+    #[msg("Distribution has not sunset - streams are still vesting and the policy end has not passed")]
+    DistributionNotSunset,
+
+    #[msg("Quote treasury still holds undistributed or owed quote")]
+    TreasuryNotSettled,
+
+    #[msg("Pool is not a CP-AMM pool account")]
+    InvalidPoolAccount,
+
+    #[msg("Pool collects fees in the base token - its collect fee mode must be OnlyB")]
+    PoolNotQuoteOnly,
+
+    #[msg("Pool token B mint is not the policy quote mint")]
+    PoolQuoteMintMismatch,
+
+    #[msg("Position account is not a CP-AMM Position")]
+    InvalidPositionAccount,
+
+    #[msg("Stream was already paid on this distribution day")]
+    DuplicateInvestor,
+
+    #[msg("Distribution has sunset - no new streams can be registered")]
+    DistributionSunset,
+
+    #[msg("Policy change delay can be changed but not removed once set")]
+    PolicyTimelockPermanent,
+
+    #[msg("Account is not in a layout this program can migrate")]
+    UnsupportedAccountLayout,
+
+    #[msg("Final page before every registered stream was read today")]
+    StreamsNotAllRead,
 }
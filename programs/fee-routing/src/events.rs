@@ -1,6 +1,21 @@
 use anchor_lang::prelude::*;
+use crate::state::{
+    AllocationMode, CapExcessMode, FeeShareStep, LockedMode, Policy, PolicyUpdate, RoundingMode,
+    Y0Mode,
+};
+
+// Events go out through a self-CPI by default so they land in the transaction's
+// inner instructions, which RPC nodes never truncate. The `log-events` feature
+// switches back to log-based `emit!` for older indexers. Either way the
+// `#[event_cpi]` accounts stay on every instruction so the account layout
+// doesn't depend on the build.
+#[cfg(not(feature = "log-events"))]
+pub(crate) use anchor_lang::prelude::emit_cpi as emit_event;
+#[cfg(feature = "log-events")]
+pub(crate) use anchor_lang::prelude::emit as emit_event;
 
 #[event]
+#[derive(Debug, Clone)]
 pub struct HonoraryPositionInitialized {
     pub position: Pubkey,
     pub owner_pda: Pubkey,
@@ -9,25 +24,199 @@ pub struct HonoraryPositionInitialized {
 }
 
 #[event]
+#[derive(Debug, Clone)]
 pub struct QuoteFeesClaimed {
     pub amount: u64,
     pub timestamp: i64,
     pub distribution_day: u64,
 }
 
+/// Per-investor payout with the inputs used to compute it
+///
+/// Without per-investor caps, `payout` is
+/// `floor(page_distributable * weight_numerator / weight_denominator)`, and
+/// under `RoundingMode::LargestRemainder` the page's leftover lamports add 1
+/// each to the largest remainders (ties to the lower stream pubkey). With a
+/// cap, `payout` is at most the policy's per-investor limit; under
+/// `CapExcessMode::Redistribute` the uncapped investors split what the capped
+/// ones leave by the same weights, so their payouts exceed the plain formula.
+/// `InvestorPayoutPage.payout_cap_excess` is what no investor took.
+/// When `paid` is true the investor received `payout + owed_released`; when
+/// false, `payout` plus the owed balance fell under `min_payout_lamports` and
+/// the payout was added to the investor's `owed` balance instead.
 #[event]
+#[derive(Debug, Clone)]
+pub struct InvestorPayout {
+    pub day: u64,
+    pub page_index: u16,
+    pub stream: Pubkey,
+    pub destination_ata: Pubkey,
+    pub locked_amount: u64,
+    pub weight_numerator: u64,
+    pub weight_denominator: u64,
+    pub page_distributable: u64,
+    pub payout: u64,
+    pub paid: bool,
+    pub owed_released: u64,
+    pub owed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Debug, Clone)]
 pub struct InvestorPayoutPage {
+    pub day: u64,
     pub page_index: u16,
     pub investors_paid: u16,
     pub total_distributed: u64,
     pub rounding_dust: u64,
+    pub total_locked: u64,
+    pub locked_fraction_bps: u64,
+    pub eligible_share_bps: u64,
+    /// Payouts above the per-investor caps sent to the creator
+    pub payout_cap_excess: u64,
     pub timestamp: i64,
 }
 
 #[event]
+#[derive(Debug, Clone)]
 pub struct CreatorPayoutDayClosed {
     pub day: u64,
     pub creator_amount: u64,
     pub total_distributed_to_investors: u64,
+    pub total_locked: u64,
+    pub locked_fraction_bps: u64,
+    pub eligible_share_bps: u64,
+    pub timestamp: i64,
+}
+
+/// Policy after an `update_policy` or `apply_policy_change` call
+#[event]
+#[derive(Debug, Clone)]
+pub struct PolicyUpdated {
+    pub updated_by: Pubkey,
+    pub y0: u64,
+    pub investor_fee_share_bps: u16,
+    pub daily_cap_lamports: u64,
+    pub min_payout_lamports: u64,
+    pub creator_wallet: Pubkey,
+    pub authority: Pubkey,
+    pub allocation_mode: AllocationMode,
+    pub rounding_mode: RoundingMode,
+    pub max_investor_payout_bps: u16,
+    pub max_investor_payout_lamports: u64,
+    pub cap_excess_mode: CapExcessMode,
+    pub locked_mode: LockedMode,
+    pub y0_mode: Y0Mode,
+    pub fee_share_schedule: Vec<FeeShareStep>,
+    pub policy_change_delay_seconds: i64,
+    pub end_ts: i64,
+    pub timestamp: i64,
+}
+
+impl PolicyUpdated {
+    pub fn new(updated_by: Pubkey, policy: &Policy, timestamp: i64) -> Self {
+        Self {
+            updated_by,
+            y0: policy.y0,
+            investor_fee_share_bps: policy.investor_fee_share_bps,
+            daily_cap_lamports: policy.daily_cap_lamports,
+            min_payout_lamports: policy.min_payout_lamports,
+            creator_wallet: policy.creator_wallet,
+            authority: policy.authority,
+            allocation_mode: policy.allocation_mode,
+            rounding_mode: policy.rounding_mode,
+            max_investor_payout_bps: policy.max_investor_payout_bps,
+            max_investor_payout_lamports: policy.max_investor_payout_lamports,
+            cap_excess_mode: policy.cap_excess_mode,
+            locked_mode: policy.locked_mode,
+            y0_mode: policy.y0_mode,
+            fee_share_schedule: policy.fee_share_schedule.clone(),
+            policy_change_delay_seconds: policy.policy_change_delay_seconds,
+            end_ts: policy.end_ts,
+            timestamp,
+        }
+    }
+}
+
+/// Fixed Y0 changed by `update_y0`
+#[event]
+#[derive(Debug, Clone)]
+pub struct Y0Updated {
+    pub updated_by: Pubkey,
+    pub previous_y0: u64,
+    pub y0: u64,
+    /// Y0 the next distribution day is measured against, which differs
+    /// from `y0` under `Y0Mode::StreamDeposits`
+    pub effective_y0: u64,
+    pub timestamp: i64,
+}
+
+/// Stream registered by `initialize_investor_ledger`
+///
+/// Its deposit joins `registered_deposits`, which under
+/// `Y0Mode::StreamDeposits` is the Y0 the next day is measured against.
+#[event]
+#[derive(Debug, Clone)]
+pub struct InvestorLedgerInitialized {
+    pub stream: Pubkey,
+    pub recipient: Pubkey,
+    pub deposited: u64,
+    pub locked: u64,
+    pub registered_streams: u64,
+    pub registered_deposits: u64,
+    /// Y0 the next distribution day is measured against
+    pub effective_y0: u64,
+    pub timestamp: i64,
+}
+
+/// Policy change queued by `queue_policy_change`
+#[event]
+#[derive(Debug, Clone)]
+pub struct PolicyChangeQueued {
+    pub id: u64,
+    pub update: PolicyUpdate,
+    pub queued_by: Pubkey,
+    /// Earliest time `apply_policy_change` may run
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+/// Queued policy change dropped by `cancel_policy_change`
+#[event]
+#[derive(Debug, Clone)]
+pub struct PolicyChangeCancelled {
+    pub id: u64,
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Queued policy change written to the policy by `apply_policy_change`;
+/// a `PolicyUpdated` with the resulting policy follows
+#[event]
+#[derive(Debug, Clone)]
+pub struct PolicyChangeApplied {
+    pub id: u64,
+    pub applied_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// A closed day read every registered stream and found nothing locked;
+/// distribution has sunset and later days pay the creator only
+#[event]
+#[derive(Debug, Clone)]
+pub struct StreamsFullyVested {
+    pub day: u64,
+    pub registered_streams: u64,
+    pub timestamp: i64,
+}
+
+/// Honorary position NFT handed to the creator by `transfer_position_to_creator`
+#[event]
+#[derive(Debug, Clone)]
+pub struct PositionTransferredToCreator {
+    pub position_nft_mint: Pubkey,
+    pub creator_wallet: Pubkey,
+    pub destination: Pubkey,
     pub timestamp: i64,
 }
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::*,
+    errors::FeeRoutingError,
+    events::{emit_event, PolicyChangeApplied, PolicyUpdated},
+    state::{PendingPolicyChange, Policy, Progress},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ApplyPolicyChange<'info> {
+    /// Anyone can apply a matured change
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POLICY_SEED],
+        bump = policy.bump
+    )]
+    pub policy: Account<'info, Policy>,
+
+    /// A matured change still waits for an open day to close
+    #[account(
+        seeds = [PROGRESS_SEED],
+        bump = progress.bump
+    )]
+    pub progress: Account<'info, Progress>,
+
+    /// Pending change to apply, rent refunded to whoever queued it
+    #[account(
+        mut,
+        seeds = [POLICY_CHANGE_SEED, pending_change.id.to_le_bytes().as_ref()],
+        bump = pending_change.bump,
+        has_one = queued_by,
+        close = queued_by
+    )]
+    pub pending_change: Account<'info, PendingPolicyChange>,
+
+    /// CHECK: Matched against `pending_change.queued_by`, only receives lamports
+    #[account(mut)]
+    pub queued_by: UncheckedAccount<'info>,
+}
+
+pub fn apply_policy_change_handler(ctx: Context<ApplyPolicyChange>) -> Result<()> {
+    let progress = &ctx.accounts.progress;
+    require!(!progress.day_in_progress(), FeeRoutingError::DistributionDayInProgress);
+
+    let now = Clock::get()?.unix_timestamp;
+    let pending_change = &ctx.accounts.pending_change;
+    require!(now >= pending_change.eta, FeeRoutingError::PolicyChangeNotMatured);
+
+    let policy = &mut ctx.accounts.policy;
+    policy.pending_fields &= !pending_change.fields;
+    pending_change.update.clone().apply_to(policy);
+
+    msg!("Policy change {} applied", pending_change.id);
+
+    emit_event!(PolicyChangeApplied {
+        id: pending_change.id,
+        applied_by: ctx.accounts.caller.key(),
+        timestamp: now,
+    });
+    emit_event!(PolicyUpdated::new(ctx.accounts.caller.key(), policy, now));
+
+    Ok(())
+}
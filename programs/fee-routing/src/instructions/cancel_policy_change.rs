@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::*,
+    errors::FeeRoutingError,
+    events::{emit_event, PolicyChangeCancelled},
+    state::{PendingPolicyChange, Policy},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelPolicyChange<'info> {
+    /// Current policy authority
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POLICY_SEED],
+        bump = policy.bump,
+        has_one = authority @ FeeRoutingError::Unauthorized
+    )]
+    pub policy: Account<'info, Policy>,
+
+    /// Pending change to drop, rent refunded to whoever queued it
+    #[account(
+        mut,
+        seeds = [POLICY_CHANGE_SEED, pending_change.id.to_le_bytes().as_ref()],
+        bump = pending_change.bump,
+        has_one = queued_by,
+        close = queued_by
+    )]
+    pub pending_change: Account<'info, PendingPolicyChange>,
+
+    /// CHECK: Matched against `pending_change.queued_by`, only receives lamports
+    #[account(mut)]
+    pub queued_by: UncheckedAccount<'info>,
+}
+
+pub fn cancel_policy_change_handler(ctx: Context<CancelPolicyChange>) -> Result<()> {
+    // Once matured anyone may apply the change, so cancelling it then would
+    // race the crank instead of honouring the delay
+    let now = Clock::get()?.unix_timestamp;
+    let pending_change = &ctx.accounts.pending_change;
+    require!(now < pending_change.eta, FeeRoutingError::PolicyChangeMatured);
+
+    ctx.accounts.policy.pending_fields &= !pending_change.fields;
+
+    msg!("Policy change {} cancelled", pending_change.id);
+
+    emit_event!(PolicyChangeCancelled {
+        id: pending_change.id,
+        cancelled_by: ctx.accounts.authority.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
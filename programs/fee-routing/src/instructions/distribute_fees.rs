@@ -3,11 +3,16 @@ use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use crate::{
     constants::*,
     errors::FeeRoutingError,
-    events::{QuoteFeesClaimed, InvestorPayoutPage, CreatorPayoutDayClosed},
-    meteora,
-    state::{Policy, Progress},
+    events::{
+        emit_event, CreatorPayoutDayClosed, InvestorPayout, InvestorPayoutPage, QuoteFeesClaimed,
+        StreamsFullyVested,
+    },
+    math::{DistributionMath, LedgerBalance},
+    meteora::{self, CpAmmAccount},
+    state::{DistributionHistory, DistributionRecord, InvestorLedger, LockedMode, Policy, Progress},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct DistributeFees<'info> {
     /// Permissionless caller
@@ -26,6 +31,14 @@ pub struct DistributeFees<'info> {
     )]
     pub progress: Account<'info, Progress>,
 
+    /// Ring buffer of closed days (written on the final page)
+    #[account(
+        mut,
+        seeds = [HISTORY_SEED],
+        bump = history.bump,
+    )]
+    pub history: Account<'info, DistributionHistory>,
+
     /// CHECK: Position owner PDA
     #[account(
         seeds = [VAULT_SEED, vault.key().as_ref(), INVESTOR_FEE_POS_OWNER_SEED],
@@ -47,7 +60,7 @@ pub struct DistributeFees<'info> {
     pub pool: AccountInfo<'info>,
 
     /// Position data account
-    /// CHECK: Validated by Meteora program
+    /// CHECK: Owner, discriminator and pool checked on page 0, claim validated by Meteora program
     #[account(mut)]
     pub position: AccountInfo<'info>,
 
@@ -101,7 +114,7 @@ pub struct DistributeFees<'info> {
 
     /// Event authority for Meteora program
     /// CHECK: Event authority PDA
-    pub event_authority: AccountInfo<'info>,
+    pub cp_amm_event_authority: AccountInfo<'info>,
 
     /// Meteora CP-AMM program
     /// CHECK: Must match cp_amm_program_id()
@@ -122,7 +135,7 @@ pub struct DistributeFees<'info> {
     pub token_program: Program<'info, Token>,
 
     // Remaining accounts:
-    // - Investor accounts (alternating: stream_pubkey, investor_ata)
+    // - Investor accounts (triples: stream_pubkey, investor_ata, investor_ledger)
 }
 
 pub fn distribute_fees_handler<'info>(
@@ -196,7 +209,7 @@ pub fn distribute_fees_handler<'info>(
     // === 3. CLAIM FEES FROM HONORARY POSITION ===
     // CRITICAL: Claim fees and validate BEFORE updating state to prevent state corruption if tx fails
     // Only claim on first page to get fresh fee total
-    let (_claimed_token_a, claimed_token_b) = if page_index == 0 {
+    if page_index == 0 {
         // Get balances before claiming
         let balance_a_before = {
             let data = ctx.accounts.treasury_token_a.try_borrow_data()?;
@@ -221,6 +234,16 @@ pub fn distribute_fees_handler<'info>(
             FeeRoutingError::InvalidProgram
         );
 
+        // Validate the position is a CP-AMM position in this pool
+        {
+            let position = meteora::Position::load(&ctx.accounts.position)?;
+            require_keys_eq!(
+                position.pool,
+                ctx.accounts.pool.key(),
+                FeeRoutingError::InvalidPositionAccount
+            );
+        }
+
         // Build CPI accounts for claim_position_fee
         let cpi_accounts = meteora::ClaimPositionFeeCPI {
             pool_authority: ctx.accounts.pool_authority.to_account_info(),
@@ -236,7 +259,7 @@ pub fn distribute_fees_handler<'info>(
             owner: ctx.accounts.position_owner_pda.to_account_info(),
             token_a_program: ctx.accounts.token_a_program.to_account_info(),
             token_b_program: ctx.accounts.token_b_program.to_account_info(),
-            event_authority: ctx.accounts.event_authority.to_account_info(),
+            event_authority: ctx.accounts.cp_amm_event_authority.to_account_info(),
             program: ctx.accounts.cp_amm_program.to_account_info(),
         };
 
@@ -271,12 +294,6 @@ pub fn distribute_fees_handler<'info>(
         let claimed_b = balance_b_after.checked_sub(balance_b_before)
             .ok_or(FeeRoutingError::ArithmeticOverflow)?;
 
-        emit!(QuoteFeesClaimed {
-            amount: claimed_b, // Token B is quote token
-            timestamp: now,
-            distribution_day: progress.current_day,
-        });
-
         msg!("Fees claimed - Token A: {}, Token B (quote): {}", claimed_a, claimed_b);
 
         // Bounty requirement (line 101): "If any base fees are observed or a claim returns
@@ -291,6 +308,37 @@ pub fn distribute_fees_handler<'info>(
         }
 
         // Base fee check passed - NOW safe to update state for new day
+        // Everything still in the ledger joins the claimed quote in today's pool,
+        // split by the fee share in effect as the day opens (none after sunset)
+        let share_bps = policy.day_fee_share_bps(progress.fully_vested_at, now);
+        let day_pool = progress.buckets.open_day(claimed_b, share_bps)?;
+
+        // The eligible share is fixed for the whole day against what every
+        // registered stream had locked when last read, so each page's
+        // allocation is its locked share and doesn't depend on page order
+        let locked_basis = if policy.sunset(progress.fully_vested_at, now) {
+            0
+        } else {
+            progress.registered_locked
+        };
+        let day_terms = DistributionMath::day_terms(
+            &Policy {
+                y0: policy.effective_y0(progress.registered_deposits),
+                investor_fee_share_bps: share_bps,
+                ..(**policy).clone()
+            },
+            day_pool,
+            locked_basis,
+        )?;
+        progress.day_locked_basis = day_terms.locked_basis;
+        progress.day_eligible_share_bps = day_terms.eligible_share_bps as u16;
+        progress.day_investor_allocation = day_terms.investor_allocation;
+        progress.day_claimed_quote = claimed_b;
+        progress.day_carry_over_in = day_pool - claimed_b;
+        progress.day_total_locked = 0;
+        progress.day_rounding_dust = 0;
+        progress.day_owed_released = 0;
+        progress.day_streams_read = 0;
         progress.last_distribution_ts = now;
         progress.current_day = progress.current_day.checked_add(1)
             .ok_or(FeeRoutingError::ArithmeticOverflow)?;
@@ -300,7 +348,11 @@ pub fn distribute_fees_handler<'info>(
         progress.creator_payout_sent = false;
         progress.has_base_fees = false; // No base fees detected, safe to proceed
 
-        (claimed_a, claimed_b)
+        emit_event!(QuoteFeesClaimed {
+            amount: claimed_b, // Token B is quote token
+            timestamp: now,
+            distribution_day: progress.current_day,
+        });
     } else {
         // Subsequent pages don't claim, just distribute remaining
         // Check if base fees were detected on page 0
@@ -308,24 +360,24 @@ pub fn distribute_fees_handler<'info>(
             !progress.has_base_fees,
             FeeRoutingError::BaseFeesDetected
         );
-        (0, 0)
-    };
+    }
 
-    // Add carry-over from previous cycles
+    // Every page allocates a share of the same allocation fixed on page 0
     // We only distribute quote token (token B) to investors
-    let total_available = claimed_token_b
-        .checked_add(progress.carry_over_lamports)
-        .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+    let day_terms = progress.day_terms()?;
+
+    // After sunset the whole pool goes to the creator and nothing is locked,
+    // so streams aren't read at all; investor triples only flush owed balances
+    let sunset = policy.sunset(progress.fully_vested_at, progress.last_distribution_ts);
 
     // === 4. PARSE INVESTOR ACCOUNTS FROM REMAINING ===
     let remaining_accounts = &ctx.remaining_accounts;
+    let investor_count = remaining_accounts.len() / 3;
     require!(
-        remaining_accounts.len() % 2 == 0,
+        investor_count * 3 == remaining_accounts.len(),
         FeeRoutingError::InvalidPageIndex
     );
 
-    let investor_count = remaining_accounts.len() / 2;
-
     // Validate investor count doesn't exceed maximum
     require!(
         investor_count <= MAX_INVESTORS_PER_PAGE,
@@ -333,124 +385,193 @@ pub fn distribute_fees_handler<'info>(
     );
 
     let mut locked_amounts: Vec<u64> = Vec::with_capacity(investor_count);
-    let mut total_locked: u64 = 0;
+    let mut stream_keys: Vec<Pubkey> = Vec::with_capacity(investor_count);
+    let mut balances: Vec<LedgerBalance> = Vec::with_capacity(investor_count);
 
     // Read locked amounts from Streamflow accounts
     for i in 0..investor_count {
-        let stream_account = &remaining_accounts[i * 2];
-        let investor_ata = &remaining_accounts[i * 2 + 1];
-
-        // Validate stream account owner is Streamflow program
-        require!(
-            stream_account.owner == &streamflow_sdk::id(),
-            FeeRoutingError::InvalidStreamflowAccount
-        );
+        let stream_account = &remaining_accounts[i * 3];
+        let investor_ata = &remaining_accounts[i * 3 + 1];
+        let ledger_info = &remaining_accounts[i * 3 + 2];
+
+        // After sunset only owed balances move, to the recipient the ledger
+        // recorded when the stream was last read
+        let contract = if sunset {
+            None
+        } else {
+            // Validate stream account owner is Streamflow program
+            require!(
+                stream_account.owner == &streamflow_sdk::id(),
+                FeeRoutingError::InvalidStreamflowAccount
+            );
 
-        // Deserialize Streamflow Contract account
-        // Note: Streamflow Contract accounts do NOT have an 8-byte discriminator
-        // They use borsh serialization directly. The deserialization will fail
-        // if the account is not a valid Contract (acts as validation).
-        let contract_data = stream_account.try_borrow_data()?;
+            // Deserialize Streamflow Contract account
+            // Note: Streamflow Contract accounts do NOT have an 8-byte discriminator
+            // They use borsh serialization directly. The deserialization will fail
+            // if the account is not a valid Contract (acts as validation).
+            let contract_data = stream_account.try_borrow_data()?;
 
-        // Additional validation: Check minimum account size
-        // Streamflow Contract accounts are typically 500+ bytes
-        require!(
-            contract_data.len() >= 400,
-            FeeRoutingError::InvalidStreamflowAccount
-        );
-
-        let contract = streamflow_sdk::state::Contract::try_from_slice(&contract_data)?;
+            // Additional validation: Check minimum account size
+            // Streamflow Contract accounts are typically 500+ bytes
+            require!(
+                contract_data.len() >= 400,
+                FeeRoutingError::InvalidStreamflowAccount
+            );
 
-        // Note: We validate the quote mint through the investor's ATA below
-        // This is sufficient since the investor ATA must match the policy quote mint
-        // and the Streamflow contract must distribute to this ATA
+            Some(streamflow_sdk::state::Contract::try_from_slice(&contract_data)?)
+        };
 
-        // Validate investor ATA
-        {
+        // Validate investor ATA: a quote account, owned by the stream's
+        // recipient as its ledger records it (checked below)
+        let investor_ata_owner = {
             let investor_ata_data = investor_ata.try_borrow_data()?;
             let investor_token_account = TokenAccount::try_deserialize(&mut &investor_ata_data[..])?;
             require!(
                 investor_token_account.mint == policy.quote_mint,
                 FeeRoutingError::InvalidQuoteMint
             );
-        }
+            investor_token_account.owner
+        };
 
-        // Calculate locked amount:
-        // locked = net_amount_deposited - (vested_available + cliff_available)
-        let now_u64 = now as u64; // Streamflow methods expect u64 timestamp
-        let vested = contract.vested_available(now_u64);
-        let cliff = contract.cliff_available(now_u64);
+        // Streamflow methods expect u64 timestamp
+        let locked = match (&contract, policy.locked_mode) {
+            (None, _) => 0,
+            (Some(contract), LockedMode::Instant) => {
+                DistributionMath::locked_amount(contract, now as u64)?
+            }
+            // Every page of the day averages over the same window, so the
+            // weights don't depend on when each page lands
+            (Some(contract), LockedMode::WindowAverage) => DistributionMath::window_average_locked(
+                |t| DistributionMath::locked_amount(contract, t),
+                DistributionMath::window_start(progress.last_distribution_ts) as u64,
+            )?,
+        };
 
-        let unlocked = vested
-            .checked_add(cliff)
-            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        // Only initialize_investor_ledger creates program-owned ledgers, and
+        // it records the stream the PDA was derived from
+        {
+            require!(
+                ledger_info.owner == &crate::ID && ledger_info.is_writable,
+                FeeRoutingError::InvalidInvestorLedger
+            );
+            let mut ledger_data = ledger_info.try_borrow_mut_data()?;
+            let mut ledger = InvestorLedger::try_deserialize(&mut &ledger_data[..])?;
+            require!(
+                ledger.stream == stream_account.key(),
+                FeeRoutingError::InvalidInvestorLedger
+            );
 
-        let locked = contract.ix.net_amount_deposited
-            .checked_sub(unlocked)
-            .unwrap_or(0); // If fully vested, locked = 0
+            // A stream is paid at most once a day. Marking the ledger before
+            // any payout also catches a triple repeated on this same page,
+            // which would otherwise release its owed balance twice
+            require!(
+                ledger.last_read_day != progress.current_day,
+                FeeRoutingError::DuplicateInvestor
+            );
+            ledger.last_read_day = progress.current_day;
+            balances.push(LedgerBalance { owed: ledger.owed });
+
+            if let Some(contract) = &contract {
+                // A stream handed to a new recipient pays the new one, owed
+                // balance included
+                ledger.recipient = contract.recipient;
+                // Top-ups since the last read move the registered deposit total
+                let deposited = contract.ix.net_amount_deposited;
+                if ledger.deposited != deposited {
+                    progress.register_deposit(ledger.deposited, deposited)?;
+                    ledger.deposited = deposited;
+                }
+                // Tomorrow's basis counts what the stream has locked now
+                if ledger.locked != locked {
+                    progress.register_locked(ledger.locked, locked)?;
+                    ledger.locked = locked;
+                }
+                progress.day_streams_read = progress.day_streams_read
+                    .checked_add(1)
+                    .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+            }
+            // Without this a caller could pair someone else's stream and
+            // ledger with its own token account and take the payout and the
+            // whole owed balance
+            require!(
+                investor_ata_owner == ledger.recipient,
+                FeeRoutingError::InvalidAccountOwnership
+            );
+            ledger.try_serialize(&mut &mut ledger_data[..])?;
+        }
 
         locked_amounts.push(locked);
-        total_locked = total_locked
-            .checked_add(locked)
-            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        stream_keys.push(stream_account.key());
     }
 
-    // === 5. CALCULATE PRO-RATA DISTRIBUTION ===
-    use crate::math::DistributionMath;
-
-    // Calculate locked fraction: f_locked(t) = locked_total(t) / Y0
-    let locked_fraction_bps = DistributionMath::calculate_locked_fraction_bps(
-        total_locked,
-        policy.y0,
-    )?;
-
-    // Calculate eligible investor share (capped)
-    let eligible_share_bps = DistributionMath::calculate_eligible_investor_share_bps(
-        locked_fraction_bps,
-        policy.investor_fee_share_bps,
-    );
-
-    // Calculate total amount for investors
-    let investor_allocation = DistributionMath::calculate_investor_allocation(
-        total_available,
-        eligible_share_bps,
-    )?;
+    // Every page of the day applies the fee share in effect when it opened.
+    // Under Y0Mode::StreamDeposits the page is measured against the deposits
+    // registered so far, including any top-up of its own streams just read.
+    // After sunset no payout will ever reach the minimum again, so owed
+    // balances are paid out whatever their size and the per-investor caps,
+    // measured against a day allocation that is now 0, no longer apply.
+    let policy = &Policy {
+        y0: policy.effective_y0(progress.registered_deposits),
+        investor_fee_share_bps: policy
+            .day_fee_share_bps(progress.fully_vested_at, progress.last_distribution_ts),
+        min_payout_lamports: if sunset { 0 } else { policy.min_payout_lamports },
+        max_investor_payout_bps: if sunset { 0 } else { policy.max_investor_payout_bps },
+        max_investor_payout_lamports: if sunset { 0 } else { policy.max_investor_payout_lamports },
+        ..(**policy).clone()
+    };
 
-    // Apply daily cap if configured
-    let (distributable, new_carry_over) = DistributionMath::apply_daily_cap(
-        investor_allocation,
-        policy.daily_cap_lamports,
-        progress.daily_distributed_to_investors,
+    // === 5. CALCULATE PRO-RATA DISTRIBUTION ===
+    // The page's investors get locked_page(t) / locked_basis of the day's
+    // investor allocation, limited by what is left of the investor budget,
+    // split pro-rata.
+    // Floor division leaves rounding dust, which goes to the dust bucket
+    // unless the policy's rounding mode hands it out by largest remainder.
+    // Released owed balances were allocated on earlier days, so only this
+    // day's allocations count against the daily cap.
+    let page = DistributionMath::distribute_page(
+        policy,
+        &day_terms,
+        progress.buckets.investor_budget,
+        &locked_amounts,
+        &stream_keys,
+        &balances,
+        progress.daily_distributed_to_investors
+            .checked_sub(progress.day_owed_released)
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?,
     )?;
+    let total_locked = page.total_locked;
 
     // === 6. DISTRIBUTE TO INVESTORS ===
-    let mut page_total_distributed = 0u64;
-    let mut investors_paid = 0u16;
-    let mut accumulated_dust = 0u64;
-    let mut rounding_dust_this_page = 0u64;
-
-    // Track total theoretical allocation to compare against actual distribution
-    let mut total_theoretical_payout = 0u64;
-
-    for i in 0..investor_count {
-        let investor_locked = locked_amounts[i];
-
-        // Calculate this investor's payout (floor division creates rounding dust)
-        let payout = DistributionMath::calculate_investor_payout(
-            investor_locked,
-            total_locked,
-            distributable,
-        )?;
-
-        total_theoretical_payout = total_theoretical_payout
-            .checked_add(payout)
-            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+    for (i, share) in page.shares.iter().enumerate() {
+        // Access investor ATA directly from remaining_accounts
+        let investor_ata_info = &remaining_accounts[i * 3 + 1];
+
+        emit_event!(InvestorPayout {
+            day: progress.current_day,
+            page_index,
+            stream: remaining_accounts[i * 3].key(),
+            destination_ata: investor_ata_info.key(),
+            locked_amount: share.locked,
+            weight_numerator: share.locked,
+            weight_denominator: total_locked,
+            page_distributable: page.distributable,
+            payout: share.payout,
+            paid: share.paid,
+            owed_released: share.owed_released,
+            owed: share.owed,
+            timestamp: now,
+        });
 
-        // Check minimum threshold
-        if DistributionMath::meets_minimum_threshold(payout, policy.min_payout_lamports) {
-            // Access investor ATA directly from remaining_accounts
-            let investor_ata_info = &remaining_accounts[i * 2 + 1];
+        // Below-threshold payouts stay in the treasury, owed to the investor
+        if share.owed != balances[i].owed {
+            let ledger_info = &remaining_accounts[i * 3 + 2];
+            let mut ledger_data = ledger_info.try_borrow_mut_data()?;
+            let mut ledger = InvestorLedger::try_deserialize(&mut &ledger_data[..])?;
+            ledger.owed = share.owed;
+            ledger.try_serialize(&mut &mut ledger_data[..])?;
+        }
 
+        if share.paid {
             // Execute token transfer via CPI
             let cpi_accounts = Transfer {
                 from: ctx.accounts.treasury_token_b.to_account_info(),
@@ -467,80 +588,68 @@ pub fn distribute_fees_handler<'info>(
             let cpi_program = ctx.accounts.token_program.to_account_info();
             let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
 
-            token::transfer(cpi_ctx, payout)?;
-
-            page_total_distributed = page_total_distributed
-                .checked_add(payout)
-                .ok_or(FeeRoutingError::ArithmeticOverflow)?;
-            investors_paid = investors_paid.checked_add(1)
-                .ok_or(FeeRoutingError::ArithmeticOverflow)?;
-        } else {
-            // Below threshold - accumulate as dust
-            accumulated_dust = accumulated_dust
-                .checked_add(payout)
-                .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+            token::transfer(cpi_ctx, share.transfer_amount())?;
         }
     }
 
-    // Calculate rounding dust: difference between distributable and sum of floor'd payouts
-    // This tracks the "lost" lamports due to integer division rounding
-    //
-    // ROUNDING DUST STRATEGY:
-    // Floor division (a/b) in pro-rata distribution creates remainder lamports that cannot
-    // be distributed fairly. These lamports are NOT lost - they remain in the treasury.
-    //
-    // Example: 1000 lamports distributed to 3 investors (333, 333, 333) = 999 distributed
-    // Rounding dust: 1000 - 999 = 1 lamport remains in treasury
-    //
-    // IMPORTANT: Rounding dust is NEVER distributed to any party. It accumulates in:
-    // - progress.total_rounding_dust (tracked for transparency/auditing)
-    // - treasury_token_b account (physically held, can be rescued by admin later)
-    //
-    // This design ensures:
-    // 1. No party receives unfair advantage from rounding
-    // 2. Dust is transparent and auditable
-    // 3. Dust can be recovered via future governance/rescue mechanism
-    // 4. Math is deterministic and verifiable
-    if distributable > total_theoretical_payout {
-        rounding_dust_this_page = distributable
-            .checked_sub(total_theoretical_payout)
-            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
-    }
+    let page_total_distributed = page.page_total_distributed;
+    let rounding_dust_this_page = page.rounding_dust;
 
     // === 7. UPDATE PROGRESS STATE ===
     progress.daily_distributed_to_investors = progress.daily_distributed_to_investors
         .checked_add(page_total_distributed)
         .ok_or(FeeRoutingError::ArithmeticOverflow)?;
 
-    progress.carry_over_lamports = accumulated_dust
-        .checked_add(new_carry_over)
+    progress.day_owed_released = progress.day_owed_released
+        .checked_add(page.owed_released)
         .ok_or(FeeRoutingError::ArithmeticOverflow)?;
 
-    // Track lifetime rounding dust for transparency (audit trail only, never redistributed)
+    progress.buckets.apply_page(&page)?;
+
+    // Track lifetime rounding dust for transparency (the dust bucket rolls into the next day)
     progress.total_rounding_dust = progress.total_rounding_dust
         .checked_add(rounding_dust_this_page)
         .ok_or(FeeRoutingError::ArithmeticOverflow)?;
 
+    // Day-level aggregates feed the DistributionHistory record on the final page
+    progress.day_total_locked = progress.day_total_locked
+        .checked_add(total_locked)
+        .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+    progress.day_rounding_dust = progress.day_rounding_dust
+        .checked_add(rounding_dust_this_page)
+        .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+
     progress.current_page = progress.current_page.checked_add(1)
         .ok_or(FeeRoutingError::ArithmeticOverflow)?;
     progress.pages_processed_today = progress.pages_processed_today.checked_add(1)
         .ok_or(FeeRoutingError::ArithmeticOverflow)?;
 
-    emit!(InvestorPayoutPage {
+    emit_event!(InvestorPayoutPage {
+        day: progress.current_day,
         page_index,
-        investors_paid,
+        investors_paid: page.investors_paid,
         total_distributed: page_total_distributed,
         rounding_dust: rounding_dust_this_page,
+        total_locked,
+        locked_fraction_bps: page.locked_fraction_bps,
+        eligible_share_bps: page.eligible_share_bps,
+        payout_cap_excess: page.payout_cap_excess,
         timestamp: now,
     });
 
     // === 8. CREATOR PAYOUT (FINAL PAGE ONLY) ===
     // Use explicit is_final_page parameter from caller to prevent multiple payouts
     if is_final_page && !progress.creator_payout_sent {
-        // Calculate remainder: total claimed minus investor allocation
-        let remainder = total_available
-            .checked_sub(investor_allocation)
-            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        // The investor budget left now goes to the creator, so a final page
+        // that skipped registered streams would hand their allocation over.
+        // After sunset there is no allocation and owed balances stay owed.
+        require!(
+            sunset || progress.day_streams_read == progress.registered_streams,
+            FeeRoutingError::StreamsNotAllRead
+        );
+
+        // Creator budget plus whatever investor budget no page allocated
+        let remainder = progress.buckets.close_day()?;
 
         if remainder > 0 {
             // Transfer remainder to creator (Token B / quote token)
@@ -563,17 +672,73 @@ pub fn distribute_fees_handler<'info>(
             );
 
             token::transfer(cpi_ctx, remainder)?;
+        }
+
+        // Report the share applied on every page next to the locked total
+        // actually read across them
+        let day_locked_fraction_bps = DistributionMath::calculate_locked_fraction_bps(
+            progress.day_total_locked.min(policy.y0),
+            policy.y0,
+        )?;
+        let day_eligible_share_bps = progress.day_eligible_share_bps as u64;
+
+        emit_event!(CreatorPayoutDayClosed {
+            day: progress.current_day,
+            creator_amount: remainder,
+            total_distributed_to_investors: progress.daily_distributed_to_investors,
+            total_locked: progress.day_total_locked,
+            locked_fraction_bps: day_locked_fraction_bps,
+            eligible_share_bps: day_eligible_share_bps,
+            timestamp: now,
+        });
+
+        // Record the closed day
+        ctx.accounts.history.push(DistributionRecord {
+            day: progress.current_day,
+            claimed_quote: progress.day_claimed_quote,
+            carry_over_used: progress.day_carry_over_in,
+            total_locked: progress.day_total_locked,
+            eligible_share_bps: day_eligible_share_bps as u16,
+            investor_total: progress.daily_distributed_to_investors,
+            creator_total: remainder,
+            rounding_dust: progress.day_rounding_dust,
+            page_count: progress.pages_processed_today,
+            started_at: progress.last_distribution_ts,
+            closed_at: now,
+        });
+
+        progress.creator_payout_sent = true;
+
+        // Every registered stream was read today and none had anything
+        // locked, so the investor share is 0 for good and later days can
+        // skip the streams
+        if !sunset
+            && progress.registered_streams > 0
+            && progress.day_streams_read == progress.registered_streams
+            && progress.day_total_locked == 0
+        {
+            progress.fully_vested_at = now;
+            msg!("Every registered stream is fully vested; distribution has sunset");
 
-            emit!(CreatorPayoutDayClosed {
+            emit_event!(StreamsFullyVested {
                 day: progress.current_day,
-                creator_amount: remainder,
-                total_distributed_to_investors: progress.daily_distributed_to_investors,
+                registered_streams: progress.registered_streams,
                 timestamp: now,
             });
         }
-
-        progress.creator_payout_sent = true;
     }
 
+    // === 9. RECONCILE LEDGER WITH TREASURY ===
+    // Anyone can send quote to the treasury, so it may hold more than the
+    // ledger, but never less
+    let treasury_balance = {
+        let data = ctx.accounts.treasury_token_b.try_borrow_data()?;
+        TokenAccount::try_deserialize(&mut &data[..])?.amount
+    };
+    require!(
+        treasury_balance >= progress.buckets.total()?,
+        FeeRoutingError::TreasuryBelowLedger
+    );
+
     Ok(())
 }
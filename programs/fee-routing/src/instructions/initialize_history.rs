@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::*,
+    state::DistributionHistory,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeHistory<'info> {
+    /// Authority that can initialize the history (typically the creator)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Distribution history PDA to be initialized
+    #[account(
+        init,
+        payer = authority,
+        space = DistributionHistory::LEN,
+        seeds = [HISTORY_SEED],
+        bump
+    )]
+    pub history: Account<'info, DistributionHistory>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_history_handler(ctx: Context<InitializeHistory>) -> Result<()> {
+    let history = &mut ctx.accounts.history;
+
+    history.next_index = 0;
+    history.total_days_recorded = 0;
+    history.records = Vec::with_capacity(DISTRIBUTION_HISTORY_DAYS);
+    history.bump = ctx.bumps.history;
+
+    msg!("Distribution history initialized successfully");
+    msg!("Capacity: {} days", DISTRIBUTION_HISTORY_DAYS);
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use crate::{
+    constants::*,
+    errors::FeeRoutingError,
+    events::{emit_event, InvestorLedgerInitialized},
+    math::DistributionMath,
+    state::{InvestorLedger, LockedMode, Policy, Progress},
+};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeInvestorLedger<'info> {
+    /// Pays for the investor's ledger
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Policy authority, the only one who may register a stream: every
+    /// registration moves `registered_deposits` and `registered_locked`
+    pub authority: Signer<'info>,
+
+    /// Streamflow contract of the investor
+    /// CHECK: Owner checked here, data deserialized as a Streamflow contract
+    #[account(
+        constraint = stream.owner == &streamflow_sdk::id() @ FeeRoutingError::InvalidStreamflowAccount
+    )]
+    pub stream: AccountInfo<'info>,
+
+    /// Locked mode the stream's registered locked amount is read with
+    #[account(
+        seeds = [POLICY_SEED],
+        bump = policy.bump,
+        has_one = authority @ FeeRoutingError::Unauthorized
+    )]
+    pub policy: Account<'info, Policy>,
+
+    /// Investor ledger PDA to be initialized
+    #[account(
+        init,
+        payer = payer,
+        space = InvestorLedger::LEN,
+        seeds = [INVESTOR_LEDGER_SEED, stream.key().as_ref()],
+        bump
+    )]
+    pub investor_ledger: Account<'info, InvestorLedger>,
+
+    /// Adds the stream's deposit to `registered_deposits`, its locked amount
+    /// to `registered_locked` and counts it in `registered_streams`
+    #[account(
+        mut,
+        seeds = [PROGRESS_SEED],
+        bump = progress.bump
+    )]
+    pub progress: Account<'info, Progress>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_investor_ledger_handler(ctx: Context<InitializeInvestorLedger>) -> Result<()> {
+    // Sunset is final, so a late stream can neither reopen distribution nor
+    // hold off the position handover
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        !ctx.accounts.policy.sunset(ctx.accounts.progress.fully_vested_at, now),
+        FeeRoutingError::DistributionSunset
+    );
+
+    let contract = {
+        let data = ctx.accounts.stream.try_borrow_data()?;
+        streamflow_sdk::state::Contract::try_from_slice(&data)
+            .map_err(|_| FeeRoutingError::InvalidStreamflowAccount)?
+    };
+    let deposited = contract.ix.net_amount_deposited;
+    // Read the way distribute_fees will, so the next day's basis counts it
+    let locked = match ctx.accounts.policy.locked_mode {
+        LockedMode::Instant => DistributionMath::locked_amount(&contract, now as u64)?,
+        LockedMode::WindowAverage => DistributionMath::window_average_locked(
+            |t| DistributionMath::locked_amount(&contract, t),
+            DistributionMath::window_start(now) as u64,
+        )?,
+    };
+
+    let ledger = &mut ctx.accounts.investor_ledger;
+    ledger.stream = ctx.accounts.stream.key();
+    ledger.recipient = contract.recipient;
+    ledger.owed = 0;
+    ledger.deposited = deposited;
+    ledger.locked = locked;
+    ledger.last_read_day = 0;
+    ledger.bump = ctx.bumps.investor_ledger;
+
+    let progress = &mut ctx.accounts.progress;
+    progress.register_deposit(0, deposited)?;
+    progress.register_locked(0, locked)?;
+    progress.registered_streams = progress.registered_streams
+        .checked_add(1)
+        .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+
+    msg!("Investor ledger initialized for stream {} ({} deposited)", ledger.stream, deposited);
+
+    emit_event!(InvestorLedgerInitialized {
+        stream: ledger.stream,
+        recipient: ledger.recipient,
+        deposited,
+        locked,
+        registered_streams: progress.registered_streams,
+        registered_deposits: progress.registered_deposits,
+        effective_y0: ctx.accounts.policy.effective_y0(progress.registered_deposits),
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -1,9 +1,10 @@
 use anchor_lang::prelude::*;
 use crate::{
     constants::*,
-    state::Policy,
+    state::{AllocationMode, CapExcessMode, LockedMode, Policy, RoundingMode, Y0Mode},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializePolicy<'info> {
     /// Authority that can initialize the policy (typically the creator)
@@ -42,6 +43,19 @@ pub fn initialize_policy_handler(
     policy.quote_mint = quote_mint;
     policy.creator_wallet = creator_wallet;
     policy.authority = ctx.accounts.authority.key();
+    policy.allocation_mode = AllocationMode::Legacy;
+    policy.rounding_mode = RoundingMode::Floor;
+    policy.max_investor_payout_bps = 0;
+    policy.max_investor_payout_lamports = 0;
+    policy.cap_excess_mode = CapExcessMode::Redistribute;
+    policy.locked_mode = LockedMode::Instant;
+    policy.y0_mode = Y0Mode::Fixed;
+    policy.fee_share_schedule = Vec::new();
+    // No timelock until the authority queues or sets a delay
+    policy.policy_change_delay_seconds = 0;
+    policy.pending_fields = 0;
+    policy.next_policy_change_id = 0;
+    policy.end_ts = 0;
     policy.bump = ctx.bumps.policy;
 
     msg!("Policy initialized successfully");
@@ -3,16 +3,25 @@ use anchor_spl::token::Token;
 use crate::{
     constants::*,
     errors::FeeRoutingError,
-    events::HonoraryPositionInitialized,
-    meteora,
+    events::{emit_event, HonoraryPositionInitialized},
+    meteora::{self, CpAmmAccount},
+    state::Policy,
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializePosition<'info> {
     /// Authority that can initialize the position (creator)
     #[account(mut)]
     pub authority: Signer<'info>,
 
+    /// Supplies the quote mint the pool must collect fees in
+    #[account(
+        seeds = [POLICY_SEED],
+        bump = policy.bump
+    )]
+    pub policy: Account<'info, Policy>,
+
     /// PDA that will own the honorary position (via NFT)
     /// CHECK: PDA that will be the owner field in create_position
     #[account(
@@ -43,7 +52,7 @@ pub struct InitializePosition<'info> {
     pub position: AccountInfo<'info>,
 
     /// The CP-AMM pool
-    /// CHECK: Validated by Meteora program
+    /// CHECK: Owner, discriminator and fee mode checked in the handler
     #[account(mut)]
     pub pool: AccountInfo<'info>,
 
@@ -51,8 +60,9 @@ pub struct InitializePosition<'info> {
     /// CHECK: Must match POOL_AUTHORITY
     pub pool_authority: AccountInfo<'info>,
 
-    /// Quote mint (token B typically)
-    /// CHECK: Validated against pool configuration
+    /// Quote mint (token B)
+    /// CHECK: Must be the policy quote mint
+    #[account(address = policy.quote_mint @ FeeRoutingError::InvalidQuoteMint)]
     pub quote_mint: AccountInfo<'info>,
 
     /// Rent sysvar
@@ -65,9 +75,9 @@ pub struct InitializePosition<'info> {
     pub system_program: Program<'info, System>,
 
     /// Event authority for Meteora program
-    /// Seeds: [b"__event_authority"]
+    /// Seeds: [b"__event_authority"] (under the CP-AMM program)
     /// CHECK: Event authority PDA
-    pub event_authority: AccountInfo<'info>,
+    pub cp_amm_event_authority: AccountInfo<'info>,
 
     /// Meteora CP-AMM program
     /// CHECK: Must match CP_AMM_PROGRAM_ID
@@ -81,18 +91,24 @@ pub fn initialize_position_handler(ctx: Context<InitializePosition>) -> Result<(
         FeeRoutingError::InvalidPoolAuthority
     );
 
-    // 2. Validate quote mint is provided
-    require!(
-        ctx.accounts.quote_mint.key() != Pubkey::default(),
-        FeeRoutingError::InvalidQuoteMint
-    );
-
-    // 3. Validate CP-AMM program ID
+    // 2. Validate CP-AMM program ID
     require!(
         ctx.accounts.cp_amm_program.key() == meteora::cp_amm_program_id(),
         FeeRoutingError::InvalidProgram
     );
 
+    // 3. The pool must charge fees in the policy's quote mint only, so the
+    // position can never accrue base fees for distribute_fees to reject
+    {
+        let pool = meteora::Pool::load(&ctx.accounts.pool)?;
+        require!(pool.is_quote_only(), FeeRoutingError::PoolNotQuoteOnly);
+        require_keys_eq!(
+            pool.token_b_mint,
+            ctx.accounts.policy.quote_mint,
+            FeeRoutingError::PoolQuoteMintMismatch
+        );
+    }
+
     // 4. Create position via CPI to Meteora CP-AMM
     // The position will be owned by our program PDA via NFT ownership
     let cpi_accounts = meteora::CreatePositionCPI {
@@ -106,7 +122,7 @@ pub fn initialize_position_handler(ctx: Context<InitializePosition>) -> Result<(
         rent: ctx.accounts.rent.to_account_info(),
         token_program: ctx.accounts.token_program.to_account_info(),
         system_program: ctx.accounts.system_program.to_account_info(),
-        event_authority: ctx.accounts.event_authority.to_account_info(),
+        event_authority: ctx.accounts.cp_amm_event_authority.to_account_info(),
         program: ctx.accounts.cp_amm_program.to_account_info(),
     };
 
@@ -114,19 +130,8 @@ pub fn initialize_position_handler(ctx: Context<InitializePosition>) -> Result<(
     // The NFT mint signs (passed from client), and owner is just a reference
     meteora::create_position_cpi(&cpi_accounts, &[])?;
 
-    // 5. NOTE: Quote-only enforcement strategy
-    // DAMM v2 / CP-AMM positions accrue fees in BOTH token A and token B.
-    // To achieve "quote-only" distribution, we have several strategies:
-    //
-    // Strategy A (Recommended): Accept both tokens, swap base→quote in distribute_fees
-    // Strategy B: Validate pool has minimal base token liquidity (prone to change)
-    // Strategy C: Only distribute quote token, hold base token indefinitely
-    //
-    // For this implementation, we accept both tokens and will handle swapping
-    // in the distribute_fees instruction.
-
-    // 6. Emit event
-    emit!(HonoraryPositionInitialized {
+    // 5. Emit event
+    emit_event!(HonoraryPositionInitialized {
         position: ctx.accounts.position.key(),
         owner_pda: ctx.accounts.position_owner_pda.key(),
         quote_mint: ctx.accounts.quote_mint.key(),
@@ -1,9 +1,10 @@
 use anchor_lang::prelude::*;
 use crate::{
     constants::*,
-    state::Progress,
+    state::{Buckets, Progress},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeProgress<'info> {
     /// Authority that can initialize the progress (typically the creator)
@@ -31,13 +32,26 @@ pub fn initialize_progress_handler(ctx: Context<InitializeProgress>) -> Result<(
     progress.last_distribution_ts = 0;
     progress.current_day = 0;
     progress.daily_distributed_to_investors = 0;
-    progress.carry_over_lamports = 0;
     progress.current_page = 0;
     progress.pages_processed_today = 0;
     progress.total_investors = 0;
     progress.creator_payout_sent = false;
     progress.has_base_fees = false;
     progress.total_rounding_dust = 0;
+    progress.day_claimed_quote = 0;
+    progress.day_carry_over_in = 0;
+    progress.day_total_locked = 0;
+    progress.day_rounding_dust = 0;
+    progress.buckets = Buckets::default();
+    progress.day_owed_released = 0;
+    progress.registered_deposits = 0;
+    progress.registered_streams = 0;
+    progress.day_streams_read = 0;
+    progress.fully_vested_at = 0;
+    progress.registered_locked = 0;
+    progress.day_locked_basis = 0;
+    progress.day_eligible_share_bps = 0;
+    progress.day_investor_allocation = 0;
     progress.bump = ctx.bumps.progress;
 
     msg!("Progress initialized successfully");
@@ -0,0 +1,114 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+    Discriminator,
+};
+use crate::{
+    constants::*,
+    errors::FeeRoutingError,
+    state::{Policy, PolicyV1, Progress, ProgressV1},
+};
+
+#[derive(Accounts)]
+pub struct MigrateAccounts<'info> {
+    /// Policy authority; pays the rent the larger accounts need
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Policy PDA in any supported layout, read by hand since an older
+    /// layout doesn't deserialize as `Policy`
+    #[account(
+        mut,
+        seeds = [POLICY_SEED],
+        bump,
+        owner = crate::ID
+    )]
+    pub policy: UncheckedAccount<'info>,
+
+    /// CHECK: Progress PDA in any supported layout
+    #[account(
+        mut,
+        seeds = [PROGRESS_SEED],
+        bump,
+        owner = crate::ID
+    )]
+    pub progress: UncheckedAccount<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_accounts_handler(ctx: Context<MigrateAccounts>) -> Result<()> {
+    let (authority, policy) = read_policy(&ctx.accounts.policy)?;
+    require_keys_eq!(authority, ctx.accounts.authority.key(), FeeRoutingError::Unauthorized);
+    let progress = read_progress(&ctx.accounts.progress)?;
+
+    if let Some(policy) = policy {
+        write_account(ctx.accounts, ctx.accounts.policy.to_account_info(), &policy, Policy::LEN)?;
+        msg!("Policy migrated to {} bytes", Policy::LEN);
+    }
+    if let Some(progress) = progress {
+        write_account(ctx.accounts, ctx.accounts.progress.to_account_info(), &progress, Progress::LEN)?;
+        msg!("Progress migrated to {} bytes", Progress::LEN);
+    }
+
+    Ok(())
+}
+
+/// The policy authority, and the policy in the current layout unless it is
+/// already there
+fn read_policy(info: &AccountInfo) -> Result<(Pubkey, Option<Policy>)> {
+    let data = info.try_borrow_data()?;
+    require!(data.starts_with(Policy::DISCRIMINATOR), FeeRoutingError::UnsupportedAccountLayout);
+    match data.len() {
+        Policy::LEN => Ok((Policy::try_deserialize(&mut &data[..])?.authority, None)),
+        PolicyV1::LEN => {
+            let policy = PolicyV1::try_from_slice(&data[8..])?.migrate();
+            Ok((policy.authority, Some(policy)))
+        }
+        _ => err!(FeeRoutingError::UnsupportedAccountLayout),
+    }
+}
+
+/// The progress in the current layout, or None if it is already there
+fn read_progress(info: &AccountInfo) -> Result<Option<Progress>> {
+    let data = info.try_borrow_data()?;
+    require!(data.starts_with(Progress::DISCRIMINATOR), FeeRoutingError::UnsupportedAccountLayout);
+    match data.len() {
+        Progress::LEN => Ok(None),
+        ProgressV1::LEN => {
+            let progress = ProgressV1::try_from_slice(&data[8..])?;
+            // Half a day's pages ran under the old layout's bookkeeping
+            require!(!progress.day_in_progress(), FeeRoutingError::DistributionDayInProgress);
+            Ok(Some(progress.migrate()))
+        }
+        _ => err!(FeeRoutingError::UnsupportedAccountLayout),
+    }
+}
+
+/// Grow `info` to `len`, topping its rent up from the authority, and write
+/// `account` over it
+fn write_account<'info, T: AccountSerialize>(
+    accounts: &MigrateAccounts<'info>,
+    info: AccountInfo<'info>,
+    account: &T,
+    len: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(len);
+    let top_up = rent.saturating_sub(info.lamports());
+    if top_up > 0 {
+        let cpi_ctx = CpiContext::new(
+            accounts.system_program.to_account_info(),
+            Transfer {
+                from: accounts.authority.to_account_info(),
+                to: info.clone(),
+            },
+        );
+        system_program::transfer(cpi_ctx, top_up)?;
+    }
+
+    info.resize(len)?;
+    let mut data = info.try_borrow_mut_data()?;
+    account.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
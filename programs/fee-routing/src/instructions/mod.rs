@@ -1,9 +1,27 @@
 pub mod initialize_policy;
 pub mod initialize_progress;
+pub mod initialize_history;
 pub mod initialize_position;
+pub mod initialize_investor_ledger;
 pub mod distribute_fees;
+pub mod update_policy;
+pub mod update_y0;
+pub mod queue_policy_change;
+pub mod cancel_policy_change;
+pub mod apply_policy_change;
+pub mod transfer_position_to_creator;
+pub mod migrate_accounts;
 
 pub use initialize_policy::*;
 pub use initialize_progress::*;
+pub use initialize_history::*;
 pub use initialize_position::*;
+pub use initialize_investor_ledger::*;
 pub use distribute_fees::*;
+pub use update_policy::*;
+pub use update_y0::*;
+pub use queue_policy_change::*;
+pub use cancel_policy_change::*;
+pub use apply_policy_change::*;
+pub use transfer_position_to_creator::*;
+pub use migrate_accounts::*;
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::*,
+    errors::FeeRoutingError,
+    events::{emit_event, PolicyChangeQueued},
+    state::{PendingPolicyChange, Policy, PolicyUpdate},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct QueuePolicyChange<'info> {
+    /// Current policy authority, pays for the pending change
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POLICY_SEED],
+        bump = policy.bump,
+        has_one = authority @ FeeRoutingError::Unauthorized
+    )]
+    pub policy: Account<'info, Policy>,
+
+    /// Pending change PDA at `policy.next_policy_change_id`
+    #[account(
+        init,
+        payer = authority,
+        space = PendingPolicyChange::LEN,
+        seeds = [POLICY_CHANGE_SEED, policy.next_policy_change_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pending_change: Account<'info, PendingPolicyChange>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn queue_policy_change_handler(
+    ctx: Context<QueuePolicyChange>,
+    update: PolicyUpdate,
+) -> Result<()> {
+    let fields = update.field_mask();
+    require!(fields != 0, FeeRoutingError::InvalidPolicyParameter);
+    update.validate()?;
+
+    let policy = &mut ctx.accounts.policy;
+    require!(fields & policy.pending_fields == 0, FeeRoutingError::PolicyChangeFieldPending);
+    // A timelock, once set, stays: dropping it would reopen direct updates
+    require!(
+        policy.policy_change_delay_seconds == 0 || update.policy_change_delay_seconds != Some(0),
+        FeeRoutingError::PolicyTimelockPermanent
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let eta = now
+        .checked_add(policy.policy_change_delay_seconds)
+        .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+    let id = policy.next_policy_change_id;
+    policy.pending_fields |= fields;
+    policy.next_policy_change_id = id
+        .checked_add(1)
+        .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+
+    let pending_change = &mut ctx.accounts.pending_change;
+    pending_change.id = id;
+    pending_change.update = update.clone();
+    pending_change.fields = fields;
+    pending_change.queued_by = ctx.accounts.authority.key();
+    pending_change.queued_at = now;
+    pending_change.eta = eta;
+    pending_change.bump = ctx.bumps.pending_change;
+
+    msg!("Policy change {} queued, applicable from {}", id, eta);
+
+    emit_event!(PolicyChangeQueued {
+        id,
+        update,
+        queued_by: ctx.accounts.authority.key(),
+        eta,
+        timestamp: now,
+    });
+
+    Ok(())
+}
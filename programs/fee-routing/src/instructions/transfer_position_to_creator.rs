@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::{
+    constants::*,
+    errors::FeeRoutingError,
+    events::{emit_event, PositionTransferredToCreator},
+    state::{Policy, Progress},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferPositionToCreator<'info> {
+    /// Current policy authority
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [POLICY_SEED],
+        bump = policy.bump,
+        has_one = authority @ FeeRoutingError::Unauthorized
+    )]
+    pub policy: Account<'info, Policy>,
+
+    /// Read for the sunset state and the treasury ledger
+    #[account(
+        seeds = [PROGRESS_SEED],
+        bump = progress.bump
+    )]
+    pub progress: Account<'info, Progress>,
+
+    /// CHECK: Position owner PDA, signs the NFT transfer
+    #[account(
+        seeds = [VAULT_SEED, vault.key().as_ref(), INVESTOR_FEE_POS_OWNER_SEED],
+        bump
+    )]
+    pub position_owner_pda: AccountInfo<'info>,
+
+    /// CHECK: Vault reference
+    pub vault: AccountInfo<'info>,
+
+    /// Position NFT mint
+    #[account(mint::token_program = token_program)]
+    pub position_nft_mint: InterfaceAccount<'info, Mint>,
+
+    /// Position NFT token account held by the owner PDA
+    #[account(
+        mut,
+        token::mint = position_nft_mint,
+        token::authority = position_owner_pda,
+        token::token_program = token_program
+    )]
+    pub position_nft_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Creator's token account for the position NFT
+    #[account(
+        mut,
+        token::mint = position_nft_mint,
+        token::token_program = token_program,
+        constraint = creator_nft_account.owner == policy.creator_wallet
+            @ FeeRoutingError::InvalidAccountOwnership
+    )]
+    pub creator_nft_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program of the position NFT (Token-2022 for CP-AMM positions)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn transfer_position_to_creator_handler(ctx: Context<TransferPositionToCreator>) -> Result<()> {
+    let policy = &ctx.accounts.policy;
+    let progress = &ctx.accounts.progress;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        policy.sunset(progress.fully_vested_at, now),
+        FeeRoutingError::DistributionNotSunset
+    );
+    require!(!progress.day_in_progress(), FeeRoutingError::DistributionDayInProgress);
+    // Without the position no day can open again, so nothing may be left
+    // for one to pay out
+    require!(progress.buckets.total()? == 0, FeeRoutingError::TreasuryNotSettled);
+
+    let bump = ctx.bumps.position_owner_pda;
+    let vault_key = ctx.accounts.vault.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        VAULT_SEED,
+        vault_key.as_ref(),
+        INVESTOR_FEE_POS_OWNER_SEED,
+        &[bump],
+    ]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.position_nft_account.to_account_info(),
+        mint: ctx.accounts.position_nft_mint.to_account_info(),
+        to: ctx.accounts.creator_nft_account.to_account_info(),
+        authority: ctx.accounts.position_owner_pda.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::transfer_checked(
+        cpi_ctx,
+        ctx.accounts.position_nft_account.amount,
+        ctx.accounts.position_nft_mint.decimals,
+    )?;
+
+    msg!("Honorary position transferred to creator {}", policy.creator_wallet);
+
+    emit_event!(PositionTransferredToCreator {
+        position_nft_mint: ctx.accounts.position_nft_mint.key(),
+        creator_wallet: policy.creator_wallet,
+        destination: ctx.accounts.creator_nft_account.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::*,
+    errors::FeeRoutingError,
+    events::{emit_event, PolicyUpdated},
+    state::{Policy, Progress},
+};
+
+pub use crate::state::PolicyUpdate;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdatePolicy<'info> {
+    /// Current policy authority
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POLICY_SEED],
+        bump = policy.bump,
+        has_one = authority @ FeeRoutingError::Unauthorized
+    )]
+    pub policy: Account<'info, Policy>,
+
+    /// Read to make sure no distribution day is half-way through its pages
+    #[account(
+        seeds = [PROGRESS_SEED],
+        bump = progress.bump
+    )]
+    pub progress: Account<'info, Progress>,
+}
+
+pub fn update_policy_handler(ctx: Context<UpdatePolicy>, update: PolicyUpdate) -> Result<()> {
+    // Pages of one day must all see the same policy, otherwise later pages
+    // would split the claimed fees with different parameters than page 0
+    let progress = &ctx.accounts.progress;
+    require!(!progress.day_in_progress(), FeeRoutingError::DistributionDayInProgress);
+
+    // With a delay configured every change goes through the queue
+    let policy = &mut ctx.accounts.policy;
+    require!(policy.policy_change_delay_seconds == 0, FeeRoutingError::PolicyChangeTimelocked);
+    require!(
+        update.field_mask() & policy.pending_fields == 0,
+        FeeRoutingError::PolicyChangeFieldPending
+    );
+    update.validate()?;
+    update.apply_to(policy);
+
+    msg!("Policy updated");
+
+    emit_event!(PolicyUpdated::new(
+        ctx.accounts.authority.key(),
+        policy,
+        Clock::get()?.unix_timestamp,
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::*,
+    errors::FeeRoutingError,
+    events::{emit_event, Y0Updated},
+    state::{Policy, PolicyUpdate, Progress},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateY0<'info> {
+    /// Current policy authority
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POLICY_SEED],
+        bump = policy.bump,
+        has_one = authority @ FeeRoutingError::Unauthorized
+    )]
+    pub policy: Account<'info, Policy>,
+
+    /// Y0 stays fixed while a distribution day is in progress
+    #[account(
+        seeds = [PROGRESS_SEED],
+        bump = progress.bump
+    )]
+    pub progress: Account<'info, Progress>,
+}
+
+pub fn update_y0_handler(ctx: Context<UpdateY0>, y0: u64) -> Result<()> {
+    let progress = &ctx.accounts.progress;
+    require!(!progress.day_in_progress(), FeeRoutingError::DistributionDayInProgress);
+    require!(y0 > 0, FeeRoutingError::InvalidPolicyParameter);
+
+    let policy = &mut ctx.accounts.policy;
+    require!(policy.policy_change_delay_seconds == 0, FeeRoutingError::PolicyChangeTimelocked);
+    let update = PolicyUpdate { y0: Some(y0), ..Default::default() };
+    require!(
+        update.field_mask() & policy.pending_fields == 0,
+        FeeRoutingError::PolicyChangeFieldPending
+    );
+    let previous_y0 = policy.y0;
+    policy.y0 = y0;
+
+    msg!("Y0 updated: {} -> {}", previous_y0, y0);
+
+    emit_event!(Y0Updated {
+        updated_by: ctx.accounts.authority.key(),
+        previous_y0,
+        y0,
+        effective_y0: policy.effective_y0(progress.registered_deposits),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
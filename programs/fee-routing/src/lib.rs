@@ -6,7 +6,8 @@
 // 1. #![allow(deprecated)] - Suppresses: "use of deprecated AccountInfo::realloc"
 //    - Source: Anchor's #[program] macro at line 18 (NOT our code)
 //    - Reason: Anchor 0.31.1 internally uses AccountInfo::realloc in macro expansion
-//    - Our code: We do NOT use realloc anywhere (verified via grep)
+//    - Our code: migrate_accounts grows accounts with AccountInfo::resize, the
+//      non-deprecated replacement
 //    - Safety: The method still works, deprecation is Anchor's internal issue
 //    - Evidence: Warning points to "#[program]" macro, not our actual code
 //
@@ -73,11 +74,21 @@ pub mod fee_routing {
         initialize_progress_handler(ctx)
     }
 
+    /// Initialize the DistributionHistory ring buffer of closed days
+    pub fn initialize_history(ctx: Context<InitializeHistory>) -> Result<()> {
+        initialize_history_handler(ctx)
+    }
+
     /// Initialize the honorary fee position (quote-only)
     pub fn initialize_position(ctx: Context<InitializePosition>) -> Result<()> {
         initialize_position_handler(ctx)
     }
 
+    /// Register a stream and create the ledger holding its below-minimum payouts (policy authority only)
+    pub fn initialize_investor_ledger(ctx: Context<InitializeInvestorLedger>) -> Result<()> {
+        initialize_investor_ledger_handler(ctx)
+    }
+
     /// Permissionless 24h distribution crank (supports pagination)
     pub fn distribute_fees<'info>(
         ctx: Context<'_, '_, '_, 'info, DistributeFees<'info>>,
@@ -86,4 +97,39 @@ pub mod fee_routing {
     ) -> Result<()> {
         distribute_fees_handler(ctx, page_index, is_final_page)
     }
+
+    /// Update policy parameters (policy authority only, between distribution days)
+    pub fn update_policy(ctx: Context<UpdatePolicy>, update: PolicyUpdate) -> Result<()> {
+        update_policy_handler(ctx, update)
+    }
+
+    /// Set the fixed Y0 after TGE (policy authority only, between distribution days)
+    pub fn update_y0(ctx: Context<UpdateY0>, y0: u64) -> Result<()> {
+        update_y0_handler(ctx, y0)
+    }
+
+    /// Queue a policy change behind the policy's timelock delay (policy authority only)
+    pub fn queue_policy_change(ctx: Context<QueuePolicyChange>, update: PolicyUpdate) -> Result<()> {
+        queue_policy_change_handler(ctx, update)
+    }
+
+    /// Drop a queued policy change before its delay ends (policy authority only)
+    pub fn cancel_policy_change(ctx: Context<CancelPolicyChange>) -> Result<()> {
+        cancel_policy_change_handler(ctx)
+    }
+
+    /// Apply a queued policy change once its delay has passed (permissionless)
+    pub fn apply_policy_change(ctx: Context<ApplyPolicyChange>) -> Result<()> {
+        apply_policy_change_handler(ctx)
+    }
+
+    /// Hand the honorary position NFT to the creator once distribution has sunset (policy authority only)
+    pub fn transfer_position_to_creator(ctx: Context<TransferPositionToCreator>) -> Result<()> {
+        transfer_position_to_creator_handler(ctx)
+    }
+
+    /// Grow Policy and Progress from an earlier release's layout to the current one (policy authority only)
+    pub fn migrate_accounts(ctx: Context<MigrateAccounts>) -> Result<()> {
+        migrate_accounts_handler(ctx)
+    }
 }
@@ -1,9 +1,114 @@
 use anchor_lang::prelude::*;
-use crate::{constants::BPS_DENOMINATOR, errors::FeeRoutingError};
+use crate::{
+    constants::{BPS_DENOMINATOR, DISTRIBUTION_WINDOW_SECONDS},
+    errors::FeeRoutingError,
+    state::{AllocationMode, CapExcessMode, Policy, RoundingMode},
+};
 
 /// Pro-rata distribution calculation utilities
 pub struct DistributionMath;
 
+/// An investor's `InvestorLedger` balances going into a page
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LedgerBalance {
+    /// Below-minimum payouts from earlier days not yet transferred
+    pub owed: u64,
+}
+
+/// One investor's result within a page
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvestorShare {
+    pub locked: u64,
+    /// The investor's pro-rata share of this page
+    pub payout: u64,
+    /// Owed balance from earlier days paid out together with `payout`
+    pub owed_released: u64,
+    /// Owed balance after this page
+    pub owed: u64,
+    /// False when `payout` plus the owed balance it can release is below
+    /// `min_payout_lamports`, so `payout` is credited to `owed` instead
+    pub paid: bool,
+}
+
+impl InvestorShare {
+    /// Amount transferred to the investor
+    pub fn transfer_amount(&self) -> u64 {
+        if self.paid {
+            self.payout + self.owed_released
+        } else {
+            0
+        }
+    }
+}
+
+/// The investor side of a distribution day, fixed on page 0
+///
+/// A page only sees its own investors, so the eligible share is measured
+/// once, against `locked_basis`: the locked total of every registered stream
+/// as it was last read. Each page then gets `investor_allocation` in
+/// proportion to its own locked amount, whatever order the pages run in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DayTerms {
+    /// The day's pool: claimed quote plus the buckets rolled in on page 0
+    pub day_pool: u64,
+    pub locked_basis: u64,
+    pub eligible_share_bps: u64,
+    /// The investors' share of `day_pool` across all pages
+    pub investor_allocation: u64,
+}
+
+/// Everything a `distribute_fees` page computes before moving tokens
+///
+/// Shared by the program and off-chain tooling (simulator, indexer checks)
+/// so both run exactly the same arithmetic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageDistribution {
+    /// The day's pool: claimed quote plus the buckets rolled in on page 0
+    pub day_pool: u64,
+    /// Investor budget left before this page
+    pub investor_budget: u64,
+    pub total_locked: u64,
+    pub locked_fraction_bps: u64,
+    pub eligible_share_bps: u64,
+    /// The page's share of the day's pool, within the investor budget
+    pub investor_allocation: u64,
+    /// Investor allocation after the daily cap
+    pub distributable: u64,
+    /// Allocation held back by the daily cap
+    pub cap_carry_over: u64,
+    pub shares: Vec<InvestorShare>,
+    /// Everything transferred to investors, including `owed_released`
+    pub page_total_distributed: u64,
+    pub investors_paid: u16,
+    /// Payouts below the minimum threshold, credited to owed balances
+    pub below_minimum_dust: u64,
+    /// Owed balances from earlier days paid out on this page
+    pub owed_released: u64,
+    /// The day's per-investor cap, before what each investor was already paid
+    pub payout_limit: Option<u64>,
+    /// Payouts above the per-investor caps that no investor took, paid to
+    /// the creator when the day closes
+    pub payout_cap_excess: u64,
+    /// `distributable` minus the payouts and `payout_cap_excess`
+    pub rounding_dust: u64,
+}
+
+impl PageDistribution {
+    /// What this page adds to the carry buckets rolled into the next day's pool
+    pub fn carry_over(&self) -> Result<u64> {
+        Ok(self.cap_carry_over
+            .checked_add(self.rounding_dust)
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?)
+    }
+
+    /// Paid out of this page's allocation, without owed balances released
+    pub fn allocation_paid(&self) -> Result<u64> {
+        Ok(self.page_total_distributed
+            .checked_sub(self.owed_released)
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?)
+    }
+}
+
 impl DistributionMath {
     /// Calculate locked fraction: f_locked(t) = locked_total(t) / Y0
     /// Returns basis points (0-10000 = 0%-100%)
@@ -60,6 +165,30 @@ impl DistributionMath {
         Ok(allocation as u64)
     }
 
+    /// Investor allocation without truncating the locked fraction to bps
+    /// investor_fee_quote = floor(claimed_quote * min(share_bps / 10000, locked_total / Y0))
+    pub fn calculate_investor_allocation_full_precision(
+        claimed_quote: u64,
+        locked_total: u64,
+        y0: u64,
+        max_investor_fee_share_bps: u16,
+    ) -> Result<u64> {
+        require!(y0 > 0, FeeRoutingError::ArithmeticOverflow);
+        require!(locked_total <= y0, FeeRoutingError::LockedExceedsTotal);
+
+        // floor(min(a, b)) == min(floor(a), floor(b)), so floor each side once
+        let by_share = (claimed_quote as u128)
+            .checked_mul(max_investor_fee_share_bps as u128)
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?
+            / BPS_DENOMINATOR as u128;
+        let by_locked = (claimed_quote as u128)
+            .checked_mul(locked_total as u128)
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?
+            / y0 as u128;
+
+        Ok(by_share.min(by_locked) as u64)
+    }
+
     /// Calculate per-investor payout based on their locked amount
     /// weight_i(t) = locked_i(t) / locked_total(t)
     /// payout_i = floor(investor_fee_quote * weight_i(t))
@@ -68,12 +197,13 @@ impl DistributionMath {
         total_locked: u64,
         investor_allocation: u64,
     ) -> Result<u64> {
-        require!(total_locked > 0, FeeRoutingError::ArithmeticOverflow);
-
+        // Also covers a page whose streams have all fully unlocked
         if investor_locked == 0 {
             return Ok(0);
         }
 
+        require!(total_locked > 0, FeeRoutingError::ArithmeticOverflow);
+
         // Calculate: (investor_allocation * investor_locked) / total_locked
         let numerator = (investor_allocation as u128)
             .checked_mul(investor_locked as u128)
@@ -86,6 +216,51 @@ impl DistributionMath {
         Ok(payout as u64)
     }
 
+    /// Hamilton apportionment of the lamports floor division left over:
+    /// one each to the investors with the largest remainders of
+    /// `distributable * locked_i / total_locked`, ties to the lower stream key
+    /// and then to page order. Afterwards `payouts` sum to `distributable`.
+    pub fn apply_largest_remainder(
+        payouts: &mut [u64],
+        locked_amounts: &[u64],
+        stream_keys: &[Pubkey],
+        total_locked: u64,
+        distributable: u64,
+    ) -> Result<()> {
+        if total_locked == 0 {
+            return Ok(());
+        }
+
+        let mut floored: u64 = 0;
+        for payout in payouts.iter() {
+            floored = floored
+                .checked_add(*payout)
+                .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        }
+        // Less than one lamport per investor with a non-zero remainder
+        let leftover = distributable.saturating_sub(floored) as usize;
+        if leftover == 0 {
+            return Ok(());
+        }
+
+        let mut ranked: Vec<(u128, Pubkey, usize)> = locked_amounts
+            .iter()
+            .enumerate()
+            .map(|(i, locked)| {
+                let remainder = (distributable as u128 * *locked as u128) % total_locked as u128;
+                (remainder, stream_keys.get(i).copied().unwrap_or_default(), i)
+            })
+            .collect();
+        ranked.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+        for &(_, _, i) in ranked.iter().take(leftover) {
+            payouts[i] = payouts[i]
+                .checked_add(1)
+                .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        }
+        Ok(())
+    }
+
     /// Apply daily cap to total distribution
     /// Returns (amount_to_distribute, carry_over_to_next_day)
     pub fn apply_daily_cap(
@@ -98,9 +273,7 @@ impl DistributionMath {
             return Ok((total_available, 0));
         }
 
-        let remaining_cap = daily_cap
-            .checked_sub(already_distributed_today)
-            .unwrap_or(0);
+        let remaining_cap = daily_cap.saturating_sub(already_distributed_today);
 
         if total_available <= remaining_cap {
             // Can distribute everything
@@ -114,6 +287,103 @@ impl DistributionMath {
         }
     }
 
+    /// Most one investor may be paid on a day, owed balances included:
+    /// `max_investor_payout_bps` of the day's investor allocation or
+    /// `max_investor_payout_lamports`, whichever is lower. `None` when
+    /// neither cap is set.
+    pub fn investor_payout_limit(policy: &Policy, day: &DayTerms) -> Option<u64> {
+        let by_share = (policy.max_investor_payout_bps > 0).then(|| {
+            (day.investor_allocation as u128 * policy.max_investor_payout_bps as u128
+                / BPS_DENOMINATOR as u128) as u64
+        });
+        let by_amount = (policy.max_investor_payout_lamports > 0)
+            .then_some(policy.max_investor_payout_lamports);
+
+        match (by_share, by_amount) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Pro-rata payouts of `distributable` with each payout held to the
+    /// investor's own `limits` entry.
+    /// Under `CapExcessMode::Redistribute` the investors under their limits
+    /// split what the capped ones leave, again pro rata, until no payout is
+    /// over its limit. Returns the payouts and the excess no investor took.
+    pub fn capped_payouts(
+        policy: &Policy,
+        locked_amounts: &[u64],
+        stream_keys: &[Pubkey],
+        distributable: u64,
+        limits: &[u64],
+    ) -> Result<(Vec<u64>, u64)> {
+        let mut capped = vec![false; locked_amounts.len()];
+        loop {
+            // Capped investors drop out of the split and take their limit
+            let mut capped_total: u64 = 0;
+            let mut uncapped_locked: u64 = 0;
+            let mut weights = Vec::with_capacity(locked_amounts.len());
+            for ((locked, capped), limit) in locked_amounts.iter().zip(&capped).zip(limits) {
+                if *capped {
+                    capped_total = capped_total
+                        .checked_add(*limit)
+                        .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+                    weights.push(0);
+                } else {
+                    uncapped_locked = uncapped_locked
+                        .checked_add(*locked)
+                        .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+                    weights.push(*locked);
+                }
+            }
+            let remaining = distributable
+                .checked_sub(capped_total)
+                .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+
+            // Floor division here is what creates rounding dust
+            let mut payouts = Vec::with_capacity(weights.len());
+            for locked in &weights {
+                payouts.push(Self::calculate_investor_payout(*locked, uncapped_locked, remaining)?);
+            }
+            if policy.rounding_mode == RoundingMode::LargestRemainder {
+                Self::apply_largest_remainder(
+                    &mut payouts,
+                    &weights,
+                    stream_keys,
+                    uncapped_locked,
+                    remaining,
+                )?;
+            }
+
+            let over: Vec<usize> = (0..payouts.len())
+                .filter(|&i| !capped[i] && payouts[i] > limits[i])
+                .collect();
+            if policy.cap_excess_mode == CapExcessMode::Creator {
+                let mut excess: u64 = 0;
+                for &i in &over {
+                    excess = excess
+                        .checked_add(payouts[i] - limits[i])
+                        .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+                    payouts[i] = limits[i];
+                }
+                return Ok((payouts, excess));
+            }
+            if over.is_empty() {
+                for ((payout, capped), limit) in payouts.iter_mut().zip(&capped).zip(limits) {
+                    if *capped {
+                        *payout = *limit;
+                    }
+                }
+                // With everyone capped nobody is left to take the rest
+                let excess = if uncapped_locked == 0 && capped.contains(&true) { remaining } else { 0 };
+                return Ok((payouts, excess));
+            }
+            for i in over {
+                capped[i] = true;
+            }
+        }
+    }
+
     /// Check if payout meets minimum threshold
     pub fn meets_minimum_threshold(
         payout: u64,
@@ -121,11 +391,235 @@ impl DistributionMath {
     ) -> bool {
         payout >= min_threshold
     }
+
+    /// Still-locked amount of a Streamflow contract at `now`
+    /// locked = net_amount_deposited - (vested_available + cliff_available)
+    pub fn locked_amount(
+        contract: &streamflow_sdk::state::Contract,
+        now: u64,
+    ) -> Result<u64> {
+        let unlocked = contract.vested_available(now)
+            .checked_add(contract.cliff_available(now))
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+
+        // If fully vested, locked = 0
+        Ok(contract.ix.net_amount_deposited.saturating_sub(unlocked))
+    }
+
+    /// Start of the fixed UTC day `ts` falls in. The 24h gate keeps two
+    /// distribution days from opening in the same one.
+    pub fn window_start(ts: i64) -> i64 {
+        ts - ts.rem_euclid(DISTRIBUTION_WINDOW_SECONDS)
+    }
+
+    /// Approximate time-weighted locked amount over the 24h window from
+    /// `window_start`, taken as the mean of the amounts at the window's two
+    /// endpoints. It only matches the true average while vesting runs
+    /// linearly through the whole window; a cliff, a period step or a
+    /// vesting start or end inside the window counts as half the change,
+    /// wherever in the window it falls
+    pub fn window_average_locked(
+        locked_at: impl Fn(u64) -> Result<u64>,
+        window_start: u64,
+    ) -> Result<u64> {
+        let window_end = window_start
+            .checked_add(DISTRIBUTION_WINDOW_SECONDS as u64)
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        let sum = locked_at(window_start)? as u128 + locked_at(window_end)? as u128;
+        Ok((sum / 2) as u64)
+    }
+
+    /// Fix the day's eligible share on page 0
+    /// eligible_investor_share_bps = min(investor_fee_share_bps, min(locked_basis, Y0) / Y0)
+    ///
+    /// A registration or top-up can take the basis past a fixed Y0, which
+    /// then counts as fully locked rather than halting every page of the day.
+    /// `locked_basis` itself is kept, so pages still split the allocation in
+    /// proportion to what they have locked.
+    pub fn day_terms(policy: &Policy, day_pool: u64, locked_basis: u64) -> Result<DayTerms> {
+        let locked_within_y0 = locked_basis.min(policy.y0);
+        let locked_fraction_bps = Self::calculate_locked_fraction_bps(locked_within_y0, policy.y0)?;
+        let eligible_share_bps = Self::calculate_eligible_investor_share_bps(
+            locked_fraction_bps,
+            policy.investor_fee_share_bps,
+        );
+        let investor_allocation = match policy.allocation_mode {
+            AllocationMode::Legacy => Self::calculate_investor_allocation(
+                day_pool,
+                eligible_share_bps,
+            )?,
+            AllocationMode::FullPrecision => Self::calculate_investor_allocation_full_precision(
+                day_pool,
+                locked_within_y0,
+                policy.y0,
+                policy.investor_fee_share_bps,
+            )?,
+        };
+
+        Ok(DayTerms {
+            day_pool,
+            locked_basis,
+            eligible_share_bps,
+            investor_allocation,
+        })
+    }
+
+    /// A page's share of the day's investor allocation
+    /// page_allocation = floor(investor_allocation * min(locked_page, locked_basis) / locked_basis)
+    ///
+    /// While the share cap doesn't bind this is `day_pool * locked_page / Y0`,
+    /// so the pages add up to the day's locked share whatever the basis.
+    /// Under the cap, streams that vested since they were last read leave
+    /// part of the allocation to the creator. No page takes more than the
+    /// whole allocation, however much was topped up since the last read.
+    pub fn page_allocation(day: &DayTerms, page_locked: u64) -> Result<u64> {
+        // Nothing was locked when the day opened, so nothing is allocated
+        if day.locked_basis == 0 {
+            return Ok(0);
+        }
+        Self::calculate_investor_payout(
+            page_locked.min(day.locked_basis),
+            day.locked_basis,
+            day.investor_allocation,
+        )
+    }
+
+    /// Run the pro-rata math for one page of investors
+    /// The page gets its locked share of the day's investor allocation, at
+    /// most `investor_budget`. `stream_keys` and `balances` run parallel to
+    /// `locked_amounts`: the keys only break largest-remainder ties,
+    /// `balances` are each investor's ledger balances (missing entries count
+    /// as 0). `already_distributed_today` is what earlier pages of the day
+    /// paid out of their allocations, which is what the daily cap limits.
+    pub fn distribute_page(
+        policy: &Policy,
+        day: &DayTerms,
+        investor_budget: u64,
+        locked_amounts: &[u64],
+        stream_keys: &[Pubkey],
+        balances: &[LedgerBalance],
+        already_distributed_today: u64,
+    ) -> Result<PageDistribution> {
+        let mut total_locked: u64 = 0;
+        for locked in locked_amounts {
+            total_locked = total_locked
+                .checked_add(*locked)
+                .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        }
+
+        // Top-ups since the streams were last read can push a page past the
+        // basis, so the budget still bounds what pages take in total
+        let day_pool = day.day_pool;
+        let locked_fraction_bps = Self::calculate_locked_fraction_bps(total_locked.min(policy.y0), policy.y0)?;
+        let eligible_share_bps = day.eligible_share_bps;
+        let investor_allocation = Self::page_allocation(day, total_locked)?.min(investor_budget);
+        let (distributable, cap_carry_over) = Self::apply_daily_cap(
+            investor_allocation,
+            policy.daily_cap_lamports,
+            already_distributed_today,
+        )?;
+
+        let mut shares = Vec::with_capacity(locked_amounts.len());
+        let mut page_total_distributed = 0u64;
+        let mut investors_paid = 0u16;
+        let mut below_minimum_dust = 0u64;
+        let mut owed_released = 0u64;
+        let mut total_theoretical_payout = 0u64;
+
+        // A stream is on one page a day, so the cap is what this page may
+        // pay each investor, owed balances included
+        let limit = Self::investor_payout_limit(policy, day);
+        let balance = |i: usize| balances.get(i).copied().unwrap_or_default();
+        let limits = vec![limit.unwrap_or(u64::MAX); locked_amounts.len()];
+        let (payouts, payout_cap_excess) = Self::capped_payouts(
+            policy,
+            locked_amounts,
+            stream_keys,
+            distributable,
+            &limits,
+        )?;
+
+        for (i, (locked, payout)) in locked_amounts.iter().zip(payouts).enumerate() {
+            total_theoretical_payout = total_theoretical_payout
+                .checked_add(payout)
+                .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+
+            // The owed balance is released only as far as the cap leaves
+            // room, and the minimum applies to the running total, so both
+            // only ever delay a payout
+            let owed_before = balance(i).owed;
+            let room = limits[i]
+                .checked_sub(payout)
+                .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+            let release = owed_before.min(room);
+            let due = payout
+                .checked_add(release)
+                .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+            let paid = Self::meets_minimum_threshold(due, policy.min_payout_lamports);
+            let share = if paid {
+                page_total_distributed = page_total_distributed
+                    .checked_add(due)
+                    .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+                owed_released = owed_released
+                    .checked_add(release)
+                    .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+                investors_paid = investors_paid.checked_add(1)
+                    .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+                InvestorShare {
+                    locked: *locked,
+                    payout,
+                    owed_released: release,
+                    owed: owed_before - release,
+                    paid,
+                }
+            } else {
+                below_minimum_dust = below_minimum_dust
+                    .checked_add(payout)
+                    .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+                InvestorShare {
+                    locked: *locked,
+                    payout,
+                    owed_released: 0,
+                    owed: owed_before
+                        .checked_add(payout)
+                        .ok_or(FeeRoutingError::ArithmeticOverflow)?,
+                    paid,
+                }
+            };
+
+            shares.push(share);
+        }
+
+        Ok(PageDistribution {
+            day_pool,
+            investor_budget,
+            total_locked,
+            locked_fraction_bps,
+            eligible_share_bps,
+            investor_allocation,
+            distributable,
+            cap_carry_over,
+            shares,
+            page_total_distributed,
+            investors_paid,
+            below_minimum_dust,
+            owed_released,
+            payout_limit: limit,
+            payout_cap_excess,
+            rounding_dust: distributable
+                .saturating_sub(total_theoretical_payout)
+                .saturating_sub(payout_cap_excess),
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        constants::MAX_FEE_SHARE_STEPS,
+        state::{FeeShareStep, Y0Mode},
+    };
 
     #[test]
     fn test_locked_fraction_calculation() {
@@ -189,6 +683,10 @@ mod tests {
         // Investor with 0 locked tokens
         let result = DistributionMath::calculate_investor_payout(0, 10000, 5000).unwrap();
         assert_eq!(result, 0);
+
+        // Every stream on the page fully unlocked
+        let result = DistributionMath::calculate_investor_payout(0, 0, 0).unwrap();
+        assert_eq!(result, 0);
     }
 
     #[test]
@@ -214,6 +712,437 @@ mod tests {
         assert_eq!(carry, 1000);
     }
 
+    fn policy(y0: u64, share_bps: u16, cap: u64, min_payout: u64) -> Policy {
+        Policy {
+            y0,
+            investor_fee_share_bps: share_bps,
+            daily_cap_lamports: cap,
+            min_payout_lamports: min_payout,
+            quote_mint: Pubkey::default(),
+            creator_wallet: Pubkey::default(),
+            authority: Pubkey::default(),
+            allocation_mode: AllocationMode::Legacy,
+            rounding_mode: RoundingMode::Floor,
+            max_investor_payout_bps: 0,
+            max_investor_payout_lamports: 0,
+            cap_excess_mode: CapExcessMode::Redistribute,
+            locked_mode: Default::default(),
+            y0_mode: Default::default(),
+            fee_share_schedule: Vec::new(),
+            policy_change_delay_seconds: 0,
+            pending_fields: 0,
+            next_policy_change_id: 0,
+            end_ts: 0,
+            bump: 0,
+        }
+    }
+
+    /// A page that makes up the whole day, so its locked total is the basis
+    fn single_page_day(
+        policy: &Policy,
+        day_pool: u64,
+        investor_budget: u64,
+        locked_amounts: &[u64],
+        stream_keys: &[Pubkey],
+        balances: &[LedgerBalance],
+        already_distributed_today: u64,
+    ) -> Result<PageDistribution> {
+        let day = DistributionMath::day_terms(policy, day_pool, locked_amounts.iter().sum())?;
+        DistributionMath::distribute_page(
+            policy,
+            &day,
+            investor_budget,
+            locked_amounts,
+            stream_keys,
+            balances,
+            already_distributed_today,
+        )
+    }
+
+    #[test]
+    fn test_distribute_page() {
+        // 60% locked, share capped at 50%: 5000 of 10000 to investors
+        let page = single_page_day(
+            &policy(10_000, 5_000, 0, 100),
+            10_000,
+            5_000,
+            &[3_000, 2_999, 1],
+            &[],
+            &[],
+            0,
+        ).unwrap();
+        assert_eq!(page.eligible_share_bps, 5000);
+        assert_eq!(page.distributable, 5000);
+        let payouts: Vec<u64> = page.shares.iter().map(|s| s.payout).collect();
+        assert_eq!(payouts, vec![2500, 2499, 0]);
+        assert!(!page.shares[2].paid);
+        assert_eq!(page.page_total_distributed, 4999);
+        assert_eq!(page.rounding_dust, 1);
+        assert_eq!(page.carry_over().unwrap(), 1);
+
+        // A later page only gets what is left of the day's investor budget
+        let page = single_page_day(
+            &policy(10_000, 5_000, 0, 0),
+            10_000,
+            1_000,
+            &[4_000],
+            &[],
+            &[],
+            0,
+        ).unwrap();
+        assert_eq!(page.eligible_share_bps, 4000);
+        assert_eq!(page.investor_allocation, 1000);
+        assert_eq!(page.page_total_distributed, 1000);
+
+        // Cap of 4000 with 1000 already paid today holds back 2000
+        let page = single_page_day(
+            &policy(10_000, 5_000, 4_000, 0),
+            10_000,
+            5_000,
+            &[6_000],
+            &[],
+            &[],
+            1_000,
+        ).unwrap();
+        assert_eq!(page.distributable, 3000);
+        assert_eq!(page.cap_carry_over, 2000);
+        assert_eq!(page.carry_over().unwrap(), 2000);
+    }
+
+    #[test]
+    fn test_pages_split_the_day_allocation_in_any_order() {
+        // 70% locked across two pages, share capped at 50%
+        let policy = policy(10_000, 5_000, 0, 0);
+        let day = DistributionMath::day_terms(&policy, 10_000, 7_000).unwrap();
+        assert_eq!((day.eligible_share_bps, day.investor_allocation), (5_000, 5_000));
+
+        let allocation = |budget, locked: &[u64]| {
+            DistributionMath::distribute_page(&policy, &day, budget, locked, &[], &[], 0)
+                .unwrap()
+                .investor_allocation
+        };
+        // Each page gets the same allocation whether it runs first or second
+        assert_eq!(allocation(5_000, &[2_000, 1_000]), 2_142);
+        assert_eq!(allocation(5_000 - 2_142, &[4_000]), 2_857);
+        assert_eq!(allocation(5_000, &[4_000]), 2_857);
+        assert_eq!(allocation(5_000 - 2_857, &[2_000, 1_000]), 2_142);
+
+        // Under the share cap a page gets exactly its locked share of the pool
+        let day = DistributionMath::day_terms(&policy, 10_000, 4_000).unwrap();
+        assert_eq!(DistributionMath::page_allocation(&day, 1_500).unwrap(), 1_500);
+        assert_eq!(DistributionMath::page_allocation(&day, u64::MAX).unwrap(), 4_000);
+
+        // Nothing was locked when the day opened
+        let day = DistributionMath::day_terms(&policy, 10_000, 0).unwrap();
+        assert_eq!(DistributionMath::page_allocation(&day, 1_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_full_precision_allocation() {
+        // 1.23456% locked: legacy floors to 123 bps, full precision keeps the rest
+        let claimed = 1_000_000_000_000;
+        let legacy = single_page_day(
+            &policy(100_000_000, 5_000, 0, 0),
+            claimed,
+            claimed,
+            &[1_234_560],
+            &[],
+            &[],
+            0,
+        ).unwrap();
+        assert_eq!(legacy.investor_allocation, 12_300_000_000);
+
+        let mut full = policy(100_000_000, 5_000, 0, 0);
+        full.allocation_mode = AllocationMode::FullPrecision;
+        let page = single_page_day(&full, claimed, claimed, &[1_234_560], &[], &[], 0).unwrap();
+        assert_eq!(page.investor_allocation, 12_345_600_000);
+        assert_eq!(page.eligible_share_bps, 123);
+
+        // Share cap still binds, and a single floor happens at the end
+        assert_eq!(
+            DistributionMath::calculate_investor_allocation_full_precision(10_001, 9, 10, 5_000).unwrap(),
+            5_000,
+        );
+        assert_eq!(
+            DistributionMath::calculate_investor_allocation_full_precision(10, 1, 3, 10_000).unwrap(),
+            3,
+        );
+        assert_eq!(
+            DistributionMath::calculate_investor_allocation_full_precision(u64::MAX, u64::MAX, u64::MAX, 10_000).unwrap(),
+            u64::MAX,
+        );
+        assert!(DistributionMath::calculate_investor_allocation_full_precision(1, 2, 1, 10_000).is_err());
+    }
+
+    #[test]
+    fn test_largest_remainder_pays_out_all_dust() {
+        // y0 equal to the page's locked total, so investors get everything
+        let policy = |y0| Policy {
+            rounding_mode: RoundingMode::LargestRemainder,
+            ..policy(y0, 10_000, 0, 0)
+        };
+        let keys = [
+            Pubkey::new_from_array([3; 32]),
+            Pubkey::new_from_array([1; 32]),
+            Pubkey::new_from_array([2; 32]),
+        ];
+        let payouts = |page: &PageDistribution| -> Vec<u64> {
+            page.shares.iter().map(|s| s.payout).collect()
+        };
+
+        // 100 split 1:1:1 floors to 33 each; one lamport left, three-way tie
+        let page = single_page_day(&policy(3), 100, 100, &[1, 1, 1], &keys, &[], 0).unwrap();
+        assert_eq!(payouts(&page), vec![33, 34, 33]);
+        assert_eq!(page.rounding_dust, 0);
+        assert_eq!(page.page_total_distributed, 100);
+
+        // Remainders 2/4, 0 and 2/4 of 10: the tie goes to the lower key
+        let page = single_page_day(&policy(4), 10, 10, &[1, 2, 1], &keys, &[], 0).unwrap();
+        assert_eq!(payouts(&page), vec![2, 5, 3]);
+
+        // Without keys ties go by page order
+        let page = single_page_day(&policy(3), 100, 100, &[1, 1, 1], &[], &[], 0).unwrap();
+        assert_eq!(payouts(&page), vec![34, 33, 33]);
+
+        // Zero-locked investors never pick up a lamport
+        let page = single_page_day(&policy(2), 7, 7, &[0, 1, 1], &keys, &[], 0).unwrap();
+        assert_eq!(payouts(&page), vec![0, 4, 3]);
+    }
+
+    #[test]
+    fn test_below_minimum_payouts_are_owed_not_pooled() {
+        let policy = policy(10_000, 10_000, 0, 100);
+
+        // 60 is under the minimum and credited to the investor's balance
+        let page = single_page_day(&policy, 1_000, 1_000, &[9_400, 600], &[], &[], 0).unwrap();
+        assert_eq!(page.shares[1].payout, 60);
+        assert!(!page.shares[1].paid);
+        assert_eq!(page.shares[1].owed, 60);
+        assert_eq!(page.below_minimum_dust, 60);
+        assert_eq!(page.carry_over().unwrap(), 0);
+
+        // Next day 60 more reaches the minimum and both are paid together
+        let owed = [LedgerBalance::default(), LedgerBalance { owed: 60 }];
+        let page = single_page_day(&policy, 1_000, 1_000, &[9_400, 600], &[], &owed, 0).unwrap();
+        let share = page.shares[1];
+        assert!(share.paid);
+        assert_eq!((share.payout, share.owed_released, share.owed), (60, 60, 0));
+        assert_eq!(share.transfer_amount(), 120);
+        assert_eq!(page.owed_released, 60);
+        assert_eq!(page.page_total_distributed, 1_060);
+        assert_eq!(page.allocation_paid().unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_investor_payout_limit() {
+        let mut policy = policy(10_000, 5_000, 0, 0);
+        // 20% locked: the day allocates 2,000 of a 5,000 investor budget
+        let day = DistributionMath::day_terms(&policy, 10_000, 2_000).unwrap();
+        assert_eq!(day.investor_allocation, 2_000);
+        assert_eq!(DistributionMath::investor_payout_limit(&policy, &day), None);
+
+        // 10% of what the day actually allocates to investors
+        policy.max_investor_payout_bps = 1_000;
+        assert_eq!(DistributionMath::investor_payout_limit(&policy, &day), Some(200));
+
+        // The lower of the two caps wins
+        policy.max_investor_payout_lamports = 150;
+        assert_eq!(DistributionMath::investor_payout_limit(&policy, &day), Some(150));
+        policy.max_investor_payout_bps = 0;
+        assert_eq!(DistributionMath::investor_payout_limit(&policy, &DayTerms::default()), Some(150));
+    }
+
+    #[test]
+    fn test_investor_cap_counts_owed_balances() {
+        let policy = Policy {
+            max_investor_payout_lamports: 450,
+            ..policy(10_000, 10_000, 0, 100)
+        };
+
+        // 500/400/100 of 1,000: A is capped and B and C split its 50 4:1.
+        // B has room for 10 of its 30 owed, C for all 50 of its owed
+        let balances = [
+            LedgerBalance::default(),
+            LedgerBalance { owed: 30 },
+            LedgerBalance { owed: 50 },
+        ];
+        let page = single_page_day(&policy, 1_000, 1_000, &[5_000, 4_000, 1_000], &[], &balances, 0).unwrap();
+        let shares: Vec<_> = page.shares.iter().map(|s| (s.payout, s.owed_released, s.owed, s.transfer_amount())).collect();
+        assert_eq!(shares, vec![(450, 0, 0, 450), (440, 10, 20, 450), (110, 50, 0, 160)]);
+        assert_eq!(page.owed_released, 60);
+        assert_eq!(page.payout_cap_excess, 0);
+
+        // A lone investor takes its cap and the rest goes to the creator
+        let page = single_page_day(&policy, 1_000, 1_000, &[10_000], &[], &[LedgerBalance { owed: 90 }], 0).unwrap();
+        let share = page.shares[0];
+        assert_eq!((share.payout, share.owed_released, share.owed), (450, 0, 90));
+        assert_eq!(page.payout_cap_excess, 550);
+    }
+
+    #[test]
+    fn test_investor_caps_redistribute_excess() {
+        // 60/30/10 of 1,000 with no investor above 400
+        let capped = |cap| Policy {
+            max_investor_payout_lamports: cap,
+            ..policy(10_000, 10_000, 0, 0)
+        };
+        let payouts = |page: &PageDistribution| -> Vec<u64> {
+            page.shares.iter().map(|s| s.payout).collect()
+        };
+
+        // A's 200 over the cap goes 3:1 to B and C, which pushes B over too,
+        // so C ends up with everything left
+        let page = single_page_day(&capped(400), 1_000, 1_000, &[6_000, 3_000, 1_000], &[], &[], 0).unwrap();
+        assert_eq!(payouts(&page), vec![400, 400, 200]);
+        assert_eq!(page.payout_cap_excess, 0);
+        assert_eq!(page.rounding_dust, 0);
+        assert_eq!(page.page_total_distributed, 1_000);
+
+        // Once everyone is capped the rest has nowhere to go but the creator
+        let page = single_page_day(&capped(300), 1_000, 1_000, &[6_000, 3_000, 1_000], &[], &[], 0).unwrap();
+        assert_eq!(payouts(&page), vec![300, 300, 300]);
+        assert_eq!(page.payout_cap_excess, 100);
+        assert_eq!(page.page_total_distributed, 900);
+
+        // A cap that rounds down to 0 sends the whole page to the creator
+        let tiny = Policy { max_investor_payout_bps: 1, ..policy(10_000, 10_000, 0, 0) };
+        let page = single_page_day(&tiny, 1_000, 1_000, &[6_000, 3_000, 1_000], &[], &[], 0).unwrap();
+        assert_eq!(page.payout_cap_excess, 1_000);
+        assert_eq!(page.page_total_distributed, 0);
+
+        // Redistributed shares floor like any other payout
+        let page = single_page_day(&capped(500), 1_000, 1_000, &[7_000, 2_000, 1_000], &[], &[], 0).unwrap();
+        assert_eq!(payouts(&page), vec![500, 333, 166]);
+        assert_eq!(page.rounding_dust, 1);
+        assert_eq!(page.payout_cap_excess, 0);
+
+        let lr = Policy { rounding_mode: RoundingMode::LargestRemainder, ..capped(500) };
+        let page = single_page_day(&lr, 1_000, 1_000, &[7_000, 2_000, 1_000], &[], &[], 0).unwrap();
+        assert_eq!(payouts(&page), vec![500, 333, 167]);
+        assert_eq!(page.rounding_dust, 0);
+    }
+
+    #[test]
+    fn test_investor_caps_excess_to_creator() {
+        let policy = Policy {
+            max_investor_payout_bps: 4_000,
+            cap_excess_mode: CapExcessMode::Creator,
+            ..policy(10_000, 10_000, 0, 0)
+        };
+        let page = single_page_day(&policy, 1_000, 1_000, &[6_000, 3_000, 1_000], &[], &[], 0).unwrap();
+        let payouts: Vec<u64> = page.shares.iter().map(|s| s.payout).collect();
+        assert_eq!(payouts, vec![400, 300, 100]);
+        assert_eq!(page.payout_cap_excess, 200);
+        assert_eq!(page.rounding_dust, 0);
+        assert_eq!(page.page_total_distributed, 800);
+    }
+
+    #[test]
+    fn test_window_average_locked() {
+        // 1_000 vesting linearly over two days from t = 86_400
+        let linear = |t: u64| -> Result<u64> {
+            Ok(1_000 - (t.clamp(86_400, 3 * 86_400) - 86_400) * 1_000 / (2 * 86_400))
+        };
+
+        // Any crank time on the second UTC day reads the same window
+        assert_eq!(DistributionMath::window_start(86_400), 86_400);
+        assert_eq!(DistributionMath::window_start(2 * 86_400 - 1), 86_400);
+        let start = DistributionMath::window_start(100_000) as u64;
+        assert_eq!(DistributionMath::window_average_locked(linear, start).unwrap(), 750);
+
+        // A cliff inside the window counts half wherever it falls: only the
+        // endpoints are read, so an unlock 6h in averages the same as one at 12h
+        let cliff = |t: u64| -> Result<u64> { Ok(if t < 86_400 + 43_200 { 800 } else { 0 }) };
+        assert_eq!(DistributionMath::window_average_locked(cliff, 86_400).unwrap(), 400);
+        let early = |t: u64| -> Result<u64> { Ok(if t < 86_400 + 21_600 { 800 } else { 0 }) };
+        assert_eq!(DistributionMath::window_average_locked(early, 86_400).unwrap(), 400);
+        assert!(DistributionMath::window_average_locked(linear, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_fee_share_schedule() {
+        let step = |start_ts, share_bps| FeeShareStep { start_ts, share_bps };
+        let mut policy = policy(1_000, 6_000, 0, 0);
+        assert_eq!(policy.fee_share_bps_at(i64::MAX), 6_000);
+
+        // 60% for the first six months, 40% for the next six, then 20%
+        policy.fee_share_schedule = vec![step(180 * 86_400, 4_000), step(365 * 86_400, 2_000)];
+        assert_eq!(policy.fee_share_bps_at(0), 6_000);
+        assert_eq!(policy.fee_share_bps_at(180 * 86_400 - 1), 6_000);
+        assert_eq!(policy.fee_share_bps_at(180 * 86_400), 4_000);
+        assert_eq!(policy.fee_share_bps_at(365 * 86_400), 2_000);
+        assert!(Policy::validate_fee_share_schedule(&policy.fee_share_schedule).is_ok());
+
+        assert!(Policy::validate_fee_share_schedule(&[]).is_ok());
+        assert!(Policy::validate_fee_share_schedule(&[step(5, 100), step(5, 50)]).is_err());
+        assert!(Policy::validate_fee_share_schedule(&[step(5, 100), step(1, 50)]).is_err());
+        assert!(Policy::validate_fee_share_schedule(&[step(5, 10_001)]).is_err());
+        let too_long: Vec<_> = (0..=MAX_FEE_SHARE_STEPS as i64).map(|i| step(i, 0)).collect();
+        assert!(Policy::validate_fee_share_schedule(&too_long).is_err());
+    }
+
+    #[test]
+    fn test_sunset() {
+        let mut policy = policy(1_000, 6_000, 0, 0);
+        policy.fee_share_schedule = vec![FeeShareStep { start_ts: 100, share_bps: 4_000 }];
+        assert!(!policy.sunset(0, i64::MAX));
+        assert_eq!(policy.day_fee_share_bps(0, 100), 4_000);
+
+        // Full vesting ends the investor share whatever the time
+        assert!(policy.sunset(50, 0));
+        assert_eq!(policy.day_fee_share_bps(50, 100), 0);
+
+        policy.end_ts = 200;
+        assert!(!policy.sunset(0, 199));
+        assert!(policy.sunset(0, 200));
+        assert_eq!(policy.day_fee_share_bps(0, 199), 4_000);
+        assert_eq!(policy.day_fee_share_bps(0, 200), 0);
+    }
+
+    #[test]
+    fn test_stream_deposits_y0() {
+        let mut policy = policy(1_000, 10_000, 0, 0);
+        assert_eq!(policy.effective_y0(1_500), 1_000);
+
+        // A top-up took the streams past the TGE allocation
+        policy.y0_mode = Y0Mode::StreamDeposits;
+        assert_eq!(policy.effective_y0(1_500), 1_500);
+        assert_eq!(policy.effective_y0(0), 1_000);
+
+        let page = |y0| {
+            single_page_day(
+                &Policy { y0, ..policy.clone() },
+                3_000,
+                3_000,
+                &[1_200],
+                &[],
+                &[],
+                0,
+            )
+        };
+        // Past a fixed Y0 the streams count as fully locked
+        assert_eq!(page(policy.y0).unwrap().page_total_distributed, 3_000);
+        assert_eq!(page(policy.effective_y0(1_500)).unwrap().page_total_distributed, 2_400);
+    }
+
+    #[test]
+    fn test_basis_above_fixed_y0_counts_as_fully_locked() {
+        for allocation_mode in [AllocationMode::Legacy, AllocationMode::FullPrecision] {
+            let policy = Policy { allocation_mode, ..policy(10_000, 5_000, 0, 0) };
+
+            // A registration took the basis to 12,000 against a Y0 of 10,000
+            let day = DistributionMath::day_terms(&policy, 10_000, 12_000).unwrap();
+            let at_y0 = DistributionMath::day_terms(&policy, 10_000, 10_000).unwrap();
+            assert_eq!(day.investor_allocation, at_y0.investor_allocation);
+            assert_eq!(day.investor_allocation, 5_000);
+            assert_eq!(day.locked_basis, 12_000);
+
+            // Pages still split it by their share of the whole basis
+            assert_eq!(DistributionMath::page_allocation(&day, 3_000).unwrap(), 1_250);
+            assert_eq!(DistributionMath::page_allocation(&day, 9_000).unwrap(), 3_750);
+        }
+    }
+
     #[test]
     fn test_minimum_threshold() {
         assert!(DistributionMath::meets_minimum_threshold(1000, 500));
@@ -8,6 +8,9 @@
 
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
+use bytemuck::{Pod, Zeroable};
+use std::cell::Ref;
+use crate::errors::FeeRoutingError;
 
 /// Meteora CP-AMM Program ID
 /// cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG
@@ -37,6 +40,187 @@ pub const CREATE_POSITION_IX: [u8; 8] = [48, 215, 197, 153, 96, 203, 180, 133];
 /// ClaimPositionFee instruction discriminator
 pub const CLAIM_POSITION_FEE_IX: [u8; 8] = [180, 38, 154, 17, 133, 33, 162, 211];
 
+/// Pool account discriminator
+pub const POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+
+/// Pool account size, discriminator included
+pub const POOL_LEN: usize = 1112;
+
+/// Position account discriminator
+pub const POSITION_DISCRIMINATOR: [u8; 8] = [170, 188, 143, 228, 122, 64, 247, 208];
+
+/// Position account size, discriminator included
+pub const POSITION_LEN: usize = 408;
+
+/// `CollectFeeMode::OnlyB`: the pool charges fees in token B only
+pub const COLLECT_FEE_MODE_ONLY_B: u8 = 1;
+
+/// A CP-AMM account read in place, without copying or deserializing
+///
+/// Layouts are `repr(C, packed)` so they can be cast from any byte offset;
+/// read multi-byte fields by value (`{ pool.liquidity }`), never by reference.
+pub trait CpAmmAccount: Pod {
+    /// First 8 bytes of the account data
+    const DISCRIMINATOR: [u8; 8];
+    /// Account size, discriminator included
+    const LEN: usize = 8 + std::mem::size_of::<Self>();
+    /// Raised when the owner, discriminator or size does not match
+    const INVALID: FeeRoutingError;
+
+    /// Cast account data, discriminator included
+    fn from_bytes(data: &[u8]) -> Result<&Self> {
+        require!(
+            data.len() >= Self::LEN && data[..8] == Self::DISCRIMINATOR,
+            Self::INVALID
+        );
+        Ok(bytemuck::from_bytes(&data[8..Self::LEN]))
+    }
+
+    /// Cast the data of an account owned by the CP-AMM program
+    fn from_account_data<'a>(owner: &Pubkey, data: &'a [u8]) -> Result<&'a Self> {
+        require_keys_eq!(*owner, cp_amm_program_id(), Self::INVALID);
+        Self::from_bytes(data)
+    }
+
+    /// Borrow an account's data as this layout
+    fn load<'a>(account: &'a AccountInfo) -> Result<Ref<'a, Self>> {
+        require_keys_eq!(*account.owner, cp_amm_program_id(), Self::INVALID);
+        let data = account.try_borrow_data()?;
+        Self::from_bytes(&data)?;
+        Ok(Ref::map(data, |data| bytemuck::from_bytes(&data[8..Self::LEN])))
+    }
+}
+
+/// CP-AMM `Pool` account, after the discriminator
+#[repr(C, packed)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Pool {
+    /// `PoolFeesStruct`, not decoded here
+    pub pool_fees: [u8; 160],
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub whitelisted_vault: Pubkey,
+    pub partner: Pubkey,
+    pub liquidity: u128,
+    pub _padding: u128,
+    pub protocol_a_fee: u64,
+    pub protocol_b_fee: u64,
+    pub partner_a_fee: u64,
+    pub partner_b_fee: u64,
+    pub sqrt_min_price: u128,
+    pub sqrt_max_price: u128,
+    pub sqrt_price: u128,
+    pub activation_point: u64,
+    pub activation_type: u8,
+    pub pool_status: u8,
+    pub token_a_flag: u8,
+    pub token_b_flag: u8,
+    /// 0 = both tokens, 1 = token B only
+    pub collect_fee_mode: u8,
+    pub pool_type: u8,
+    pub version: u8,
+    pub _padding_0: u8,
+    pub fee_a_per_liquidity: [u8; 32],
+    pub fee_b_per_liquidity: [u8; 32],
+    pub permanent_lock_liquidity: u128,
+    pub metrics: PoolMetrics,
+    pub creator: Pubkey,
+    pub _padding_1: [u64; 6],
+    pub reward_infos: [RewardInfo; 2],
+}
+
+impl CpAmmAccount for Pool {
+    const DISCRIMINATOR: [u8; 8] = POOL_DISCRIMINATOR;
+    const INVALID: FeeRoutingError = FeeRoutingError::InvalidPoolAccount;
+}
+
+impl Pool {
+    /// Fees accrue in token B only, so a position never holds base fees
+    pub fn is_quote_only(&self) -> bool {
+        self.collect_fee_mode == COLLECT_FEE_MODE_ONLY_B
+    }
+}
+
+/// Lifetime fee totals kept on the pool
+#[repr(C, packed)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct PoolMetrics {
+    pub total_lp_a_fee: u128,
+    pub total_lp_b_fee: u128,
+    pub total_protocol_a_fee: u64,
+    pub total_protocol_b_fee: u64,
+    pub total_partner_a_fee: u64,
+    pub total_partner_b_fee: u64,
+    pub total_position: u64,
+    pub padding: u64,
+}
+
+/// Farming reward state kept on the pool
+#[repr(C, packed)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct RewardInfo {
+    pub initialized: u8,
+    pub reward_token_flag: u8,
+    pub _padding_0: [u8; 6],
+    pub _padding_1: [u8; 8],
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub funder: Pubkey,
+    pub reward_duration: u64,
+    pub reward_duration_end: u64,
+    pub reward_rate: u128,
+    pub reward_per_token_stored: [u8; 32],
+    pub last_update_time: u64,
+    pub cumulative_seconds_with_empty_liquidity_reward: u64,
+}
+
+/// CP-AMM `Position` account, after the discriminator
+#[repr(C, packed)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Position {
+    pub pool: Pubkey,
+    pub nft_mint: Pubkey,
+    pub fee_a_per_token_checkpoint: [u8; 32],
+    pub fee_b_per_token_checkpoint: [u8; 32],
+    /// Token A fees settled into the position but not yet claimed
+    pub fee_a_pending: u64,
+    /// Token B fees settled into the position but not yet claimed
+    pub fee_b_pending: u64,
+    pub unlocked_liquidity: u128,
+    pub vested_liquidity: u128,
+    pub permanent_locked_liquidity: u128,
+    pub metrics: PositionMetrics,
+    pub reward_infos: [UserRewardInfo; 2],
+    pub padding: [u128; 6],
+}
+
+impl CpAmmAccount for Position {
+    const DISCRIMINATOR: [u8; 8] = POSITION_DISCRIMINATOR;
+    const INVALID: FeeRoutingError = FeeRoutingError::InvalidPositionAccount;
+}
+
+/// Lifetime fees claimed by a position
+#[repr(C, packed)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct PositionMetrics {
+    pub total_claimed_a_fee: u64,
+    pub total_claimed_b_fee: u64,
+}
+
+/// Farming reward state kept on a position
+#[repr(C, packed)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct UserRewardInfo {
+    pub reward_per_token_checkpoint: [u8; 32],
+    pub reward_pendings: u64,
+    pub total_claimed_rewards: u64,
+}
+
+const _: () = assert!(Pool::LEN == POOL_LEN);
+const _: () = assert!(Position::LEN == POSITION_LEN);
+
 /// Account structure for CreatePosition CPI call
 ///
 /// This instruction creates a new NFT-based position in a CP-AMM pool.
@@ -282,3 +466,87 @@ pub fn claim_position_fee_cpi<'info>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Field offsets in the CP-AMM account data, discriminator included
+    const POOL_TOKEN_B_MINT_OFFSET: usize = 200;
+    const POOL_TOKEN_B_VAULT_OFFSET: usize = 264;
+    const POOL_LIQUIDITY_OFFSET: usize = 360;
+    const POOL_COLLECT_FEE_MODE_OFFSET: usize = 484;
+    const POOL_CREATOR_OFFSET: usize = 648;
+    const POSITION_NFT_MINT_OFFSET: usize = 40;
+    const POSITION_FEE_A_PENDING_OFFSET: usize = 136;
+    const POSITION_FEE_B_PENDING_OFFSET: usize = 144;
+    const POSITION_UNLOCKED_LIQUIDITY_OFFSET: usize = 152;
+
+    fn pool_data(token_b_mint: &Pubkey, collect_fee_mode: u8) -> Vec<u8> {
+        let mut data = vec![0; POOL_LEN];
+        data[..8].copy_from_slice(&POOL_DISCRIMINATOR);
+        data[POOL_TOKEN_B_MINT_OFFSET..POOL_TOKEN_B_MINT_OFFSET + 32]
+            .copy_from_slice(token_b_mint.as_ref());
+        data[POOL_COLLECT_FEE_MODE_OFFSET] = collect_fee_mode;
+        data
+    }
+
+    #[test]
+    fn test_discriminators_match_anchor() {
+        let hash = solana_program::hash::hash(b"account:Pool");
+        assert_eq!(hash.to_bytes()[..8], POOL_DISCRIMINATOR);
+        let hash = solana_program::hash::hash(b"account:Position");
+        assert_eq!(hash.to_bytes()[..8], POSITION_DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_pool_layout_reads_fields_at_cp_amm_offsets() {
+        let quote = Pubkey::new_unique();
+        let (vault, creator) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = pool_data(&quote, COLLECT_FEE_MODE_ONLY_B);
+        data[POOL_TOKEN_B_VAULT_OFFSET..POOL_TOKEN_B_VAULT_OFFSET + 32].copy_from_slice(vault.as_ref());
+        data[POOL_LIQUIDITY_OFFSET..POOL_LIQUIDITY_OFFSET + 16].copy_from_slice(&7u128.to_le_bytes());
+        data[POOL_CREATOR_OFFSET..POOL_CREATOR_OFFSET + 32].copy_from_slice(creator.as_ref());
+
+        let pool = Pool::from_bytes(&data).unwrap();
+        assert_eq!(pool.token_b_mint, quote);
+        assert_eq!(pool.token_b_vault, vault);
+        assert_eq!({ pool.liquidity }, 7);
+        assert_eq!(pool.creator, creator);
+        assert!(pool.is_quote_only());
+
+        assert!(!Pool::from_bytes(&pool_data(&quote, 0)).unwrap().is_quote_only());
+    }
+
+    #[test]
+    fn test_position_layout_reads_fields_at_cp_amm_offsets() {
+        let nft_mint = Pubkey::new_unique();
+        let mut data = vec![0; POSITION_LEN];
+        data[..8].copy_from_slice(&POSITION_DISCRIMINATOR);
+        data[POSITION_NFT_MINT_OFFSET..POSITION_NFT_MINT_OFFSET + 32].copy_from_slice(nft_mint.as_ref());
+        data[POSITION_FEE_A_PENDING_OFFSET..POSITION_FEE_A_PENDING_OFFSET + 8].copy_from_slice(&3u64.to_le_bytes());
+        data[POSITION_FEE_B_PENDING_OFFSET..POSITION_FEE_B_PENDING_OFFSET + 8].copy_from_slice(&5u64.to_le_bytes());
+        data[POSITION_UNLOCKED_LIQUIDITY_OFFSET..POSITION_UNLOCKED_LIQUIDITY_OFFSET + 16]
+            .copy_from_slice(&11u128.to_le_bytes());
+
+        let position = Position::from_bytes(&data).unwrap();
+        assert_eq!(position.nft_mint, nft_mint);
+        assert_eq!(({ position.fee_a_pending }, { position.fee_b_pending }), (3, 5));
+        assert_eq!({ position.unlocked_liquidity }, 11);
+    }
+
+    #[test]
+    fn test_layouts_reject_wrong_owner_discriminator_and_size() {
+        let quote = Pubkey::new_unique();
+        let data = pool_data(&quote, COLLECT_FEE_MODE_ONLY_B);
+        assert!(Pool::from_account_data(&cp_amm_program_id(), &data).is_ok());
+        assert!(Pool::from_account_data(&Pubkey::new_unique(), &data).is_err());
+        assert!(Pool::from_bytes(&data[..POOL_LEN - 1]).is_err());
+        // A pool is not a position, even when it is long enough
+        assert!(Position::from_bytes(&data).is_err());
+
+        let mut wrong_discriminator = data;
+        wrong_discriminator[0] ^= 1;
+        assert!(Pool::from_bytes(&wrong_discriminator).is_err());
+    }
+}
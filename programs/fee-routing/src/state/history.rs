@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use crate::constants::DISTRIBUTION_HISTORY_DAYS;
+
+/// Summary of a single closed distribution day
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DistributionRecord {
+    /// Distribution day this record closes
+    pub day: u64,
+
+    /// Quote fees claimed from the honorary position on page 0
+    pub claimed_quote: u64,
+
+    /// Carry-over from previous days rolled into this day's distribution
+    pub carry_over_used: u64,
+
+    /// Sum of locked amounts read across every page of the day
+    pub total_locked: u64,
+
+    /// Investor share fixed for the day on page 0 (basis points)
+    pub eligible_share_bps: u16,
+
+    /// Total paid out to investors
+    pub investor_total: u64,
+
+    /// Remainder paid out to the creator
+    pub creator_total: u64,
+
+    /// Rounding dust left in the treasury
+    pub rounding_dust: u64,
+
+    /// Number of pages processed
+    pub page_count: u16,
+
+    /// Timestamp of page 0
+    pub started_at: i64,
+
+    /// Timestamp of the final page
+    pub closed_at: i64,
+}
+
+impl DistributionRecord {
+    pub const LEN: usize = 8 + // day
+        8 + // claimed_quote
+        8 + // carry_over_used
+        8 + // total_locked
+        2 + // eligible_share_bps
+        8 + // investor_total
+        8 + // creator_total
+        8 + // rounding_dust
+        2 + // page_count
+        8 + // started_at
+        8; // closed_at
+}
+
+#[account]
+#[derive(Debug)]
+pub struct DistributionHistory {
+    /// Slot the next closed day will be written to
+    pub next_index: u16,
+
+    /// Number of days recorded since initialization (including overwritten ones)
+    pub total_days_recorded: u64,
+
+    /// Ring buffer of the most recent closed days (oldest overwritten first)
+    pub records: Vec<DistributionRecord>,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl DistributionHistory {
+    pub const LEN: usize = 8 + // discriminator
+        2 + // next_index
+        8 + // total_days_recorded
+        4 + DISTRIBUTION_HISTORY_DAYS * DistributionRecord::LEN + // records
+        1; // bump
+
+    /// Append a closed day, overwriting the oldest record once the buffer is full
+    pub fn push(&mut self, record: DistributionRecord) {
+        let index = self.next_index as usize;
+
+        if self.records.len() < DISTRIBUTION_HISTORY_DAYS {
+            self.records.push(record);
+        } else {
+            self.records[index] = record;
+        }
+
+        self.next_index = ((index + 1) % DISTRIBUTION_HISTORY_DAYS) as u16;
+        self.total_days_recorded = self.total_days_recorded.saturating_add(1);
+    }
+
+    /// Most recently closed day, if any
+    pub fn latest(&self) -> Option<&DistributionRecord> {
+        if self.records.is_empty() {
+            return None;
+        }
+
+        let index = (self.next_index as usize + DISTRIBUTION_HISTORY_DAYS - 1)
+            % DISTRIBUTION_HISTORY_DAYS;
+        self.records.get(index)
+    }
+
+    /// Records ordered from oldest to newest
+    pub fn iter_chronological(&self) -> impl Iterator<Item = &DistributionRecord> {
+        let split = if self.records.len() < DISTRIBUTION_HISTORY_DAYS {
+            0
+        } else {
+            self.next_index as usize
+        };
+
+        self.records[split..].iter().chain(self.records[..split].iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history() -> DistributionHistory {
+        DistributionHistory {
+            next_index: 0,
+            total_days_recorded: 0,
+            records: Vec::new(),
+            bump: 255,
+        }
+    }
+
+    fn record(day: u64) -> DistributionRecord {
+        DistributionRecord {
+            day,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_push_before_wrap() {
+        let mut history = history();
+        assert!(history.latest().is_none());
+
+        for day in 1..=3 {
+            history.push(record(day));
+        }
+
+        assert_eq!(history.records.len(), 3);
+        assert_eq!(history.latest().unwrap().day, 3);
+        let days: Vec<u64> = history.iter_chronological().map(|r| r.day).collect();
+        assert_eq!(days, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_push_wraps_and_overwrites_oldest() {
+        let mut history = history();
+        let total = DISTRIBUTION_HISTORY_DAYS as u64 + 5;
+
+        for day in 1..=total {
+            history.push(record(day));
+        }
+
+        assert_eq!(history.records.len(), DISTRIBUTION_HISTORY_DAYS);
+        assert_eq!(history.total_days_recorded, total);
+        assert_eq!(history.latest().unwrap().day, total);
+
+        let days: Vec<u64> = history.iter_chronological().map(|r| r.day).collect();
+        let expected: Vec<u64> = (6..=total).collect();
+        assert_eq!(days, expected);
+    }
+}
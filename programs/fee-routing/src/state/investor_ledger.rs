@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+/// Per-investor balance of payouts held back by `min_payout_lamports`
+///
+/// One per Streamflow stream. Payouts below the minimum are credited here
+/// instead of going back into the shared pool, and the whole balance is paid
+/// on the first page where it reaches the minimum together with that day's
+/// payout.
+#[account]
+#[derive(Debug)]
+pub struct InvestorLedger {
+    /// Streamflow contract this ledger belongs to
+    pub stream: Pubkey,
+
+    /// The stream's `recipient` when it was last read; every payout goes
+    /// to a quote token account this wallet owns
+    pub recipient: Pubkey,
+
+    /// Payouts credited below the minimum and not yet transferred
+    pub owed: u64,
+
+    /// The stream's `net_amount_deposited` when it was last read, counted
+    /// in `Progress.registered_deposits`
+    pub deposited: u64,
+
+    /// What the stream had locked when it was last read, counted in
+    /// `Progress.registered_locked`
+    pub locked: u64,
+
+    /// `Progress.current_day` of the last page that included the stream;
+    /// a second appearance on the same day is rejected, so each stream is
+    /// paid and counted in `Progress.day_streams_read` once a day, and the
+    /// policy's per-investor cap on that one page is its cap for the day
+    pub last_read_day: u64,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl InvestorLedger {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // stream
+        32 + // recipient
+        8 + // owed
+        8 + // deposited
+        8 + // locked
+        8 + // last_read_day
+        1; // bump
+}
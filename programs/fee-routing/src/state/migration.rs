@@ -0,0 +1,196 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    AllocationMode, Buckets, CapExcessMode, LockedMode, Policy, Progress, RoundingMode, Y0Mode,
+};
+
+/// Policy as the first release laid it out, before any field was appended
+///
+/// `migrate_accounts` recognises it by its length and rewrites it as the
+/// current `Policy`. Every later layout change must keep its predecessor
+/// here the same way.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PolicyV1 {
+    pub y0: u64,
+    pub investor_fee_share_bps: u16,
+    pub daily_cap_lamports: u64,
+    pub min_payout_lamports: u64,
+    pub quote_mint: Pubkey,
+    pub creator_wallet: Pubkey,
+    pub authority: Pubkey,
+    pub bump: u8,
+}
+
+impl PolicyV1 {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // y0
+        2 + // investor_fee_share_bps
+        8 + // daily_cap_lamports
+        8 + // min_payout_lamports
+        32 + // quote_mint
+        32 + // creator_wallet
+        32 + // authority
+        1; // bump
+
+    /// The current layout, with every appended field at the value
+    /// `initialize_policy` gives a new policy
+    pub fn migrate(self) -> Policy {
+        Policy {
+            y0: self.y0,
+            investor_fee_share_bps: self.investor_fee_share_bps,
+            daily_cap_lamports: self.daily_cap_lamports,
+            min_payout_lamports: self.min_payout_lamports,
+            quote_mint: self.quote_mint,
+            creator_wallet: self.creator_wallet,
+            authority: self.authority,
+            allocation_mode: AllocationMode::Legacy,
+            rounding_mode: RoundingMode::Floor,
+            max_investor_payout_bps: 0,
+            max_investor_payout_lamports: 0,
+            cap_excess_mode: CapExcessMode::Redistribute,
+            locked_mode: LockedMode::Instant,
+            y0_mode: Y0Mode::Fixed,
+            fee_share_schedule: Vec::new(),
+            policy_change_delay_seconds: 0,
+            pending_fields: 0,
+            next_policy_change_id: 0,
+            end_ts: 0,
+            bump: self.bump,
+        }
+    }
+}
+
+// `migrate_accounts` tells layouts apart by length and only grows accounts
+const _: () = assert!(Policy::LEN > PolicyV1::LEN && Progress::LEN > ProgressV1::LEN);
+
+/// Progress as the first release laid it out
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProgressV1 {
+    pub last_distribution_ts: i64,
+    pub current_day: u64,
+    pub daily_distributed_to_investors: u64,
+    /// Rounding dust and cap excess rolled into the next day
+    pub carry_over_lamports: u64,
+    pub current_page: u16,
+    pub pages_processed_today: u16,
+    pub total_investors: u16,
+    pub creator_payout_sent: bool,
+    pub has_base_fees: bool,
+    pub total_rounding_dust: u64,
+    pub bump: u8,
+}
+
+impl ProgressV1 {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // last_distribution_ts
+        8 + // current_day
+        8 + // daily_distributed_to_investors
+        8 + // carry_over_lamports
+        2 + // current_page
+        2 + // pages_processed_today
+        2 + // total_investors
+        1 + // creator_payout_sent
+        1 + // has_base_fees
+        8 + // total_rounding_dust
+        1; // bump
+
+    /// Whether a distribution day is half-way through its pages
+    pub fn day_in_progress(&self) -> bool {
+        self.current_page != 0 && !self.creator_payout_sent
+    }
+
+    /// The current layout, carrying the day counter and window over
+    ///
+    /// The carry-over rolled into the next day's pool either way, so it
+    /// becomes the `dust` bucket. No stream is registered yet: each needs
+    /// `initialize_investor_ledger` before it can be paid again.
+    pub fn migrate(self) -> Progress {
+        Progress {
+            last_distribution_ts: self.last_distribution_ts,
+            current_day: self.current_day,
+            daily_distributed_to_investors: self.daily_distributed_to_investors,
+            current_page: self.current_page,
+            pages_processed_today: self.pages_processed_today,
+            total_investors: self.total_investors,
+            creator_payout_sent: self.creator_payout_sent,
+            has_base_fees: self.has_base_fees,
+            total_rounding_dust: self.total_rounding_dust,
+            day_claimed_quote: 0,
+            day_carry_over_in: 0,
+            day_total_locked: 0,
+            day_rounding_dust: 0,
+            buckets: Buckets { dust: self.carry_over_lamports, ..Default::default() },
+            day_owed_released: 0,
+            registered_deposits: 0,
+            registered_streams: 0,
+            day_streams_read: 0,
+            fully_vested_at: 0,
+            registered_locked: 0,
+            day_locked_basis: 0,
+            day_eligible_share_bps: 0,
+            day_investor_allocation: 0,
+            bump: self.bump,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v1_lengths_match_their_encoding() {
+        let policy = PolicyV1 {
+            y0: 1,
+            investor_fee_share_bps: 1,
+            daily_cap_lamports: 1,
+            min_payout_lamports: 1,
+            quote_mint: Pubkey::default(),
+            creator_wallet: Pubkey::default(),
+            authority: Pubkey::default(),
+            bump: 1,
+        };
+        assert_eq!(8 + policy.try_to_vec().unwrap().len(), PolicyV1::LEN);
+
+        let progress = ProgressV1 {
+            last_distribution_ts: 1,
+            current_day: 1,
+            daily_distributed_to_investors: 1,
+            carry_over_lamports: 1,
+            current_page: 1,
+            pages_processed_today: 1,
+            total_investors: 1,
+            creator_payout_sent: true,
+            has_base_fees: false,
+            total_rounding_dust: 1,
+            bump: 1,
+        };
+        assert_eq!(8 + progress.try_to_vec().unwrap().len(), ProgressV1::LEN);
+    }
+
+    #[test]
+    fn test_v1_progress_keeps_the_day_and_carry_over() {
+        let v1 = ProgressV1 {
+            last_distribution_ts: 1_700_000_000,
+            current_day: 12,
+            daily_distributed_to_investors: 4_000,
+            carry_over_lamports: 37,
+            current_page: 3,
+            pages_processed_today: 3,
+            total_investors: 150,
+            creator_payout_sent: true,
+            has_base_fees: false,
+            total_rounding_dust: 90,
+            bump: 254,
+        };
+        assert!(!v1.day_in_progress());
+        assert!(ProgressV1 { creator_payout_sent: false, ..v1.clone() }.day_in_progress());
+
+        let progress = v1.migrate();
+        assert_eq!(progress.current_day, 12);
+        assert_eq!(progress.last_distribution_ts, 1_700_000_000);
+        assert_eq!(progress.buckets, Buckets { dust: 37, ..Default::default() });
+        assert_eq!(progress.buckets.total().unwrap(), 37);
+        assert_eq!(progress.registered_streams, 0);
+        assert_eq!(progress.bump, 254);
+    }
+}
@@ -1,5 +1,13 @@
+pub mod history;
+pub mod investor_ledger;
+pub mod migration;
 pub mod policy;
+pub mod policy_change;
 pub mod progress;
 
+pub use history::*;
+pub use investor_ledger::*;
+pub use migration::*;
 pub use policy::*;
+pub use policy_change::*;
 pub use progress::*;
@@ -1,11 +1,87 @@
 use anchor_lang::prelude::*;
+use crate::{
+    constants::{BPS_DENOMINATOR, MAX_FEE_SHARE_STEPS},
+    errors::FeeRoutingError,
+};
+
+/// How the investor allocation is derived from the locked fraction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AllocationMode {
+    /// Floor `locked_total / y0` to whole bps, then apply it to claimed fees
+    #[default]
+    Legacy,
+    /// `claimed * min(share_bps / 10000, locked_total / y0)` with a single
+    /// final floor, so no fraction of a bps is lost
+    FullPrecision,
+}
+
+/// What happens to the lamports floor division leaves over on a page
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Floor every payout; the leftover stays in the treasury as rounding dust
+    #[default]
+    Floor,
+    /// Hamilton apportionment: leftover lamports go one each to the largest
+    /// fractional remainders, ties to the lower stream pubkey
+    LargestRemainder,
+}
+
+/// When a stream's locked amount is read
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LockedMode {
+    /// At the moment the page runs, so it shifts with the crank time
+    #[default]
+    Instant,
+    /// Approximately averaged over the fixed UTC day the distribution day
+    /// opened in, from the amounts at the day's start and end, so every page
+    /// of the day sees the same amounts whenever it lands
+    WindowAverage,
+}
+
+/// Where the Y0 the locked fraction is measured against comes from
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Y0Mode {
+    /// `Policy.y0`, set at initialization and changed with `update_y0`
+    #[default]
+    Fixed,
+    /// The sum of `net_amount_deposited` over every stream with an
+    /// `InvestorLedger`, so top-ups and new rounds raise Y0 by themselves
+    StreamDeposits,
+}
+
+/// One step of the investor fee share schedule: `share_bps` replaces the
+/// share from `start_ts` on
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeShareStep {
+    pub start_ts: i64,
+    pub share_bps: u16,
+}
+
+impl FeeShareStep {
+    pub const LEN: usize = 8 + // start_ts
+        2; // share_bps
+}
+
+/// Where payouts above the per-investor caps go
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CapExcessMode {
+    /// Share the excess pro rata among the investors still under the cap,
+    /// repeating until none is over it (water-filling); only what no
+    /// investor can take goes to the creator
+    #[default]
+    Redistribute,
+    /// Send the whole excess to the creator
+    Creator,
+}
 
 #[account]
+#[derive(Debug)]
 pub struct Policy {
-    /// Total investor allocation at TGE
+    /// Total investor allocation at TGE, used while `y0_mode` is `Fixed`
     pub y0: u64,
 
-    /// Investor fee share in basis points (max 10000 = 100%)
+    /// Investor fee share in basis points (max 10000 = 100%), in effect
+    /// until the first `fee_share_schedule` step starts
     pub investor_fee_share_bps: u16,
 
     /// Optional daily cap in lamports (0 = no cap)
@@ -23,6 +99,50 @@ pub struct Policy {
     /// Authority that can update policy
     pub authority: Pubkey,
 
+    /// Investor allocation rounding, `Legacy` until changed via `update_policy`
+    pub allocation_mode: AllocationMode,
+
+    /// Payout rounding, `Floor` until changed via `update_policy`
+    pub rounding_mode: RoundingMode,
+
+    /// Most one investor may be paid on a day, owed balances included, in
+    /// bps of the day's investor allocation (0 = no cap)
+    pub max_investor_payout_bps: u16,
+
+    /// Most one investor may be paid on a day, owed balances included, in
+    /// lamports (0 = no cap)
+    pub max_investor_payout_lamports: u64,
+
+    /// What happens to payouts above the per-investor caps
+    pub cap_excess_mode: CapExcessMode,
+
+    /// How locked amounts are read, `Instant` until changed via `update_policy`
+    pub locked_mode: LockedMode,
+
+    /// Where Y0 comes from, `Fixed` until changed via `update_policy`
+    pub y0_mode: Y0Mode,
+
+    /// Later investor fee shares by start time, at most
+    /// `MAX_FEE_SHARE_STEPS`; empty keeps `investor_fee_share_bps` for good
+    pub fee_share_schedule: Vec<FeeShareStep>,
+
+    /// How long a change queued with `queue_policy_change` waits before
+    /// `apply_policy_change` may run it. 0 means no timelock: `update_policy`
+    /// and `update_y0` change the policy directly. Any other value is at
+    /// least `MIN_POLICY_CHANGE_DELAY_SECONDS` and can't be set back to 0
+    pub policy_change_delay_seconds: i64,
+
+    /// `PolicyUpdate::field_mask` bits of every queued change, so each field
+    /// has at most one change pending
+    pub pending_fields: u32,
+
+    /// Id of the next `PendingPolicyChange`
+    pub next_policy_change_id: u64,
+
+    /// Distribution sunsets from this time on, like after full vesting
+    /// (0 = no end)
+    pub end_ts: i64,
+
     /// Bump seed for PDA derivation
     pub bump: u8,
 }
@@ -36,5 +156,67 @@ impl Policy {
         32 + // quote_mint
         32 + // creator_wallet
         32 + // authority
+        1 + // allocation_mode
+        1 + // rounding_mode
+        2 + // max_investor_payout_bps
+        8 + // max_investor_payout_lamports
+        1 + // cap_excess_mode
+        1 + // locked_mode
+        1 + // y0_mode
+        4 + MAX_FEE_SHARE_STEPS * FeeShareStep::LEN + // fee_share_schedule
+        8 + // policy_change_delay_seconds
+        4 + // pending_fields
+        8 + // next_policy_change_id
+        8 + // end_ts
         1; // bump
+
+    /// Y0 the locked fraction is measured against, given the deposits of
+    /// the registered streams (`Progress.registered_deposits`)
+    pub fn effective_y0(&self, registered_deposits: u64) -> u64 {
+        match self.y0_mode {
+            Y0Mode::Fixed => self.y0,
+            Y0Mode::StreamDeposits if registered_deposits > 0 => registered_deposits,
+            // With nothing deposited nothing is locked either, so any
+            // positive Y0 gives the same split
+            Y0Mode::StreamDeposits => self.y0,
+        }
+    }
+
+    /// Investor fee share in effect at `ts`: the last schedule step started
+    /// by then, or `investor_fee_share_bps` before the first one
+    pub fn fee_share_bps_at(&self, ts: i64) -> u16 {
+        self.fee_share_schedule
+            .iter()
+            .rev()
+            .find(|step| step.start_ts <= ts)
+            .map_or(self.investor_fee_share_bps, |step| step.share_bps)
+    }
+
+    /// Whether distribution has sunset by `ts`: every registered stream was
+    /// found fully vested (`Progress.fully_vested_at`) or `end_ts` has passed
+    pub fn sunset(&self, fully_vested_at: i64, ts: i64) -> bool {
+        fully_vested_at != 0 || (self.end_ts != 0 && ts >= self.end_ts)
+    }
+
+    /// Investor fee share a day opening at `day_start` splits its pool by:
+    /// 0 once distribution has sunset, the scheduled share before that
+    pub fn day_fee_share_bps(&self, fully_vested_at: i64, day_start: i64) -> u16 {
+        if self.sunset(fully_vested_at, day_start) {
+            0
+        } else {
+            self.fee_share_bps_at(day_start)
+        }
+    }
+
+    /// A schedule fits the account, starts every step strictly after the
+    /// previous one and keeps every share within 100%
+    pub fn validate_fee_share_schedule(schedule: &[FeeShareStep]) -> Result<()> {
+        require!(
+            schedule.len() <= MAX_FEE_SHARE_STEPS
+                && schedule.windows(2).all(|w| w[0].start_ts < w[1].start_ts)
+                && schedule.iter().all(|step| step.share_bps as u64 <= BPS_DENOMINATOR),
+            FeeRoutingError::InvalidFeeShareSchedule
+        );
+        Ok(())
+    }
 }
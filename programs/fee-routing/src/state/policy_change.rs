@@ -0,0 +1,256 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{BPS_DENOMINATOR, MAX_FEE_SHARE_STEPS, MIN_POLICY_CHANGE_DELAY_SECONDS},
+    errors::FeeRoutingError,
+    state::{AllocationMode, CapExcessMode, FeeShareStep, LockedMode, Policy, RoundingMode, Y0Mode},
+};
+
+/// Policy fields to change; `None` leaves the current value
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PolicyUpdate {
+    pub y0: Option<u64>,
+    pub investor_fee_share_bps: Option<u16>,
+    pub daily_cap_lamports: Option<u64>,
+    pub min_payout_lamports: Option<u64>,
+    pub creator_wallet: Option<Pubkey>,
+    /// Hand policy administration to a new authority
+    pub authority: Option<Pubkey>,
+    pub allocation_mode: Option<AllocationMode>,
+    pub rounding_mode: Option<RoundingMode>,
+    /// 0 removes the cap
+    pub max_investor_payout_bps: Option<u16>,
+    /// 0 removes the cap
+    pub max_investor_payout_lamports: Option<u64>,
+    pub cap_excess_mode: Option<CapExcessMode>,
+    pub locked_mode: Option<LockedMode>,
+    pub y0_mode: Option<Y0Mode>,
+    /// Replaces the whole schedule; an empty one keeps `investor_fee_share_bps`
+    pub fee_share_schedule: Option<Vec<FeeShareStep>>,
+    /// 0 (no timelock) or at least `MIN_POLICY_CHANGE_DELAY_SECONDS`; once
+    /// a delay is set it can be changed but not removed
+    pub policy_change_delay_seconds: Option<i64>,
+    /// 0 removes the end
+    pub end_ts: Option<i64>,
+}
+
+impl PolicyUpdate {
+    /// Largest serialized update: every field set and a full schedule
+    pub const MAX_LEN: usize = (1 + 8) + // y0
+        (1 + 2) + // investor_fee_share_bps
+        (1 + 8) + // daily_cap_lamports
+        (1 + 8) + // min_payout_lamports
+        (1 + 32) + // creator_wallet
+        (1 + 32) + // authority
+        (1 + 1) + // allocation_mode
+        (1 + 1) + // rounding_mode
+        (1 + 2) + // max_investor_payout_bps
+        (1 + 8) + // max_investor_payout_lamports
+        (1 + 1) + // cap_excess_mode
+        (1 + 1) + // locked_mode
+        (1 + 1) + // y0_mode
+        (1 + 4 + MAX_FEE_SHARE_STEPS * FeeShareStep::LEN) + // fee_share_schedule
+        (1 + 8) + // policy_change_delay_seconds
+        (1 + 8); // end_ts
+
+    /// One bit per field the update sets, in declaration order
+    pub fn field_mask(&self) -> u32 {
+        [
+            self.y0.is_some(),
+            self.investor_fee_share_bps.is_some(),
+            self.daily_cap_lamports.is_some(),
+            self.min_payout_lamports.is_some(),
+            self.creator_wallet.is_some(),
+            self.authority.is_some(),
+            self.allocation_mode.is_some(),
+            self.rounding_mode.is_some(),
+            self.max_investor_payout_bps.is_some(),
+            self.max_investor_payout_lamports.is_some(),
+            self.cap_excess_mode.is_some(),
+            self.locked_mode.is_some(),
+            self.y0_mode.is_some(),
+            self.fee_share_schedule.is_some(),
+            self.policy_change_delay_seconds.is_some(),
+            self.end_ts.is_some(),
+        ]
+        .iter()
+        .enumerate()
+        .filter(|(_, set)| **set)
+        .fold(0, |mask, (bit, _)| mask | 1 << bit)
+    }
+
+    /// Check every value the update sets
+    pub fn validate(&self) -> Result<()> {
+        if let Some(y0) = self.y0 {
+            require!(y0 > 0, FeeRoutingError::InvalidPolicyParameter);
+        }
+        if let Some(bps) = self.investor_fee_share_bps {
+            require!(bps as u64 <= BPS_DENOMINATOR, FeeRoutingError::InvalidPolicyParameter);
+        }
+        if let Some(bps) = self.max_investor_payout_bps {
+            require!(bps as u64 <= BPS_DENOMINATOR, FeeRoutingError::InvalidPolicyParameter);
+        }
+        if let Some(schedule) = &self.fee_share_schedule {
+            Policy::validate_fee_share_schedule(schedule)?;
+        }
+        if let Some(delay) = self.policy_change_delay_seconds {
+            require!(
+                delay == 0 || delay >= MIN_POLICY_CHANGE_DELAY_SECONDS,
+                FeeRoutingError::InvalidPolicyParameter
+            );
+        }
+        if let Some(end_ts) = self.end_ts {
+            require!(end_ts >= 0, FeeRoutingError::InvalidPolicyParameter);
+        }
+        Ok(())
+    }
+
+    /// Write the fields the update sets into `policy`
+    pub fn apply_to(self, policy: &mut Policy) {
+        if let Some(y0) = self.y0 {
+            policy.y0 = y0;
+        }
+        if let Some(bps) = self.investor_fee_share_bps {
+            policy.investor_fee_share_bps = bps;
+        }
+        if let Some(cap) = self.daily_cap_lamports {
+            policy.daily_cap_lamports = cap;
+        }
+        if let Some(min) = self.min_payout_lamports {
+            policy.min_payout_lamports = min;
+        }
+        if let Some(creator_wallet) = self.creator_wallet {
+            policy.creator_wallet = creator_wallet;
+        }
+        if let Some(authority) = self.authority {
+            policy.authority = authority;
+        }
+        if let Some(mode) = self.allocation_mode {
+            policy.allocation_mode = mode;
+        }
+        if let Some(mode) = self.rounding_mode {
+            policy.rounding_mode = mode;
+        }
+        if let Some(bps) = self.max_investor_payout_bps {
+            policy.max_investor_payout_bps = bps;
+        }
+        if let Some(cap) = self.max_investor_payout_lamports {
+            policy.max_investor_payout_lamports = cap;
+        }
+        if let Some(mode) = self.cap_excess_mode {
+            policy.cap_excess_mode = mode;
+        }
+        if let Some(mode) = self.locked_mode {
+            policy.locked_mode = mode;
+        }
+        if let Some(mode) = self.y0_mode {
+            policy.y0_mode = mode;
+        }
+        if let Some(schedule) = self.fee_share_schedule {
+            policy.fee_share_schedule = schedule;
+        }
+        if let Some(delay) = self.policy_change_delay_seconds {
+            policy.policy_change_delay_seconds = delay;
+        }
+        if let Some(end_ts) = self.end_ts {
+            policy.end_ts = end_ts;
+        }
+    }
+}
+
+/// A queued policy change waiting out `Policy.policy_change_delay_seconds`
+///
+/// Created by `queue_policy_change` and closed by `cancel_policy_change` or
+/// `apply_policy_change`, refunding the rent to whoever queued it. Its fields
+/// stay in `Policy.pending_fields` until then, so no other change to them
+/// can be queued.
+#[account]
+#[derive(Debug)]
+pub struct PendingPolicyChange {
+    /// Sequence number, also the PDA seed
+    pub id: u64,
+
+    /// The change itself
+    pub update: PolicyUpdate,
+
+    /// `update.field_mask()`
+    pub fields: u32,
+
+    /// Authority that queued the change; gets the rent back
+    pub queued_by: Pubkey,
+
+    /// When the change was queued
+    pub queued_at: i64,
+
+    /// Earliest time `apply_policy_change` may run
+    pub eta: i64,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl PendingPolicyChange {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // id
+        PolicyUpdate::MAX_LEN + // update
+        4 + // fields
+        32 + // queued_by
+        8 + // queued_at
+        8 + // eta
+        1; // bump
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_mask_covers_every_field() {
+        assert_eq!(PolicyUpdate::default().field_mask(), 0);
+
+        let share = PolicyUpdate { investor_fee_share_bps: Some(4_000), ..Default::default() };
+        let schedule = PolicyUpdate { fee_share_schedule: Some(Vec::new()), ..Default::default() };
+        assert_eq!(share.field_mask(), 1 << 1);
+        assert_eq!(share.field_mask() & schedule.field_mask(), 0);
+
+        let full = PolicyUpdate {
+            y0: Some(1),
+            investor_fee_share_bps: Some(1),
+            daily_cap_lamports: Some(1),
+            min_payout_lamports: Some(1),
+            creator_wallet: Some(Pubkey::default()),
+            authority: Some(Pubkey::default()),
+            allocation_mode: Some(AllocationMode::Legacy),
+            rounding_mode: Some(RoundingMode::Floor),
+            max_investor_payout_bps: Some(1),
+            max_investor_payout_lamports: Some(1),
+            cap_excess_mode: Some(CapExcessMode::Creator),
+            locked_mode: Some(LockedMode::Instant),
+            y0_mode: Some(Y0Mode::Fixed),
+            fee_share_schedule: Some(vec![FeeShareStep::default(); MAX_FEE_SHARE_STEPS]),
+            policy_change_delay_seconds: Some(1),
+            end_ts: Some(1),
+        };
+        assert_eq!(full.field_mask(), (1 << 16) - 1);
+        assert_eq!(full.try_to_vec().unwrap().len(), PolicyUpdate::MAX_LEN);
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range_values() {
+        assert!(PolicyUpdate::default().validate().is_ok());
+        for update in [
+            PolicyUpdate { y0: Some(0), ..Default::default() },
+            PolicyUpdate { investor_fee_share_bps: Some(10_001), ..Default::default() },
+            PolicyUpdate { max_investor_payout_bps: Some(10_001), ..Default::default() },
+            PolicyUpdate { policy_change_delay_seconds: Some(-1), ..Default::default() },
+            PolicyUpdate { policy_change_delay_seconds: Some(3_600), ..Default::default() },
+            PolicyUpdate { end_ts: Some(-1), ..Default::default() },
+        ] {
+            assert!(update.validate().is_err());
+        }
+        for delay in [0, MIN_POLICY_CHANGE_DELAY_SECONDS] {
+            let update =
+                PolicyUpdate { policy_change_delay_seconds: Some(delay), ..Default::default() };
+            assert!(update.validate().is_ok());
+        }
+    }
+}
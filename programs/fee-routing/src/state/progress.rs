@@ -1,6 +1,117 @@
 use anchor_lang::prelude::*;
+use crate::{
+    constants::BPS_DENOMINATOR,
+    errors::FeeRoutingError,
+    math::{DayTerms, PageDistribution},
+};
+
+/// Quote the treasury holds for distribution, split by what it is owed to
+///
+/// Between instructions the buckets add up to everything the program owes
+/// out of the quote treasury. Pages only move lamports from one bucket to
+/// another or out to a recipient, never create or drop them.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Buckets {
+    /// Investor share of the day's pool not yet allocated to a page
+    pub investor_budget: u64,
+
+    /// Creator share of the day's pool, paid out when the day closes
+    pub creator_budget: u64,
+
+    /// Allocation held back by the daily cap, rolled into the next day
+    pub cap_carry: u64,
+
+    /// Below-minimum payouts owed to specific investors (the sum of every
+    /// `InvestorLedger.owed`); never rolls into the pool
+    pub owed: u64,
+
+    /// Floor-division remainders, rolled into the next day
+    pub dust: u64,
+}
+
+impl Buckets {
+    pub const LEN: usize = 8 + // investor_budget
+        8 + // creator_budget
+        8 + // cap_carry
+        8 + // owed
+        8; // dust
+
+    /// Everything the ledger says the quote treasury must hold
+    pub fn total(&self) -> Result<u64> {
+        Ok(self.carry_over()?
+            .checked_add(self.investor_budget)
+            .and_then(|t| t.checked_add(self.creator_budget))
+            .and_then(|t| t.checked_add(self.owed))
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?)
+    }
+
+    /// What rolls into the next day's pool
+    pub fn carry_over(&self) -> Result<u64> {
+        Ok(self.cap_carry
+            .checked_add(self.dust)
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?)
+    }
+
+    /// Page 0: pool the claimed quote with every bucket except `owed`
+    /// (including the budgets of a day that was never closed) and split it
+    /// into the investor and creator budgets. Returns the day's pool.
+    pub fn open_day(&mut self, claimed_quote: u64, investor_fee_share_bps: u16) -> Result<u64> {
+        let pool = self.total()?
+            .checked_sub(self.owed)
+            .and_then(|t| t.checked_add(claimed_quote))
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        let investor_budget = (pool as u128 * investor_fee_share_bps as u128
+            / BPS_DENOMINATOR as u128) as u64;
+
+        *self = Buckets {
+            investor_budget,
+            creator_budget: pool - investor_budget,
+            owed: self.owed,
+            ..Default::default()
+        };
+        Ok(pool)
+    }
+
+    /// Move a page's allocation out of the investor budget: paid amounts
+    /// leave the treasury, payouts over the per-investor caps go to the
+    /// creator budget and the rest to the carry and owed buckets. Owed
+    /// balances paid on the page leave the owed bucket.
+    pub fn apply_page(&mut self, page: &PageDistribution) -> Result<()> {
+        self.investor_budget = self.investor_budget
+            .checked_sub(page.investor_allocation)
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        self.creator_budget = self.creator_budget
+            .checked_add(page.payout_cap_excess)
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        self.cap_carry = self.cap_carry
+            .checked_add(page.cap_carry_over)
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        self.owed = self.owed
+            .checked_add(page.below_minimum_dust)
+            .and_then(|t| t.checked_sub(page.owed_released))
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        self.dust = self.dust
+            .checked_add(page.rounding_dust)
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Final page, once every registered stream was read: the creator gets
+    /// its budget plus any investor budget no page allocated, which is the
+    /// day's allocation left by streams that vested since their last read
+    /// and the pages' floor remainders. Returns the creator payout.
+    pub fn close_day(&mut self) -> Result<u64> {
+        let creator_amount = self.creator_budget
+            .checked_add(self.investor_budget)
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        self.creator_budget = 0;
+        self.investor_budget = 0;
+        Ok(creator_amount)
+    }
+}
 
 #[account]
+#[derive(Debug)]
 pub struct Progress {
     /// Timestamp of last distribution start
     pub last_distribution_ts: i64,
@@ -11,9 +122,6 @@ pub struct Progress {
     /// Total distributed to investors in current day
     pub daily_distributed_to_investors: u64,
 
-    /// Carry-over dust from previous pages/days
-    pub carry_over_lamports: u64,
-
     /// Current page cursor (0-indexed)
     pub current_page: u16,
 
@@ -32,6 +140,53 @@ pub struct Progress {
     /// Total rounding dust accumulated over lifetime (for transparency)
     pub total_rounding_dust: u64,
 
+    /// Quote fees claimed on page 0 of the current day
+    pub day_claimed_quote: u64,
+
+    /// Buckets rolled into the current day's pool on page 0
+    pub day_carry_over_in: u64,
+
+    /// Sum of locked amounts read across the current day's pages
+    pub day_total_locked: u64,
+
+    /// Rounding dust produced across the current day's pages
+    pub day_rounding_dust: u64,
+
+    /// Ledger of the quote the treasury holds for distribution
+    pub buckets: Buckets,
+
+    /// Owed balances paid out across the current day's pages (included in
+    /// `daily_distributed_to_investors`, not counted against the daily cap)
+    pub day_owed_released: u64,
+
+    /// Sum of `InvestorLedger.deposited` over every ledger, the Y0 of
+    /// `Y0Mode::StreamDeposits`
+    pub registered_deposits: u64,
+
+    /// Number of `InvestorLedger` accounts
+    pub registered_streams: u64,
+
+    /// Distinct streams read across the current day's pages
+    pub day_streams_read: u64,
+
+    /// When a closed day had read every registered stream and found nothing
+    /// locked (0 = not yet); distribution has sunset from then on
+    pub fully_vested_at: i64,
+
+    /// Sum of `InvestorLedger.locked` over every ledger: what the registered
+    /// streams had locked when last read
+    pub registered_locked: u64,
+
+    /// `registered_locked` when the current day opened, the basis every
+    /// page's allocation is measured against
+    pub day_locked_basis: u64,
+
+    /// Eligible investor share fixed for the current day on page 0
+    pub day_eligible_share_bps: u16,
+
+    /// Investor allocation of the current day across all pages
+    pub day_investor_allocation: u64,
+
     /// Bump seed for PDA derivation
     pub bump: u8,
 }
@@ -41,12 +196,154 @@ impl Progress {
         8 + // last_distribution_ts
         8 + // current_day
         8 + // daily_distributed_to_investors
-        8 + // carry_over_lamports
         2 + // current_page
         2 + // pages_processed_today
         2 + // total_investors
         1 + // creator_payout_sent
         1 + // has_base_fees
         8 + // total_rounding_dust
+        8 + // day_claimed_quote
+        8 + // day_carry_over_in
+        8 + // day_total_locked
+        8 + // day_rounding_dust
+        Buckets::LEN + // buckets
+        8 + // day_owed_released
+        8 + // registered_deposits
+        8 + // registered_streams
+        8 + // day_streams_read
+        8 + // fully_vested_at
+        8 + // registered_locked
+        8 + // day_locked_basis
+        2 + // day_eligible_share_bps
+        8 + // day_investor_allocation
         1; // bump
+
+    /// Whether a distribution day is half-way through its pages
+    ///
+    /// Anything its remaining pages read (the policy, Y0, the position) must
+    /// stay as page 0 saw it until the final page closes the day.
+    pub fn day_in_progress(&self) -> bool {
+        self.current_page != 0 && !self.creator_payout_sent
+    }
+
+    /// Replace a ledger's recorded deposit with the stream's current one
+    pub fn register_deposit(&mut self, previous: u64, current: u64) -> Result<()> {
+        self.registered_deposits = self.registered_deposits
+            .checked_sub(previous)
+            .and_then(|t| t.checked_add(current))
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Replace a ledger's recorded locked amount with the one just read
+    pub fn register_locked(&mut self, previous: u64, current: u64) -> Result<()> {
+        self.registered_locked = self.registered_locked
+            .checked_sub(previous)
+            .and_then(|t| t.checked_add(current))
+            .ok_or(FeeRoutingError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Pool, basis and allocation every page of the current day shares
+    pub fn day_terms(&self) -> Result<DayTerms> {
+        Ok(DayTerms {
+            day_pool: self.day_claimed_quote
+                .checked_add(self.day_carry_over_in)
+                .ok_or(FeeRoutingError::ArithmeticOverflow)?,
+            locked_basis: self.day_locked_basis,
+            eligible_share_bps: self.day_eligible_share_bps as u64,
+            investor_allocation: self.day_investor_allocation,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{math::DistributionMath, state::Policy};
+
+    fn policy() -> Policy {
+        Policy {
+            y0: 10_000,
+            investor_fee_share_bps: 5_000,
+            daily_cap_lamports: 0,
+            min_payout_lamports: 100,
+            quote_mint: Pubkey::default(),
+            creator_wallet: Pubkey::default(),
+            authority: Pubkey::default(),
+            allocation_mode: Default::default(),
+            rounding_mode: Default::default(),
+            max_investor_payout_bps: 0,
+            max_investor_payout_lamports: 0,
+            cap_excess_mode: Default::default(),
+            locked_mode: Default::default(),
+            y0_mode: Default::default(),
+            fee_share_schedule: Vec::new(),
+            policy_change_delay_seconds: 0,
+            pending_fields: 0,
+            next_policy_change_id: 0,
+            end_ts: 0,
+            bump: 0,
+        }
+    }
+
+    fn page(buckets: &mut Buckets, day: &DayTerms, locked: &[u64]) -> u64 {
+        let page = DistributionMath::distribute_page(
+            &policy(),
+            day,
+            buckets.investor_budget,
+            locked,
+            &[],
+            &[],
+            0,
+        ).unwrap();
+        buckets.apply_page(&page).unwrap();
+        page.page_total_distributed
+    }
+
+    #[test]
+    fn test_multi_page_day_conserves_every_lamport() {
+        // Owed balances stay with their investors instead of joining the pool
+        let open = || {
+            let mut buckets = Buckets { dust: 7, cap_carry: 3, owed: 40, ..Default::default() };
+            let pool = buckets.open_day(9_990, 5_000).unwrap();
+            (buckets, pool)
+        };
+        let (mut buckets, pool) = open();
+        assert_eq!(pool, 10_000);
+        assert_eq!(buckets, Buckets { investor_budget: 5_000, creator_budget: 5_000, owed: 40, ..Default::default() });
+
+        // 30% + 40% locked across two pages, capped at the 50% budget: each
+        // page gets its locked share of the day's allocation
+        let day = DistributionMath::day_terms(&policy(), pool, 7_000).unwrap();
+        let mut paid = page(&mut buckets, &day, &[2_000, 990, 10]);
+        assert_eq!(buckets.investor_budget, 2_858);
+        paid += page(&mut buckets, &day, &[4_000]);
+        assert_eq!(buckets.investor_budget, 1);
+
+        let creator = buckets.close_day().unwrap();
+        assert_eq!(creator, 5_001);
+        assert_eq!(paid + creator + buckets.total().unwrap(), pool + 40);
+        // The 7-lamport payout fell below the minimum
+        assert_eq!(buckets, Buckets { owed: 47, dust: 1, ..Default::default() });
+
+        // The same pages the other way round pay out the same
+        let (mut reversed, _) = open();
+        let reversed_paid = page(&mut reversed, &day, &[4_000]) + page(&mut reversed, &day, &[2_000, 990, 10]);
+        assert_eq!((reversed_paid, reversed.close_day().unwrap()), (paid, creator));
+        assert_eq!(reversed, buckets);
+    }
+
+    #[test]
+    fn test_unclosed_day_rolls_into_next_pool() {
+        let mut buckets = Buckets::default();
+        let pool = buckets.open_day(1_000, 5_000).unwrap();
+        let day = DistributionMath::day_terms(&policy(), pool, 1_000).unwrap();
+        page(&mut buckets, &day, &[1_000]);
+        assert_eq!(buckets.total().unwrap(), 900);
+
+        // Final page never ran: both budgets join tomorrow's pool
+        assert_eq!(buckets.open_day(100, 5_000).unwrap(), 1_000);
+        assert_eq!(buckets.investor_budget, 500);
+    }
 }
@@ -0,0 +1,88 @@
+/**
+ * Dump a Meteora CP-AMM Pool and Position as layout fixtures
+ *
+ * Writes, under crates/fee-routing-testkit/fixtures/cp-amm/:
+ * - <name>.pool.bin / <name>.position.bin: the raw account data
+ * - <name>.expected: the fields Meteora's own SDK decodes from them, which
+ *   tests/cp_amm_dumps.rs checks the program's zero-copy readers against
+ *
+ * Pick a quote-only pool (collect fee mode OnlyB) and one of its positions
+ * with liquidity, so every asserted field is non-zero.
+ *
+ * Run: ts-node scripts/dump-cp-amm-fixtures.ts <rpc-url> <name> <pool> <position>
+ *   e.g. ts-node scripts/dump-cp-amm-fixtures.ts https://api.mainnet-beta.solana.com \
+ *        mainnet-usdc <pool> <position>
+ */
+
+import { Connection, PublicKey } from "@solana/web3.js";
+import { CpAmm } from "@meteora-ag/cp-amm-sdk";
+import * as fs from "fs";
+import * as path from "path";
+
+const CP_AMM_PROGRAM_ID = new PublicKey("cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG");
+
+const FIXTURES_DIR = path.join(
+  __dirname,
+  "..",
+  "crates",
+  "fee-routing-testkit",
+  "fixtures",
+  "cp-amm"
+);
+
+async function fetchData(connection: Connection, address: PublicKey): Promise<Buffer> {
+  const account = await connection.getAccountInfo(address);
+  if (!account) {
+    throw new Error(`${address.toBase58()} not found`);
+  }
+  if (!account.owner.equals(CP_AMM_PROGRAM_ID)) {
+    throw new Error(`${address.toBase58()} is owned by ${account.owner.toBase58()}, not CP-AMM`);
+  }
+  return account.data;
+}
+
+async function main() {
+  const [rpcUrl, name, poolArg, positionArg] = process.argv.slice(2);
+  if (!rpcUrl || !name || !poolArg || !positionArg) {
+    console.error("Usage: ts-node scripts/dump-cp-amm-fixtures.ts <rpc-url> <name> <pool> <position>");
+    process.exit(1);
+  }
+  const poolAddress = new PublicKey(poolArg);
+  const positionAddress = new PublicKey(positionArg);
+
+  const connection = new Connection(rpcUrl, "confirmed");
+  const cpAmm = new CpAmm(connection);
+
+  const poolData = await fetchData(connection, poolAddress);
+  const positionData = await fetchData(connection, positionAddress);
+  const pool = await cpAmm.fetchPoolState(poolAddress);
+  const position = await cpAmm.fetchPositionState(positionAddress);
+  if (!position.pool.equals(poolAddress)) {
+    throw new Error(`Position ${positionArg} belongs to pool ${position.pool.toBase58()}`);
+  }
+
+  const expected = [
+    `cluster = ${rpcUrl}`,
+    `pool = ${poolAddress.toBase58()}`,
+    `token_a_mint = ${pool.tokenAMint.toBase58()}`,
+    `token_b_mint = ${pool.tokenBMint.toBase58()}`,
+    `collect_fee_mode = ${pool.collectFeeMode}`,
+    `liquidity = ${pool.liquidity.toString()}`,
+    `position = ${positionAddress.toBase58()}`,
+    `nft_mint = ${position.nftMint.toBase58()}`,
+    `unlocked_liquidity = ${position.unlockedLiquidity.toString()}`,
+  ];
+
+  fs.mkdirSync(FIXTURES_DIR, { recursive: true });
+  fs.writeFileSync(path.join(FIXTURES_DIR, `${name}.pool.bin`), poolData);
+  fs.writeFileSync(path.join(FIXTURES_DIR, `${name}.position.bin`), positionData);
+  fs.writeFileSync(path.join(FIXTURES_DIR, `${name}.expected`), expected.join("\n") + "\n");
+
+  console.log(`Wrote ${name} fixtures to ${FIXTURES_DIR}`);
+  console.log(expected.join("\n"));
+}
+
+main().catch((err) => {
+  console.error(err);
+  process.exit(1);
+});